@@ -169,7 +169,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
 
         println!("\n  Stream #{}", stream.index());
         println!("    Type: {:?}", media_type);
-        println!("    Codec: {:?}", codec_params.id());
+        println!("    Codec: {} ({})", codec_params.id().name(), codec_params.id().long_name());
         println!("    Time base: {}/{}", stream.time_base().numerator(), stream.time_base().denominator());
 
         let fps = stream.avg_frame_rate();
@@ -28,5 +28,69 @@ bitflags! {
         const INTRA_ONLY          = AV_CODEC_CAP_INTRA_ONLY;
         #[cfg(not(feature = "ffmpeg_6_0"))]
         const LOSSLESS            = AV_CODEC_CAP_LOSSLESS;
+        #[cfg(feature = "ffmpeg_4_0")]
+        const HARDWARE            = AV_CODEC_CAP_HARDWARE;
+        #[cfg(feature = "ffmpeg_4_0")]
+        const HYBRID              = AV_CODEC_CAP_HYBRID;
+    }
+}
+
+impl Capabilities {
+    /// Audio encoder accepts frames of any size, so no FIFO is needed to cut input into
+    /// `frame_size` chunks.
+    pub fn variable_frame_size(&self) -> bool {
+        self.contains(Capabilities::VARIABLE_FRAME_SIZE)
+    }
+
+    /// Codec has delay: it must be flushed with an empty packet/frame at end of stream.
+    pub fn delay(&self) -> bool {
+        self.contains(Capabilities::DELAY)
+    }
+
+    /// Codec is backed by a hardware implementation.
+    pub fn hardware(&self) -> bool {
+        #[cfg(feature = "ffmpeg_4_0")]
+        {
+            self.contains(Capabilities::HARDWARE)
+        }
+        #[cfg(not(feature = "ffmpeg_4_0"))]
+        {
+            false
+        }
+    }
+
+    /// Codec only produces intra frames.
+    ///
+    /// Since FFmpeg 6.0 this is no longer a capability and always returns `false`; use
+    /// [`Codec::is_intra_only()`](super::Codec::is_intra_only) instead.
+    pub fn intra_only(&self) -> bool {
+        #[cfg(not(feature = "ffmpeg_6_0"))]
+        {
+            self.contains(Capabilities::INTRA_ONLY)
+        }
+        #[cfg(feature = "ffmpeg_6_0")]
+        {
+            false
+        }
+    }
+
+    /// Codec supports lossless coding.
+    ///
+    /// Since FFmpeg 6.0 this is no longer a capability and always returns `false`; use
+    /// [`Codec::is_lossless()`](super::Codec::is_lossless) instead.
+    pub fn lossless(&self) -> bool {
+        #[cfg(not(feature = "ffmpeg_6_0"))]
+        {
+            self.contains(Capabilities::LOSSLESS)
+        }
+        #[cfg(feature = "ffmpeg_6_0")]
+        {
+            false
+        }
+    }
+
+    /// Codec is experimental; opening it requires `strict` set to experimental compliance.
+    pub fn experimental(&self) -> bool {
+        self.contains(Capabilities::EXPERIMENTAL)
     }
 }
@@ -1,7 +1,8 @@
-use std::{ffi::CStr, str::from_utf8_unchecked};
+use std::{ffi::CStr, ptr, str::from_utf8_unchecked};
 
-use super::{Audio, Capabilities, Id, Profile, Video};
-use crate::{Error, ffi::*, media};
+use super::{Audio, Capabilities, Id, Profile, Props, Video, video};
+use crate::{Error, Rational, ffi::*, media};
+use libc::c_void;
 
 #[derive(PartialEq, Eq, Copy, Clone)]
 pub struct Codec {
@@ -73,6 +74,32 @@ impl Codec {
         unsafe { Capabilities::from_bits_truncate((*self.as_ptr()).capabilities as u32) }
     }
 
+    /// Whether the codec only uses intra frames.
+    ///
+    /// Checks the codec capabilities on older FFmpeg and the codec descriptor properties,
+    /// where this information lives since FFmpeg 6.0.
+    pub fn is_intra_only(&self) -> bool {
+        self.capabilities().intra_only() || self.id().descriptor().is_some_and(|d| d.props().contains(Props::INTRA_ONLY))
+    }
+
+    /// Whether the codec supports lossless coding.
+    ///
+    /// Checks the codec capabilities on older FFmpeg and the codec descriptor properties,
+    /// where this information lives since FFmpeg 6.0.
+    pub fn is_lossless(&self) -> bool {
+        self.capabilities().lossless() || self.id().descriptor().is_some_and(|d| d.props().contains(Props::LOSSLESS))
+    }
+
+    /// Frame rates the codec is restricted to, e.g. the MPEG-1/2 rates
+    /// (`24000/1001`, `25/1`, `30000/1001`, ...).
+    ///
+    /// Empty when any rate is allowed, which is the case for most codecs, and for
+    /// audio and subtitle codecs. Limits of hardware encoders are not described
+    /// here; they only show when opening the encoder fails.
+    pub fn supported_framerates(&self) -> Vec<Rational> {
+        unsafe { if (*self.as_ptr()).supported_framerates.is_null() { Vec::new() } else { video::RateIter::new((*self.as_ptr()).supported_framerates).collect() } }
+    }
+
     pub fn profiles(&self) -> Option<ProfileIter> {
         unsafe { if (*self.as_ptr()).profiles.is_null() { None } else { Some(ProfileIter::new(self.id(), (*self.as_ptr()).profiles)) } }
     }
@@ -105,3 +132,33 @@ impl Iterator for ProfileIter {
         }
     }
 }
+
+/// Iterator over the encoders and decoders compiled into libavcodec
+/// (`av_codec_iterate`), see [`codecs()`](super::codecs).
+pub struct CodecIter {
+    opaque: *mut c_void,
+}
+
+impl CodecIter {
+    pub fn new() -> Self {
+        CodecIter { opaque: ptr::null_mut() }
+    }
+}
+
+impl Default for CodecIter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Iterator for CodecIter {
+    type Item = Codec;
+
+    fn next(&mut self) -> Option<<Self as Iterator>::Item> {
+        unsafe {
+            let ptr = av_codec_iterate(&mut self.opaque);
+
+            if ptr.is_null() { None } else { Some(Codec::wrap(ptr)) }
+        }
+    }
+}
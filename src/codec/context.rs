@@ -1,19 +1,25 @@
 use std::{any::Any, ptr, rc::Rc};
 
-use super::{Compliance, Debug, Flags, Id, Parameters, decoder::Decoder, encoder::Encoder, threading};
-use crate::{Codec, Error, Rational, ffi::*, media};
-use libc::c_int;
+use super::{
+    Compliance, Debug, Flags, Id, Parameters,
+    decoder::{AnyDecoder, Decoder},
+    encoder::{Encoder, monotonic::Guard},
+    threading,
+};
+use crate::{Codec, Error, Rational, ffi::*, media, option};
+use libc::{c_int, c_void};
 
 pub struct Context {
     ptr: *mut AVCodecContext,
     owner: Option<Rc<dyn Any>>,
+    monotonic: Guard,
 }
 
 unsafe impl Send for Context {}
 
 impl Context {
     pub unsafe fn wrap(ptr: *mut AVCodecContext, owner: Option<Rc<dyn Any>>) -> Self {
-        Context { ptr, owner }
+        Context { ptr, owner, monotonic: Guard::default() }
     }
 
     pub unsafe fn as_ptr(&self) -> *const AVCodecContext {
@@ -27,11 +33,11 @@ impl Context {
 
 impl Context {
     pub fn new() -> Self {
-        unsafe { Context { ptr: avcodec_alloc_context3(ptr::null()), owner: None } }
+        unsafe { Context { ptr: avcodec_alloc_context3(ptr::null()), owner: None, monotonic: Guard::default() } }
     }
 
     pub fn new_with_codec(codec: Codec) -> Self {
-        unsafe { Context { ptr: avcodec_alloc_context3(codec.as_ptr()), owner: None } }
+        unsafe { Context { ptr: avcodec_alloc_context3(codec.as_ptr()), owner: None, monotonic: Guard::default() } }
     }
 
     pub fn from_parameters<P: Into<Parameters>>(parameters: P) -> Result<Self, Error> {
@@ -46,10 +52,108 @@ impl Context {
         }
     }
 
+    /// Creates a fresh, unopened context with the settings of this one, e.g. to
+    /// derive several encoders from one template and change only their bit rate.
+    ///
+    /// Copied are the codec, every field exposed as an AVOption (bit rate, GOP
+    /// size, B-frames, quantizer limits, flags, threading, ...), the private
+    /// options of the codec (`preset`, `crf`, ...), the stream parameters
+    /// (dimensions, formats, channel layout, color properties, extradata), the
+    /// time base and the frame rate. Not copied are the internal state of an
+    /// opened codec, hardware device and frame contexts and callbacks; the clone
+    /// has to be opened on its own, with the usual options dictionary if any.
+    ///
+    /// Fails with the error of the first copy that fails; the partially built
+    /// context is freed.
+    pub fn clone_settings(&self) -> Result<Context, Error> {
+        unsafe {
+            let source = self.as_ptr();
+            let ptr = avcodec_alloc_context3((*source).codec);
+
+            if ptr.is_null() {
+                panic!("out of memory");
+            }
+
+            // Dropped, and so freed, on any error below.
+            let mut context = Context { ptr, owner: None, monotonic: Guard::default() };
+            let destination = context.as_mut_ptr();
+
+            let res = av_opt_copy(destination as *mut _, source as *const _);
+
+            if res < 0 {
+                return Err(Error::from(res));
+            }
+
+            if !(*source).priv_data.is_null() && !(*destination).priv_data.is_null() {
+                let res = av_opt_copy((*destination).priv_data, (*source).priv_data);
+
+                if res < 0 {
+                    return Err(Error::from(res));
+                }
+            }
+
+            let mut parameters = Parameters::new();
+            let res = avcodec_parameters_from_context(parameters.as_mut_ptr(), source);
+
+            if res < 0 {
+                return Err(Error::from(res));
+            }
+
+            let res = avcodec_parameters_to_context(destination, parameters.as_ptr());
+
+            if res < 0 {
+                return Err(Error::from(res));
+            }
+
+            (*destination).time_base = (*source).time_base;
+            (*destination).framerate = (*source).framerate;
+
+            Ok(context)
+        }
+    }
+
+    /// Restores every option of the context, and the private options of its
+    /// codec, to their default values (`av_opt_set_defaults`), e.g. to reuse one
+    /// context for successive jobs without settings of a job leaking into the next.
+    ///
+    /// Only options are reset: fields that are not exposed as AVOptions (time
+    /// base, extradata, hardware contexts, ...) are kept, so allocating a new
+    /// context remains the safest way to start over. Fails with `EINVAL` on an
+    /// opened codec, whose state depends on its options.
+    pub fn reset_defaults(&mut self) -> Result<(), Error> {
+        unsafe {
+            if avcodec_is_open(self.as_mut_ptr()) != 0 {
+                return Err(Error::Other { errno: libc::EINVAL });
+            }
+
+            av_opt_set_defaults(self.as_mut_ptr() as *mut c_void);
+
+            if !(*self.as_ptr()).priv_data.is_null() {
+                av_opt_set_defaults((*self.as_mut_ptr()).priv_data);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn monotonic(&self) -> &Guard {
+        &self.monotonic
+    }
+
+    pub(crate) fn monotonic_mut(&mut self) -> &mut Guard {
+        &mut self.monotonic
+    }
+
     pub fn decoder(self) -> Decoder {
         Decoder(self)
     }
 
+    /// Opens a video, audio or subtitle decoder depending on the media type of the
+    /// context, sparing the match on [`medium()`](Self::medium).
+    pub fn open_decoder(self) -> Result<AnyDecoder, Error> {
+        AnyDecoder::open(self.decoder())
+    }
+
     pub fn encoder(self) -> Encoder {
         Encoder(self)
     }
@@ -143,6 +247,30 @@ impl Context {
     }
 }
 
+unsafe impl option::Target for Context {
+    fn as_ptr(&self) -> *const c_void {
+        self.ptr as *const _
+    }
+
+    fn as_mut_ptr(&mut self) -> *mut c_void {
+        self.ptr as *mut _
+    }
+}
+
+impl option::Settable for Context {}
+
+impl AsRef<Context> for Context {
+    fn as_ref(&self) -> &Context {
+        self
+    }
+}
+
+impl AsMut<Context> for Context {
+    fn as_mut(&mut self) -> &mut Context {
+        self
+    }
+}
+
 impl Default for Context {
     fn default() -> Self {
         Self::new()
@@ -175,3 +303,20 @@ impl Clone for Context {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reset_defaults() {
+        let mut context = Context::new();
+
+        unsafe {
+            (*context.as_mut_ptr()).bit_rate = 5_000_000;
+        }
+
+        context.reset_defaults().unwrap();
+        assert_eq!(unsafe { (*context.as_ptr()).bit_rate }, 200_000);
+    }
+}
@@ -0,0 +1,74 @@
+use std::ops::{Deref, DerefMut};
+
+use super::{Audio, Decoder, Opened, Subtitle, Video};
+use crate::{Error, media};
+
+/// An opened decoder of whatever type the stream calls for.
+///
+/// Returned by [`Context::open_decoder()`](crate::codec::Context::open_decoder) for
+/// code that handles all streams alike, e.g. an indexer. It dereferences to
+/// [`Opened`], so `send_packet()`, `send_eof()`, `receive_frame()` and `flush()`
+/// are available without matching; subtitles are not decoded through frames,
+/// match on [`AnyDecoder::Subtitle`] to use [`Subtitle::decode()`].
+pub enum AnyDecoder {
+    Video(Video),
+    Audio(Audio),
+    Subtitle(Subtitle),
+}
+
+impl AnyDecoder {
+    /// Opens `decoder` with the decoder matching its codec id and media type.
+    ///
+    /// Fails with [`Error::DecoderNotFound`] for streams that cannot be decoded,
+    /// such as data or attachment streams.
+    pub fn open(decoder: Decoder) -> Result<Self, Error> {
+        match decoder.medium() {
+            media::Type::Video => decoder.video().map(AnyDecoder::Video),
+            media::Type::Audio => decoder.audio().map(AnyDecoder::Audio),
+            media::Type::Subtitle => decoder.subtitle().map(AnyDecoder::Subtitle),
+            _ => Err(Error::DecoderNotFound),
+        }
+    }
+
+    pub fn medium(&self) -> media::Type {
+        match self {
+            AnyDecoder::Video(_) => media::Type::Video,
+            AnyDecoder::Audio(_) => media::Type::Audio,
+            AnyDecoder::Subtitle(_) => media::Type::Subtitle,
+        }
+    }
+
+    pub fn video(self) -> Option<Video> {
+        if let AnyDecoder::Video(video) = self { Some(video) } else { None }
+    }
+
+    pub fn audio(self) -> Option<Audio> {
+        if let AnyDecoder::Audio(audio) = self { Some(audio) } else { None }
+    }
+
+    pub fn subtitle(self) -> Option<Subtitle> {
+        if let AnyDecoder::Subtitle(subtitle) = self { Some(subtitle) } else { None }
+    }
+}
+
+impl Deref for AnyDecoder {
+    type Target = Opened;
+
+    fn deref(&self) -> &<Self as Deref>::Target {
+        match self {
+            AnyDecoder::Video(video) => video,
+            AnyDecoder::Audio(audio) => audio,
+            AnyDecoder::Subtitle(subtitle) => subtitle,
+        }
+    }
+}
+
+impl DerefMut for AnyDecoder {
+    fn deref_mut(&mut self) -> &mut <Self as Deref>::Target {
+        match self {
+            AnyDecoder::Video(video) => video,
+            AnyDecoder::Audio(audio) => audio,
+            AnyDecoder::Subtitle(subtitle) => subtitle,
+        }
+    }
+}
@@ -1,16 +1,12 @@
 use std::ops::{Deref, DerefMut};
 
-#[cfg(not(feature = "ffmpeg_5_0"))]
 use crate::ffi::*;
-#[cfg(not(feature = "ffmpeg_5_0"))]
 use libc::c_int;
 
-use super::Opened;
-#[cfg(not(feature = "ffmpeg_5_0"))]
-use crate::frame;
-use crate::{AudioService, ChannelLayout, codec::Context, util::format};
+use super::{Frames, Opened};
 #[cfg(not(feature = "ffmpeg_5_0"))]
-use {crate::Error, crate::packet};
+use crate::Error;
+use crate::{AudioService, ChannelLayout, codec::Context, frame, packet, util::format};
 
 pub struct Audio(pub Opened);
 
@@ -32,6 +28,16 @@ impl Audio {
         }
     }
 
+    /// Decodes `packets` and iterates over the resulting frames, draining the
+    /// decoder at the end. See [`Video::decode_iter()`](super::Video::decode_iter).
+    pub fn decode_iter<I>(&mut self, packets: I) -> Frames<'_, I::IntoIter, frame::Audio>
+    where
+        I: IntoIterator,
+        I::Item: packet::Ref,
+    {
+        Frames::new(&mut self.0, packets.into_iter(), frame::Audio::empty)
+    }
+
     pub fn rate(&self) -> u32 {
         unsafe { (*self.as_ptr()).sample_rate as u32 }
     }
@@ -48,10 +54,16 @@ impl Audio {
         }
     }
 
+    /// Sample format produced by the decoder.
+    ///
+    /// Reliable once the first frame has been decoded; use it to check whether a
+    /// [requested format](super::Decoder::set_request_sample_format) was honored.
     pub fn format(&self) -> format::Sample {
         unsafe { format::Sample::from((*self.as_ptr()).sample_fmt) }
     }
 
+    /// Has no effect once the decoder is open; use
+    /// [`Decoder::set_request_sample_format()`](super::Decoder::set_request_sample_format) instead.
     pub fn request_format(&mut self, value: format::Sample) {
         unsafe {
             (*self.as_mut_ptr()).request_sample_fmt = value.into();
@@ -119,6 +131,18 @@ impl Audio {
         unsafe { (*self.as_ptr()).frame_size as u32 }
     }
 
+    /// Number of samples per channel in a packet of `packet_size` bytes, from the
+    /// codec framing (1024 for AAC, 1152 for MP3 layer 3, size based for PCM and
+    /// ADPCM). `None` when it cannot be derived without decoding.
+    pub fn frame_duration(&self, packet_size: usize) -> Option<u32> {
+        unsafe {
+            match av_get_audio_frame_duration(self.as_ptr() as *mut _, packet_size as c_int) {
+                0 => None,
+                n => Some(n as u32),
+            }
+        }
+    }
+
     #[cfg(not(feature = "ffmpeg_5_0"))]
     pub fn frame_start(&self) -> Option<usize> {
         unsafe {
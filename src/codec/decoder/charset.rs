@@ -0,0 +1,41 @@
+use crate::ffi::*;
+use libc::c_int;
+
+/// How a subtitle decoder applies the character encoding set with
+/// [`Decoder::set_subtitle_charset()`](super::Decoder::set_subtitle_charset).
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum CharsetMode {
+    /// Leave the text untouched, even if a charset is set.
+    DoNothing,
+    /// Let the decoder pick: text subtitles are converted to UTF-8 before decoding,
+    /// bitmap subtitles are left alone. This is FFmpeg's default.
+    Automatic,
+    /// Convert every packet to UTF-8 before handing it to the decoder.
+    PreDecoder,
+    /// Skip the UTF-8 validation of the decoded text, for decoders that already
+    /// produce valid output from non-UTF-8 input.
+    Ignore,
+}
+
+impl From<c_int> for CharsetMode {
+    fn from(value: c_int) -> Self {
+        match value {
+            FF_SUB_CHARENC_MODE_DO_NOTHING => CharsetMode::DoNothing,
+            FF_SUB_CHARENC_MODE_PRE_DECODER => CharsetMode::PreDecoder,
+            FF_SUB_CHARENC_MODE_IGNORE => CharsetMode::Ignore,
+
+            _ => CharsetMode::Automatic,
+        }
+    }
+}
+
+impl From<CharsetMode> for c_int {
+    fn from(value: CharsetMode) -> c_int {
+        match value {
+            CharsetMode::DoNothing => FF_SUB_CHARENC_MODE_DO_NOTHING,
+            CharsetMode::Automatic => FF_SUB_CHARENC_MODE_AUTOMATIC,
+            CharsetMode::PreDecoder => FF_SUB_CHARENC_MODE_PRE_DECODER,
+            CharsetMode::Ignore => FF_SUB_CHARENC_MODE_IGNORE,
+        }
+    }
+}
@@ -1,13 +1,15 @@
 use std::{
+    ffi::CString,
     ops::{Deref, DerefMut},
     ptr,
 };
 
-use super::{Audio, Check, Conceal, Opened, Subtitle, Video};
+use super::{Audio, CharsetMode, Check, Conceal, Opened, Subtitle, Video};
 use crate::{
     Dictionary, Discard, Error, Rational,
     codec::{Context, traits},
     ffi::*,
+    util::format,
 };
 
 /// A decoder for compressed media streams.
@@ -181,6 +183,10 @@ impl Decoder {
     ///
     /// Controls how strictly the decoder validates input data. Stricter checking catches
     /// more errors but may reject valid but non-compliant streams.
+    ///
+    /// Corruption is only reported (through [`Frame::is_corrupt()`](crate::Frame::is_corrupt)
+    /// and [`Frame::decode_errors()`](crate::Frame::decode_errors)) when it is detected,
+    /// so monitoring tools should enable at least `Check::CRC` and `Check::BISTREAM`.
     pub fn check(&mut self, value: Check) {
         unsafe {
             (*self.as_mut_ptr()).err_recognition = value.bits();
@@ -217,6 +223,45 @@ impl Decoder {
         }
     }
 
+    /// Asks audio decoders to output the given sample format.
+    ///
+    /// Must be called before the decoder is opened. Decoders that can produce several
+    /// formats (e.g. AC-3 or MP3 choosing between `fltp` and `s16p`) will use it and
+    /// spare a resampling pass; others ignore it. Check
+    /// [`decoder::Audio::format()`](super::Audio::format) after the first decoded frame
+    /// to see which format is actually produced.
+    pub fn set_request_sample_format(&mut self, value: format::Sample) {
+        unsafe {
+            (*self.as_mut_ptr()).request_sample_fmt = value.into();
+        }
+    }
+
+    /// Sets the character encoding of text subtitles (e.g. `"cp1251"` for Cyrillic SRT
+    /// files), so the decoded text comes out as UTF-8.
+    ///
+    /// Must be called before the decoder is opened. The conversion is done with iconv,
+    /// so any name it understands is accepted; an unknown one makes opening fail.
+    /// How the charset is applied is controlled by
+    /// [`set_subtitle_charset_mode()`](Self::set_subtitle_charset_mode), which
+    /// defaults to [`CharsetMode::Automatic`].
+    pub fn set_subtitle_charset(&mut self, charset: &str) {
+        unsafe {
+            let charset = CString::new(charset).unwrap();
+
+            av_freep(&mut (*self.as_mut_ptr()).sub_charenc as *mut *mut _ as *mut libc::c_void);
+            (*self.as_mut_ptr()).sub_charenc = av_strdup(charset.as_ptr());
+        }
+    }
+
+    /// Sets how the [subtitle charset](Self::set_subtitle_charset) is applied.
+    ///
+    /// Must be called before the decoder is opened.
+    pub fn set_subtitle_charset_mode(&mut self, value: CharsetMode) {
+        unsafe {
+            (*self.as_mut_ptr()).sub_charenc_mode = value.into();
+        }
+    }
+
     /// Gets the time base used for packet timestamps.
     ///
     /// This is the time unit for interpreting PTS/DTS values in input packets.
@@ -0,0 +1,74 @@
+use std::ops::DerefMut;
+
+use libc::EAGAIN;
+
+use super::Opened;
+use crate::{Error, Frame, packet};
+
+/// Iterator over the frames decoded from a sequence of packets.
+///
+/// Created by [`Video::decode_iter()`](super::Video::decode_iter) and
+/// [`Audio::decode_iter()`](super::Audio::decode_iter). Packets are pulled from the
+/// underlying iterator only when the decoder asks for more input; once it is
+/// exhausted the decoder is drained, so the frames it still buffers are yielded
+/// too. A decoding error is yielded as is and iteration can continue past it.
+pub struct Frames<'a, I, F> {
+    decoder: &'a mut Opened,
+    packets: I,
+    empty: fn() -> F,
+    draining: bool,
+    done: bool,
+}
+
+impl<'a, I, F> Frames<'a, I, F> {
+    pub(super) fn new(decoder: &'a mut Opened, packets: I, empty: fn() -> F) -> Self {
+        Frames { decoder, packets, empty, draining: false, done: false }
+    }
+}
+
+impl<'a, I, P, F> Iterator for Frames<'a, I, F>
+where
+    I: Iterator<Item = P>,
+    P: packet::Ref,
+    F: DerefMut<Target = Frame>,
+{
+    type Item = Result<F, Error>;
+
+    fn next(&mut self) -> Option<<Self as Iterator>::Item> {
+        if self.done {
+            return None;
+        }
+
+        loop {
+            let mut frame = (self.empty)();
+
+            match self.decoder.receive_frame(&mut frame) {
+                Ok(()) => return Some(Ok(frame)),
+
+                Err(Error::Other { errno: EAGAIN }) if !self.draining => {
+                    let result = match self.packets.next() {
+                        Some(packet) => self.decoder.send_packet(&packet),
+
+                        None => {
+                            self.draining = true;
+                            self.decoder.send_eof()
+                        }
+                    };
+
+                    match result {
+                        Ok(()) => (),
+                        Err(Error::Eof) => self.draining = true,
+                        Err(e) => return Some(Err(e)),
+                    }
+                }
+
+                Err(Error::Eof) | Err(Error::Other { errno: EAGAIN }) => {
+                    self.done = true;
+                    return None;
+                }
+
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+}
@@ -18,14 +18,23 @@ pub use self::conceal::Conceal;
 pub mod check;
 pub use self::check::Check;
 
+pub mod charset;
+pub use self::charset::CharsetMode;
+
 pub mod opened;
 pub use self::opened::Opened;
 
+pub mod frames;
+pub use self::frames::Frames;
+
+pub mod any;
+pub use self::any::AnyDecoder;
+
 use std::ffi::CString;
 
 use crate::{
     Codec,
-    codec::{Context, Id},
+    codec::{Capabilities, Context, Id},
     ffi::*,
 };
 
@@ -43,12 +52,30 @@ pub fn find(id: Id) -> Option<Codec> {
     }
 }
 
+/// Finds the decoder called `name` (`avcodec_find_decoder_by_name`), e.g. `"libdav1d"`
+/// or `"h264_cuvid"` rather than whichever decoder [`find()`] prefers for the ID.
+///
+/// Returns `None` if no such decoder is compiled in, including when `name` is the
+/// name of an encoder only.
 pub fn find_by_name(name: &str) -> Option<Codec> {
+    let name = CString::new(name).ok()?;
+
     unsafe {
-        let name = CString::new(name).unwrap();
         #[allow(clippy::unnecessary_cast)]
         let ptr = avcodec_find_decoder_by_name(name.as_ptr()) as *mut AVCodec;
 
         if ptr.is_null() { None } else { Some(Codec::wrap(ptr)) }
     }
 }
+
+/// Iterates over every decoder of `id`, in the order [`find()`] considers them.
+pub fn find_all(id: Id) -> impl Iterator<Item = Codec> {
+    super::codecs().filter(move |codec| codec.is_decoder() && codec.id() == id)
+}
+
+/// Finds the first decoder of `id` with all of `capabilities`, e.g.
+/// [`Capabilities::HARDWARE`] to prefer a hardware decoder when one is compiled
+/// in. Unlike [`find()`], experimental decoders are not skipped.
+pub fn find_with(id: Id, capabilities: Capabilities) -> Option<Codec> {
+    find_all(id).find(|codec| codec.capabilities().contains(capabilities))
+}
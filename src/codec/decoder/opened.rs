@@ -6,7 +6,7 @@ use std::{
 use super::{Audio, Decoder, Subtitle, Video};
 use crate::{
     Error, Frame, Rational,
-    codec::{Context, Profile},
+    codec::{Context, Parameters, Profile},
     ffi::*,
     media, packet,
 };
@@ -46,6 +46,13 @@ impl Opened {
         }
     }
 
+    /// Receives the next decoded frame into `frame`.
+    ///
+    /// `avcodec_receive_frame` always unreferences `frame` before filling it, so the
+    /// same frame can be passed on every call: the previous buffers are released (or
+    /// returned to the decoder's pool) and nothing leaks. This also means the previous
+    /// content is gone even when an error such as `EAGAIN` is returned; clone or copy
+    /// the frame first if it must outlive the next call.
     pub fn receive_frame(&mut self, frame: &mut Frame) -> Result<(), Error> {
         unsafe {
             match avcodec_receive_frame(self.as_mut_ptr(), frame.as_mut_ptr()) {
@@ -75,6 +82,17 @@ impl Opened {
         }
     }
 
+    /// Returns the codec parameters as currently known to the decoder.
+    ///
+    /// Decoders update their context from the bitstream, so after the first frame has
+    /// been received this reflects the actual decoded properties (resolution, pixel or
+    /// sample format, channel layout, ...), which may differ from what the container
+    /// declares, e.g. in MPEG-TS. Use it rather than the stream parameters to configure
+    /// downstream components.
+    pub fn parameters(&self) -> Parameters {
+        Parameters::from(self)
+    }
+
     pub fn flush(&mut self) {
         unsafe {
             avcodec_flush_buffers(self.as_mut_ptr());
@@ -116,3 +134,40 @@ impl AsMut<Context> for Opened {
         &mut self.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::{Packet, codec, decoder, ffi::*, frame};
+
+    #[test]
+    fn test_receive_frame_reuse() {
+        let mut context = codec::Context::new_with_codec(decoder::find(codec::Id::RAWVIDEO).unwrap());
+
+        unsafe {
+            (*context.as_mut_ptr()).width = 4;
+            (*context.as_mut_ptr()).height = 4;
+            (*context.as_mut_ptr()).pix_fmt = AVPixelFormat::AV_PIX_FMT_GRAY8;
+        }
+
+        let mut decoder = context.decoder().video().unwrap();
+        let mut frame = frame::Video::empty();
+
+        for index in 0..100 {
+            decoder.send_packet(&Packet::copy(&[index as u8; 16])).unwrap();
+
+            unsafe {
+                // Reference of our own to the buffer of the previous frame.
+                let mut previous = if index > 0 { av_buffer_ref((*frame.as_ptr()).buf[0]) } else { std::ptr::null_mut() };
+
+                decoder.receive_frame(&mut frame).unwrap();
+
+                if !previous.is_null() {
+                    assert_eq!(av_buffer_get_ref_count(previous), 1, "frame {index} kept the previous buffer");
+                    av_buffer_unref(&mut previous);
+                }
+            }
+
+            assert_eq!(frame.data(0)[0], index as u8);
+        }
+    }
+}
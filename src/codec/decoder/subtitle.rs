@@ -9,10 +9,13 @@ use crate::{Error, codec::Context, packet};
 pub struct Subtitle(pub Opened);
 
 impl Subtitle {
+    /// Decodes `packet` into `out`, releasing the rects `out` held before.
     pub fn decode<P: packet::Ref>(&mut self, packet: &P, out: &mut crate::Subtitle) -> Result<bool, Error> {
         unsafe {
             let mut got: c_int = 0;
 
+            avsubtitle_free(out.as_mut_ptr());
+
             match avcodec_decode_subtitle2(self.as_mut_ptr(), out.as_mut_ptr(), &mut got, packet.as_ptr() as *mut _) {
                 e if e < 0 => Err(Error::from(e)),
                 _ => Ok(got != 0),
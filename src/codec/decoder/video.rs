@@ -2,19 +2,54 @@ use std::ops::{Deref, DerefMut};
 
 #[cfg(not(feature = "ffmpeg_5_0"))]
 use crate::ffi::*;
-use libc::c_int;
+use libc::{EAGAIN, c_int};
 
-use super::{Opened, slice};
-#[cfg(not(feature = "ffmpeg_5_0"))]
-use crate::frame;
+use super::{Frames, Opened, slice};
 use crate::{
-    FieldOrder, Rational,
+    Error, FieldOrder, Rational,
     codec::Context,
-    color,
+    color, frame, packet,
     util::{chroma, format},
 };
-#[cfg(not(feature = "ffmpeg_5_0"))]
-use {crate::Error, crate::packet};
+
+/// Outcome of [`Video::receive_frame_checked()`].
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum Received {
+    /// A frame was produced with the same format and dimensions as the previous one.
+    Frame,
+    /// A frame was produced whose format or dimensions differ from the previous one.
+    FormatChanged,
+    /// No frame is available yet; send more packets.
+    Again,
+    /// The decoder has been fully drained.
+    Eof,
+}
+
+/// Remembers the pixel format and dimensions of the frames passed to
+/// [`Video::receive_frame_checked()`] to detect changes.
+#[derive(Eq, PartialEq, Clone, Copy, Debug, Default)]
+pub struct FormatTracker {
+    last: Option<(format::Pixel, u32, u32)>,
+}
+
+impl FormatTracker {
+    pub fn new() -> Self {
+        FormatTracker { last: None }
+    }
+
+    /// Records the properties of `frame` and returns whether they differ from
+    /// the ones of the previous frame. The first frame never counts as a change.
+    pub fn update(&mut self, frame: &frame::Video) -> bool {
+        let current = (frame.format(), frame.width(), frame.height());
+
+        self.last.replace(current).is_some_and(|previous| previous != current)
+    }
+
+    /// Forgets the last frame, e.g. when switching to another stream.
+    pub fn reset(&mut self) {
+        self.last = None;
+    }
+}
 
 pub struct Video(pub Opened);
 
@@ -36,6 +71,53 @@ impl Video {
         }
     }
 
+    /// Receives a decoded frame and reports whether its format changed.
+    ///
+    /// The pixel format and dimensions of each frame received are recorded in
+    /// `tracker` and compared with the previous one; the first frame never reports
+    /// a change. Adaptive streams switch resolution mid-stream, so anything sized
+    /// from the first frame (scalers, filter graphs) must be rebuilt on
+    /// [`Received::FormatChanged`]. Use the same tracker for every call on this
+    /// decoder.
+    ///
+    /// ```ignore
+    /// let mut tracker = decoder::video::FormatTracker::new();
+    ///
+    /// while let Received::Frame | Received::FormatChanged = decoder.receive_frame_checked(&mut frame, &mut tracker)? {
+    ///     // ...
+    /// }
+    /// ```
+    pub fn receive_frame_checked(&mut self, frame: &mut frame::Video, tracker: &mut FormatTracker) -> Result<Received, Error> {
+        match self.receive_frame(frame) {
+            Ok(()) => Ok(if tracker.update(frame) { Received::FormatChanged } else { Received::Frame }),
+
+            Err(Error::Other { errno: EAGAIN }) => Ok(Received::Again),
+            Err(Error::Eof) => Ok(Received::Eof),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Decodes `packets` and iterates over the resulting frames, replacing the
+    /// send/receive loop:
+    ///
+    /// ```ignore
+    /// for frame in decoder.decode_iter(input.packets_for(index)) {
+    ///     let frame = frame?;
+    ///     // ...
+    /// }
+    /// ```
+    ///
+    /// `EAGAIN` is handled internally and the decoder is drained once `packets`
+    /// runs out, so no buffered frame is lost. Afterwards the decoder is at EOF;
+    /// call [`flush()`](Opened::flush) to reuse it.
+    pub fn decode_iter<I>(&mut self, packets: I) -> Frames<'_, I::IntoIter, frame::Video>
+    where
+        I: IntoIterator,
+        I::Item: packet::Ref,
+    {
+        Frames::new(&mut self.0, packets.into_iter(), frame::Video::empty)
+    }
+
     pub fn width(&self) -> u32 {
         unsafe { (*self.as_ptr()).width as u32 }
     }
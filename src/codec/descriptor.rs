@@ -0,0 +1,130 @@
+use std::{ffi::CStr, ptr, str::from_utf8_unchecked};
+
+use super::{Id, Props};
+use crate::{ffi::*, media};
+
+/// Static description of a codec ID, independent of any concrete implementation.
+///
+/// Descriptors are the canonical list of formats libavcodec knows about. A descriptor
+/// exists for every [`Id`], even when no encoder or decoder for it was compiled in.
+#[derive(PartialEq, Eq, Copy, Clone)]
+pub struct Descriptor {
+    ptr: *const AVCodecDescriptor,
+}
+
+unsafe impl Send for Descriptor {}
+unsafe impl Sync for Descriptor {}
+
+impl Descriptor {
+    pub unsafe fn wrap(ptr: *const AVCodecDescriptor) -> Self {
+        Descriptor { ptr }
+    }
+
+    pub unsafe fn as_ptr(&self) -> *const AVCodecDescriptor {
+        self.ptr
+    }
+}
+
+impl Descriptor {
+    pub fn id(&self) -> Id {
+        unsafe { Id::from((*self.as_ptr()).id) }
+    }
+
+    pub fn medium(&self) -> media::Type {
+        unsafe { media::Type::from((*self.as_ptr()).type_) }
+    }
+
+    pub fn name(&self) -> &'static str {
+        unsafe { from_utf8_unchecked(CStr::from_ptr((*self.as_ptr()).name).to_bytes()) }
+    }
+
+    pub fn description(&self) -> &'static str {
+        unsafe {
+            let long_name = (*self.as_ptr()).long_name;
+            if long_name.is_null() { "" } else { from_utf8_unchecked(CStr::from_ptr(long_name).to_bytes()) }
+        }
+    }
+
+    pub fn props(&self) -> Props {
+        unsafe { Props::from_bits_truncate((*self.as_ptr()).props) }
+    }
+
+    pub fn mime_types(&self) -> Option<MimeTypeIter> {
+        unsafe {
+            let ptr = (*self.as_ptr()).mime_types;
+            if ptr.is_null() { None } else { Some(MimeTypeIter::new(ptr)) }
+        }
+    }
+}
+
+impl Id {
+    /// Returns the descriptor for this codec ID, if libavcodec knows about it.
+    pub fn descriptor(&self) -> Option<Descriptor> {
+        unsafe {
+            let ptr = avcodec_descriptor_get((*self).into());
+
+            if ptr.is_null() { None } else { Some(Descriptor::wrap(ptr)) }
+        }
+    }
+}
+
+pub struct DescriptorIter {
+    ptr: *const AVCodecDescriptor,
+}
+
+impl DescriptorIter {
+    pub fn new() -> Self {
+        DescriptorIter { ptr: ptr::null() }
+    }
+}
+
+impl Default for DescriptorIter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Iterator for DescriptorIter {
+    type Item = Descriptor;
+
+    fn next(&mut self) -> Option<<Self as Iterator>::Item> {
+        unsafe {
+            let ptr = avcodec_descriptor_next(self.ptr);
+
+            if ptr.is_null() {
+                return None;
+            }
+
+            self.ptr = ptr;
+
+            Some(Descriptor::wrap(ptr))
+        }
+    }
+}
+
+pub struct MimeTypeIter {
+    ptr: *const *const libc::c_char,
+}
+
+impl MimeTypeIter {
+    pub fn new(ptr: *const *const libc::c_char) -> Self {
+        MimeTypeIter { ptr }
+    }
+}
+
+impl Iterator for MimeTypeIter {
+    type Item = &'static str;
+
+    fn next(&mut self) -> Option<<Self as Iterator>::Item> {
+        unsafe {
+            if (*self.ptr).is_null() {
+                return None;
+            }
+
+            let mime_type = from_utf8_unchecked(CStr::from_ptr(*self.ptr).to_bytes());
+            self.ptr = self.ptr.offset(1);
+
+            Some(mime_type)
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use std::{
     ops::{Deref, DerefMut},
-    ptr,
+    ptr, slice,
 };
 
 use crate::ffi::*;
@@ -211,6 +211,39 @@ impl Encoder {
     pub fn frame_size(&self) -> u32 {
         unsafe { (*self.as_ptr()).frame_size as u32 }
     }
+
+    /// Number of samples per channel in a packet of `packet_size` bytes produced by
+    /// this encoder.
+    ///
+    /// Encoders with a fixed frame size report [`frame_size()`](Self::frame_size)
+    /// (the last packet of a stream may be shorter; its own duration tells); for the
+    /// others, such as PCM, it is derived from the packet size. `None` if unknown.
+    pub fn frame_duration(&self, packet_size: usize) -> Option<u32> {
+        unsafe {
+            match (*self.as_ptr()).frame_size {
+                0 => match av_get_audio_frame_duration(self.as_ptr() as *mut _, packet_size as _) {
+                    0 => None,
+                    n => Some(n as u32),
+                },
+
+                n => Some(n as u32),
+            }
+        }
+    }
+
+    /// Codec-specific configuration written while opening (e.g. the AAC
+    /// AudioSpecificConfig or the Opus header).
+    ///
+    /// Only available after open, and for most encoders only when
+    /// [`codec::Flags::GLOBAL_HEADER`](crate::codec::Flags::GLOBAL_HEADER) was set
+    /// beforehand; `None` otherwise.
+    pub fn extradata(&self) -> Option<&[u8]> {
+        unsafe {
+            let context = self.as_ptr();
+
+            if (*context).extradata.is_null() || (*context).extradata_size <= 0 { None } else { Some(slice::from_raw_parts((*context).extradata, (*context).extradata_size as usize)) }
+        }
+    }
 }
 
 impl Deref for Encoder {
@@ -6,8 +6,8 @@ use std::{
 use crate::ffi::*;
 use libc::c_int;
 
-use super::{audio, subtitle, video};
-use crate::{Error, Frame, codec::Context, media, packet};
+use super::{Packets, audio, monotonic::Verdict, subtitle, video};
+use crate::{Error, Frame, Packet, Rational, Rescale, codec::Context, media, packet};
 
 /// An encoder for compressing raw media frames.
 ///
@@ -128,7 +128,38 @@ impl Encoder {
     /// - `Error::Other(EAGAIN)` - The encoder needs more frames before producing output
     /// - `Error::Eof` - The encoder has been flushed and won't accept more frames
     /// - Other errors indicate encoding failure
+    ///
+    /// Frames whose pts does not increase are handled according to
+    /// [`set_monotonic_mode()`](video::Video::set_monotonic_mode).
     pub fn send_frame(&mut self, frame: &Frame) -> Result<(), Error> {
+        unsafe {
+            if !frame.as_ptr().is_null() {
+                match self.0.monotonic_mut().check(frame.pts()) {
+                    Verdict::Send => (),
+                    Verdict::Skip => return Ok(()),
+
+                    Verdict::Replace(pts) => {
+                        let ptr = av_frame_clone(frame.as_ptr());
+
+                        if ptr.is_null() {
+                            panic!("out of memory");
+                        }
+
+                        (*ptr).pts = pts;
+
+                        return self.send(&Frame::wrap(ptr));
+                    }
+                }
+            } else {
+                // Once flushed, the encoder starts over.
+                self.0.monotonic_mut().reset();
+            }
+
+            self.send(frame)
+        }
+    }
+
+    fn send(&mut self, frame: &Frame) -> Result<(), Error> {
         unsafe {
             match avcodec_send_frame(self.as_mut_ptr(), frame.as_ptr()) {
                 e if e < 0 => Err(Error::from(e)),
@@ -137,6 +168,47 @@ impl Encoder {
         }
     }
 
+    /// Sends `frame` with its timestamps rescaled from `time_base` to the time
+    /// base of the encoder, for frames timed in another unit (seconds,
+    /// milliseconds, the input stream's time base, ...).
+    ///
+    /// The pts and duration of a reference to `frame` are rescaled, `frame` itself
+    /// is left untouched. A frame without pts is sent as is, letting the encoder
+    /// handle it (most simply number such frames one after the other).
+    pub fn send_frame_rescaled<R: Into<Rational>>(&mut self, frame: &Frame, time_base: R) -> Result<(), Error> {
+        let source = time_base.into();
+        let destination = self.time_base();
+
+        if source == destination {
+            return self.send_frame(frame);
+        }
+
+        unsafe {
+            let ptr = av_frame_clone(frame.as_ptr());
+
+            if ptr.is_null() {
+                panic!("out of memory");
+            }
+
+            let rescaled = Frame::wrap(ptr);
+
+            if (*ptr).pts != AV_NOPTS_VALUE {
+                (*ptr).pts = (*ptr).pts.rescale(source, destination);
+            }
+
+            #[cfg(not(feature = "ffmpeg_7_0"))]
+            {
+                (*ptr).pkt_duration = (*ptr).pkt_duration.rescale(source, destination);
+            }
+            #[cfg(feature = "ffmpeg_7_0")]
+            {
+                (*ptr).duration = (*ptr).duration.rescale(source, destination);
+            }
+
+            self.send_frame(&rescaled)
+        }
+    }
+
     /// Signals end-of-stream and enters draining mode.
     ///
     /// After calling this, continue calling [`receive_packet()`](Encoder::receive_packet)
@@ -171,6 +243,41 @@ impl Encoder {
         }
     }
 
+    /// Sends `frame` and returns the packets it completes, replacing the
+    /// `send_frame()` / `receive_packet()` loop:
+    ///
+    /// ```ignore
+    /// for packet in encoder.encode_iter(&frame)? {
+    ///     let mut packet = packet?;
+    ///     packet.set_stream(0);
+    ///     packet.write_interleaved(&mut output)?;
+    /// }
+    /// ```
+    ///
+    /// Encoders buffer frames (lookahead, B-frames, audio frame size), so a frame
+    /// often yields no packet at all and the last ones only come out of
+    /// [`finish()`](Self::finish), which must be called once all frames are sent.
+    /// Consume the iterator before sending the next frame.
+    pub fn encode_iter(&mut self, frame: &Frame) -> Result<Packets<'_>, Error> {
+        self.send_frame(frame)?;
+
+        Ok(Packets::new(self))
+    }
+
+    /// Signals the end of the stream and returns every packet the encoder still
+    /// buffers, typically the last GOP of a video encoder. Forgetting this step
+    /// truncates the output.
+    ///
+    /// Calling it again returns no packets; the encoder cannot take frames anymore.
+    pub fn finish(&mut self) -> Result<Vec<Packet>, Error> {
+        match self.send_eof() {
+            Ok(()) | Err(Error::Eof) => (),
+            Err(e) => return Err(e),
+        }
+
+        Packets::new(self).collect()
+    }
+
     /// Sets the target bitrate in bits per second.
     ///
     /// This is the average bitrate the encoder will try to achieve. Used for
@@ -1,6 +1,9 @@
 pub mod encoder;
 pub use self::encoder::Encoder;
 
+pub mod packets;
+pub use self::packets::Packets;
+
 pub mod video;
 pub use self::video::Encoder as Video;
 
@@ -24,11 +27,14 @@ pub use self::comparison::Comparison;
 pub mod decision;
 pub use self::decision::Decision;
 
+pub mod monotonic;
+pub use self::monotonic::Monotonic;
+
 use std::ffi::CString;
 
 use crate::{
     Codec,
-    codec::{Context, Id},
+    codec::{Capabilities, Context, Id},
     ffi::*,
 };
 
@@ -46,12 +52,30 @@ pub fn find(id: Id) -> Option<Codec> {
     }
 }
 
+/// Finds the encoder called `name` (`avcodec_find_encoder_by_name`), e.g. `"libx264"`
+/// or `"h264_nvenc"` rather than whichever encoder [`find()`] prefers for the ID.
+///
+/// Returns `None` if no such encoder is compiled in, including when `name` is the
+/// name of a decoder only.
 pub fn find_by_name(name: &str) -> Option<Codec> {
+    let name = CString::new(name).ok()?;
+
     unsafe {
-        let name = CString::new(name).unwrap();
         #[allow(clippy::unnecessary_cast)]
         let ptr = avcodec_find_encoder_by_name(name.as_ptr()) as *mut AVCodec;
 
         if ptr.is_null() { None } else { Some(Codec::wrap(ptr)) }
     }
 }
+
+/// Iterates over every encoder of `id`, in the order [`find()`] considers them.
+pub fn find_all(id: Id) -> impl Iterator<Item = Codec> {
+    super::codecs().filter(move |codec| codec.is_encoder() && codec.id() == id)
+}
+
+/// Finds the first encoder of `id` with all of `capabilities`, e.g.
+/// [`Capabilities::HARDWARE`] to prefer a hardware encoder when one is compiled
+/// in. Unlike [`find()`], experimental encoders are not skipped.
+pub fn find_with(id: Id, capabilities: Capabilities) -> Option<Codec> {
+    find_all(id).find(|codec| codec.capabilities().contains(capabilities))
+}
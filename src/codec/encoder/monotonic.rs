@@ -0,0 +1,132 @@
+/// What an encoder does with a frame whose pts is not greater than the one of
+/// the previous frame, see
+/// [`Video::set_monotonic_mode()`](super::video::Video::set_monotonic_mode).
+#[derive(Eq, PartialEq, Clone, Copy, Debug, Default)]
+pub enum Monotonic {
+    /// Send the frame as is, leaving it to the encoder (or later the muxer) to
+    /// reject it.
+    #[default]
+    Error,
+    /// Skip the frame.
+    Drop,
+    /// Send the frame with its pts moved one tick after the previous one.
+    Clamp,
+}
+
+pub(crate) enum Verdict {
+    Send,
+    Skip,
+    Replace(i64),
+}
+
+// Pts of the last frame sent to an encoder, kept by its codec context.
+#[derive(Default)]
+pub(crate) struct Guard {
+    pub(crate) mode: Monotonic,
+    pub(crate) report: Option<Box<dyn FnMut(i64, i64) + Send>>,
+    last: Option<i64>,
+}
+
+impl Guard {
+    // Forgets the previous pts, once the encoder is flushed.
+    pub(crate) fn reset(&mut self) {
+        self.last = None;
+    }
+
+    pub(crate) fn check(&mut self, pts: Option<i64>) -> Verdict {
+        let Some(pts) = pts else {
+            return Verdict::Send;
+        };
+
+        match self.last {
+            Some(last) if pts <= last => {
+                if let Some(report) = self.report.as_mut() {
+                    report(pts, last);
+                }
+
+                match self.mode {
+                    Monotonic::Error => Verdict::Send,
+                    Monotonic::Drop => Verdict::Skip,
+
+                    Monotonic::Clamp => {
+                        self.last = Some(last + 1);
+                        Verdict::Replace(last + 1)
+                    }
+                }
+            }
+
+            _ => {
+                self.last = Some(pts);
+                Verdict::Send
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+
+    fn guard(mode: Monotonic) -> Guard {
+        Guard { mode, ..Default::default() }
+    }
+
+    #[test]
+    fn test_error_sends() {
+        let mut guard = guard(Monotonic::Error);
+
+        assert!(matches!(guard.check(Some(10)), Verdict::Send));
+        assert!(matches!(guard.check(Some(5)), Verdict::Send));
+        assert!(matches!(guard.check(Some(10)), Verdict::Send));
+        assert!(matches!(guard.check(None), Verdict::Send));
+        assert!(matches!(guard.check(Some(11)), Verdict::Send));
+    }
+
+    #[test]
+    fn test_drop_skips() {
+        let mut guard = guard(Monotonic::Drop);
+
+        assert!(matches!(guard.check(Some(10)), Verdict::Send));
+        assert!(matches!(guard.check(Some(10)), Verdict::Skip));
+        assert!(matches!(guard.check(Some(5)), Verdict::Skip));
+        assert!(matches!(guard.check(None), Verdict::Send));
+        assert!(matches!(guard.check(Some(11)), Verdict::Send));
+    }
+
+    #[test]
+    fn test_clamp() {
+        let mut guard = guard(Monotonic::Clamp);
+
+        assert!(matches!(guard.check(Some(10)), Verdict::Send));
+        assert!(matches!(guard.check(Some(10)), Verdict::Replace(11)));
+        assert!(matches!(guard.check(Some(11)), Verdict::Replace(12)));
+        assert!(matches!(guard.check(Some(5)), Verdict::Replace(13)));
+        assert!(matches!(guard.check(Some(14)), Verdict::Send));
+    }
+
+    #[test]
+    fn test_report() {
+        let reported = Arc::new(Mutex::new(Vec::new()));
+        let mut guard = guard(Monotonic::Drop);
+
+        let sink = reported.clone();
+        guard.report = Some(Box::new(move |pts, last| sink.lock().unwrap().push((pts, last))));
+
+        for pts in [10, 10, 5, 11, 11] {
+            guard.check(Some(pts));
+        }
+
+        assert_eq!(*reported.lock().unwrap(), vec![(10, 10), (5, 10), (11, 11)]);
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut guard = guard(Monotonic::Drop);
+
+        assert!(matches!(guard.check(Some(10)), Verdict::Send));
+        guard.reset();
+        assert!(matches!(guard.check(Some(0)), Verdict::Send));
+    }
+}
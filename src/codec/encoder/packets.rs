@@ -0,0 +1,47 @@
+use libc::EAGAIN;
+
+use super::Encoder;
+use crate::{Error, Packet};
+
+/// Iterator over the packets an encoder has ready.
+///
+/// Created by [`Encoder::encode_iter()`] after sending a frame. It receives
+/// packets until the encoder asks for more input (`EAGAIN`) or is fully drained
+/// (`EOF`), so either condition simply ends the iteration. Any other error is
+/// yielded and ends it as well.
+pub struct Packets<'a> {
+    encoder: &'a mut Encoder,
+    done: bool,
+}
+
+impl<'a> Packets<'a> {
+    pub(super) fn new(encoder: &'a mut Encoder) -> Self {
+        Packets { encoder, done: false }
+    }
+}
+
+impl<'a> Iterator for Packets<'a> {
+    type Item = Result<Packet, Error>;
+
+    fn next(&mut self) -> Option<<Self as Iterator>::Item> {
+        if self.done {
+            return None;
+        }
+
+        let mut packet = Packet::empty();
+
+        match self.encoder.receive_packet(&mut packet) {
+            Ok(()) => Some(Ok(packet)),
+
+            Err(Error::Eof) | Err(Error::Other { errno: EAGAIN }) => {
+                self.done = true;
+                None
+            }
+
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
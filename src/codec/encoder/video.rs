@@ -1,43 +1,191 @@
 use std::{
+    cell::RefCell,
+    error,
+    ffi::CString,
+    fmt,
     ops::{Deref, DerefMut},
-    ptr,
+    ptr, slice,
 };
 
 use crate::ffi::*;
-use libc::{c_float, c_int};
+use libc::{EINVAL, c_float, c_int, c_void};
 
-use super::{Comparison, Decision, Encoder as Super};
+use super::{Comparison, Decision, Encoder as Super, Monotonic};
 #[cfg(not(feature = "ffmpeg_5_0"))]
 use super::{MotionEstimation, Prediction};
 use crate::{
-    Dictionary, Error, Rational,
+    Codec, Dictionary, Error, Rational,
     codec::{Context, traits},
-    color, format,
+    color, format, log,
 };
 #[cfg(not(feature = "ffmpeg_5_0"))]
 use {crate::frame, crate::packet};
 
+/// A video encoder setting that `avcodec_open2` would reject.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Invalid {
+    /// The time base is unset or not positive.
+    TimeBase(Rational),
+    /// Width or height is zero.
+    Dimensions(u32, u32),
+    /// No pixel format is set.
+    MissingFormat,
+    /// The pixel format is not supported by the encoder.
+    UnsupportedFormat(format::Pixel),
+}
+
+impl fmt::Display for Invalid {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Invalid::TimeBase(value) => write!(f, "encoder time base {value} is not set or not positive, set it to the inverse of the frame rate or to the stream time base"),
+            Invalid::Dimensions(width, height) => write!(f, "encoder dimensions {width}x{height} are invalid, width and height must be set"),
+            Invalid::MissingFormat => f.write_str("encoder pixel format is not set"),
+            Invalid::UnsupportedFormat(format) => write!(f, "pixel format {format:?} is not supported by the encoder"),
+        }
+    }
+}
+
+impl error::Error for Invalid {}
+
+impl From<Invalid> for Error {
+    fn from(_: Invalid) -> Error {
+        Error::Other { errno: EINVAL }
+    }
+}
+
+thread_local! {
+    static OPEN_ERROR: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Why the last `open*` call of a video encoder on this thread failed: the
+/// description of the setting [`Video::validate()`] rejected, or else the last
+/// error FFmpeg logged while opening the encoder, e.g. `"timebase 1/100000 not
+/// supported by MPEG 4 standard, ..."` for an `EINVAL`. `None` after a successful
+/// open, or if FFmpeg logged nothing.
+pub fn open_error() -> Option<String> {
+    OPEN_ERROR.with(|error| error.borrow().clone())
+}
+
 pub struct Video(pub Super);
 
 impl Video {
-    #[inline]
-    pub fn open(mut self) -> Result<Encoder, Error> {
+    /// Checks the settings `avcodec_open2` rejects with a bare `EINVAL`.
+    ///
+    /// The `open*` methods run this first: on failure they log the description at
+    /// error level (so it shows up next to FFmpeg's own messages) and return
+    /// `EINVAL` without calling into FFmpeg; [`open_error()`] gives the description
+    /// afterwards. The pixel format is checked against the encoder already
+    /// associated with the context, if any.
+    ///
+    /// Two settings are not rejected, but a warning is logged when the encoder is
+    /// opened: a frame rate whose frame duration is not a whole number of time base
+    /// units, since it leads to uneven timestamps, and a maximum bit rate without a
+    /// rate control buffer size, which most encoders ignore or replace with a guess.
+    /// Both can also come from the `maxrate` and `bufsize` options passed to
+    /// [`open_with()`](Self::open_with) and [`open_as_with()`](Self::open_as_with).
+    pub fn validate(&self) -> Result<(), Invalid> {
+        self.validate_for(self.codec())
+    }
+
+    fn validate_for(&self, codec: Option<Codec>) -> Result<(), Invalid> {
+        let time_base = self.time_base();
+
+        if time_base.numerator() <= 0 || time_base.denominator() <= 0 {
+            return Err(Invalid::TimeBase(time_base));
+        }
+
+        if self.width() == 0 || self.height() == 0 {
+            return Err(Invalid::Dimensions(self.width(), self.height()));
+        }
+
+        if self.format() == format::Pixel::None {
+            return Err(Invalid::MissingFormat);
+        }
+
+        let supported = codec.and_then(|codec| codec.video().ok()).and_then(|codec| codec.formats()).is_none_or(|mut formats| formats.any(|format| format == self.format()));
+
+        if !supported {
+            return Err(Invalid::UnsupportedFormat(self.format()));
+        }
+
+        Ok(())
+    }
+
+    // `options` is the dictionary about to be passed to `avcodec_open2`, which may
+    // set what the fields lack.
+    fn check(&mut self, codec: Option<Codec>, options: Option<&Dictionary>) -> Result<(), Error> {
+        if let Err(invalid) = self.validate_for(codec) {
+            self.log(AV_LOG_ERROR as c_int, &invalid.to_string());
+            OPEN_ERROR.with(|error| *error.borrow_mut() = Some(invalid.to_string()));
+
+            return Err(invalid.into());
+        }
+
+        let option = |name: &str| options.is_some_and(|options| options.get(name).is_some());
+        let (max_rate, buffer_size) = unsafe { ((*self.as_ptr()).rc_max_rate > 0 || option("maxrate"), (*self.as_ptr()).rc_buffer_size > 0 || option("bufsize")) };
+
+        if max_rate && !buffer_size {
+            self.log(AV_LOG_WARNING as c_int, "maximum bit rate is set without a rate control buffer size, set both for VBV constrained encoding");
+        }
+
+        let time_base = self.time_base();
+        let frame_rate = self.frame_rate();
+
+        if frame_rate.numerator() > 0 && frame_rate.denominator() > 0 {
+            let duration = i64::from(frame_rate.denominator()) * i64::from(time_base.denominator());
+
+            if duration % (i64::from(frame_rate.numerator()) * i64::from(time_base.numerator())) != 0 {
+                self.log(AV_LOG_WARNING as c_int, &format!("frame rate {frame_rate} cannot be represented exactly in time base {time_base}, timestamps will be uneven"));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn log(&mut self, level: c_int, message: &str) {
+        let format = CString::new("%s\n").unwrap();
+        let message = CString::new(message).unwrap();
+
         unsafe {
-            match avcodec_open2(self.as_mut_ptr(), ptr::null(), ptr::null_mut()) {
-                0 => Ok(Encoder(self)),
+            av_log(self.as_mut_ptr() as *mut c_void, level, format.as_ptr(), message.as_ptr());
+        }
+    }
+
+    // Opens the encoder, keeping the last error FFmpeg logged for `open_error()`.
+    unsafe fn open_codec(&mut self, codec: *const AVCodec, options: Option<Dictionary>) -> Result<(), Error> {
+        unsafe {
+            let mut opts = options.map_or(ptr::null_mut(), |options| options.disown());
+            let (res, message) = log::capture_error(|| avcodec_open2(self.as_mut_ptr(), codec, &mut opts));
+
+            Dictionary::own(opts);
+            OPEN_ERROR.with(|error| *error.borrow_mut() = if res < 0 { message } else { None });
+
+            match res {
+                0 => Ok(()),
                 e => Err(Error::from(e)),
             }
         }
     }
 
+    #[inline]
+    pub fn open(mut self) -> Result<Encoder, Error> {
+        self.check(self.codec(), None)?;
+
+        unsafe {
+            self.open_codec(ptr::null(), None)?;
+        }
+
+        Ok(Encoder(self))
+    }
+
     #[inline]
     pub fn open_as<E: traits::Encoder>(mut self, codec: E) -> Result<Encoder, Error> {
         unsafe {
             if let Some(codec) = codec.encoder() {
-                match avcodec_open2(self.as_mut_ptr(), codec.as_ptr(), ptr::null_mut()) {
-                    0 => Ok(Encoder(self)),
-                    e => Err(Error::from(e)),
-                }
+                self.check(Some(codec), None)?;
+                self.open_codec(codec.as_ptr(), None)?;
+
+                Ok(Encoder(self))
             } else {
                 Err(Error::EncoderNotFound)
             }
@@ -46,32 +194,23 @@ impl Video {
 
     #[inline]
     pub fn open_with(mut self, options: Dictionary) -> Result<Encoder, Error> {
-        unsafe {
-            let mut opts = options.disown();
-            let res = avcodec_open2(self.as_mut_ptr(), ptr::null(), &mut opts);
-
-            Dictionary::own(opts);
+        self.check(self.codec(), Some(&options))?;
 
-            match res {
-                0 => Ok(Encoder(self)),
-                e => Err(Error::from(e)),
-            }
+        unsafe {
+            self.open_codec(ptr::null(), Some(options))?;
         }
+
+        Ok(Encoder(self))
     }
 
     #[inline]
     pub fn open_as_with<E: traits::Encoder>(mut self, codec: E, options: Dictionary) -> Result<Encoder, Error> {
         unsafe {
             if let Some(codec) = codec.encoder() {
-                let mut opts = options.disown();
-                let res = avcodec_open2(self.as_mut_ptr(), codec.as_ptr(), &mut opts);
-
-                Dictionary::own(opts);
+                self.check(Some(codec), Some(&options))?;
+                self.open_codec(codec.as_ptr(), Some(options))?;
 
-                match res {
-                    0 => Ok(Encoder(self)),
-                    e => Err(Error::from(e)),
-                }
+                Ok(Encoder(self))
             } else {
                 Err(Error::EncoderNotFound)
             }
@@ -388,6 +527,79 @@ impl Video {
     pub fn color_range(&self) -> color::Range {
         unsafe { (*self.as_ptr()).color_range.into() }
     }
+
+    /// Sets the size of the rate control (VBV/HRD) buffer, in bits.
+    ///
+    /// Together with [`set_max_bit_rate()`](Super::set_max_bit_rate) this bounds
+    /// how far the bit rate may burst: a decoder receiving the stream at the
+    /// maximum rate never buffers more than this many bits. Encoders ignore a
+    /// maximum rate without a buffer size or replace it with a guess, so opening
+    /// the encoder logs a warning when the buffer size is missing. For low latency
+    /// streaming use a buffer of about one frame, `max_bit_rate / frame_rate`.
+    #[inline]
+    pub fn set_rc_buffer_size(&mut self, bits: u32) {
+        unsafe {
+            (*self.as_mut_ptr()).rc_buffer_size = bits as c_int;
+        }
+    }
+
+    #[inline]
+    pub fn rc_buffer_size(&self) -> u32 {
+        unsafe { (*self.as_ptr()).rc_buffer_size.max(0) as u32 }
+    }
+
+    /// Sets how full the rate control buffer is when decoding starts, in bits;
+    /// libx264 defaults to 90% of the buffer size.
+    #[inline]
+    pub fn set_rc_initial_buffer_occupancy(&mut self, bits: u32) {
+        unsafe {
+            (*self.as_mut_ptr()).rc_initial_buffer_occupancy = bits as c_int;
+        }
+    }
+
+    #[inline]
+    pub fn rc_initial_buffer_occupancy(&self) -> u32 {
+        unsafe { (*self.as_ptr()).rc_initial_buffer_occupancy.max(0) as u32 }
+    }
+
+    /// Number of frames the encoder buffers before producing the first packet
+    /// (lookahead and B-frames). Set by the encoder when it is opened; reduce it
+    /// with the encoder options, e.g. `tune=zerolatency` for libx264.
+    #[inline]
+    pub fn delay(&self) -> u32 {
+        unsafe { (*self.as_ptr()).delay.max(0) as u32 }
+    }
+
+    /// Chooses what [`send_frame()`](Super::send_frame) does with a frame whose
+    /// pts is not greater than the previous one, as live sources sometimes
+    /// deliver them.
+    ///
+    /// [`Monotonic::Error`], the default, sends the frame anyway, so the encoder
+    /// or the muxer fails on it ("non monotonically increasing dts").
+    /// [`Monotonic::Drop`] skips it. [`Monotonic::Clamp`] sends it with a pts one
+    /// tick after the previous frame; the next frames of the source may then carry
+    /// the same timestamps as clamped ones, in which case they are clamped as well
+    /// and the output drifts until the source catches up. Frames without pts are
+    /// always sent as is. Flushing the encoder with
+    /// [`send_eof()`](Super::send_eof) forgets the previous pts.
+    #[inline]
+    pub fn set_monotonic_mode(&mut self, mode: Monotonic) {
+        self.0.0.monotonic_mut().mode = mode;
+    }
+
+    #[inline]
+    pub fn monotonic_mode(&self) -> Monotonic {
+        self.0.0.monotonic().mode
+    }
+
+    /// Calls `callback` with the pts of every out-of-order frame and the pts of
+    /// the frame before it, whatever the [monotonic mode](Self::set_monotonic_mode).
+    pub fn on_non_monotonic<F>(&mut self, callback: F)
+    where
+        F: FnMut(i64, i64) + Send + 'static,
+    {
+        self.0.0.monotonic_mut().report = Some(Box::new(callback));
+    }
 }
 
 impl Deref for Video {
@@ -465,6 +677,20 @@ impl Encoder {
     pub fn frame_size(&self) -> u32 {
         unsafe { (*self.as_ptr()).frame_size as u32 }
     }
+
+    /// Codec-specific global header produced while opening, e.g. the SPS/PPS of
+    /// H.264 in avcC form, as needed for an SDP `sprop-parameter-sets` line.
+    ///
+    /// Encoders only write it when [`codec::Flags::GLOBAL_HEADER`](crate::codec::Flags::GLOBAL_HEADER)
+    /// was set before opening; without the flag the parameter sets are repeated
+    /// in-band in the packets instead and this returns `None`.
+    pub fn extradata(&self) -> Option<&[u8]> {
+        unsafe {
+            let context = self.as_ptr();
+
+            if (*context).extradata.is_null() || (*context).extradata_size <= 0 { None } else { Some(slice::from_raw_parts((*context).extradata, (*context).extradata_size as usize)) }
+        }
+    }
 }
 
 impl Deref for Encoder {
@@ -494,3 +720,35 @@ impl AsMut<Context> for Encoder {
         &mut self.0
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{codec, encoder};
+
+    fn mpeg4(time_base: Rational, format: format::Pixel) -> Video {
+        let mut video = codec::Context::new_with_codec(encoder::find(codec::Id::MPEG4).unwrap()).encoder().video().unwrap();
+        video.set_width(64);
+        video.set_height(64);
+        video.set_format(format);
+        video.set_time_base(time_base);
+
+        video
+    }
+
+    #[test]
+    fn test_open_error() {
+        let codec = encoder::find(codec::Id::MPEG4).unwrap();
+
+        assert_eq!(mpeg4(Rational(1, 25), format::Pixel::None).open_as(codec).err(), Some(Error::Other { errno: EINVAL }));
+        assert_eq!(open_error().as_deref(), Some("encoder pixel format is not set"));
+
+        // Only the encoder knows that MPEG-4 time bases have 16 bits.
+        assert!(mpeg4(Rational(1, 100_000), format::Pixel::YUV420P).validate().is_ok());
+        assert_eq!(mpeg4(Rational(1, 100_000), format::Pixel::YUV420P).open_as(codec).err(), Some(Error::Other { errno: EINVAL }));
+        assert!(open_error().is_some_and(|message| message.contains("timebase 1/100000")), "{:?}", open_error());
+
+        assert!(mpeg4(Rational(1, 25), format::Pixel::YUV420P).open_as(codec).is_ok());
+        assert_eq!(open_error(), None);
+    }
+}
@@ -701,9 +701,23 @@ impl Id {
         unsafe { media::Type::from(avcodec_get_type((*self).into())) }
     }
 
+    /// Short name of the codec (`avcodec_get_name`), e.g. `"h264"`.
+    ///
+    /// It does not depend on an encoder or decoder being compiled in. `Id::None`
+    /// is named `"none"`, and IDs libavcodec has no name for `"unknown_codec"`.
     pub fn name(&self) -> &'static str {
         unsafe { from_utf8_unchecked(CStr::from_ptr(avcodec_get_name((*self).into())).to_bytes()) }
     }
+
+    /// Human readable name of the codec from its [descriptor](Self::descriptor),
+    /// e.g. `"H.264 / AVC / MPEG-4 AVC / MPEG-4 part 10"`, falling back to
+    /// [`name()`](Self::name) when there is none.
+    pub fn long_name(&self) -> &'static str {
+        match self.descriptor().map(|descriptor| descriptor.description()) {
+            Some(description) if !description.is_empty() => description,
+            _ => self.name(),
+        }
+    }
 }
 
 impl From<AVCodecID> for Id {
@@ -9,6 +9,7 @@
 //! - [`encoder`] - Encode raw frames to compressed media
 //! - [`Context`] - Codec context managing encoder/decoder state
 //! - [`packet::Packet`] - Compressed data packet (encoded media)
+//! - [`Parser`] - Splits raw elementary streams into packets
 //! - [`Parameters`] - Codec parameters (resolution, bitrate, sample rate, etc.)
 //! - [`Audio`] / [`Video`] - Type-specific codec information
 //!
@@ -22,6 +23,7 @@
 //! - `packet` - Compressed media packets
 //! - `subtitle` - Subtitle codec support
 //! - `capabilities` - Codec capability flags
+//! - `descriptor` - Codec descriptors (the canonical list of known codec IDs)
 //! - `threading` - Multi-threaded encoding/decoding
 //! - `profile` - Codec profiles (baseline, main, high, etc.)
 //! - `compliance` - Standard compliance levels
@@ -34,6 +36,9 @@ pub use self::id::Id;
 
 pub mod packet;
 
+pub mod parser;
+pub use self::parser::Parser;
+
 pub mod subtitle;
 
 #[cfg(not(feature = "ffmpeg_5_0"))]
@@ -47,6 +52,12 @@ pub use self::context::Context;
 pub mod capabilities;
 pub use self::capabilities::Capabilities;
 
+pub mod props;
+pub use self::props::Props;
+
+pub mod descriptor;
+pub use self::descriptor::Descriptor;
+
 pub mod codec;
 
 pub mod parameters;
@@ -103,3 +114,27 @@ pub fn configuration() -> &'static str {
 pub fn license() -> &'static str {
     unsafe { from_utf8_unchecked(CStr::from_ptr(avcodec_license()).to_bytes()) }
 }
+
+/// Iterates over every encoder and decoder compiled into libavcodec, in the
+/// order [`encoder::find()`] and [`decoder::find()`] search them.
+pub fn codecs() -> codec::CodecIter {
+    codec::CodecIter::new()
+}
+
+/// Iterates over every codec descriptor known to libavcodec.
+///
+/// Descriptors describe codec *IDs* (name, long name, media type, properties) and are
+/// the canonical catalog of formats, whether or not an implementation was compiled in.
+/// To list the concrete encoders and decoders actually available, iterate codecs
+/// (`av_codec_iterate`) instead; several codecs may share one descriptor, e.g.
+/// `libx264` and `h264_nvenc` both implement [`Id::H264`].
+pub fn descriptors() -> descriptor::DescriptorIter {
+    descriptor::DescriptorIter::new()
+}
+
+/// Iterates over the codec descriptors of a single media type.
+///
+/// Equivalent to filtering [`descriptors()`] by [`Descriptor::medium`].
+pub fn descriptors_of(medium: crate::media::Type) -> impl Iterator<Item = Descriptor> {
+    descriptors().filter(move |descriptor| descriptor.medium() == medium)
+}
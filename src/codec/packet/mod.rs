@@ -12,3 +12,6 @@ pub use self::side_data::SideData;
 
 pub mod flag;
 pub use self::flag::Flags;
+
+pub mod quality;
+pub use self::quality::QualityStats;
@@ -1,7 +1,7 @@
 use std::{marker::PhantomData, mem, slice};
 
-use super::{Borrow, Flags, Mut, Ref, SideData};
-use crate::{Error, Rational, ffi::*, format};
+use super::{Borrow, Flags, Mut, QualityStats, Ref, SideData, side_data};
+use crate::{Dictionary, Error, Rational, ffi::*, format};
 use libc::c_int;
 
 pub struct Packet(AVPacket);
@@ -95,6 +95,12 @@ impl Packet {
         self.flags().contains(Flags::KEY)
     }
 
+    /// Whether the packet is flagged as corrupt (`AV_PKT_FLAG_CORRUPT`).
+    ///
+    /// Demuxers set the flag when they detect damaged data, e.g. MPEG-TS continuity
+    /// errors or checksum mismatches. Not every demuxer checks, so an unflagged packet
+    /// is not guaranteed to be intact; decode-time corruption shows up on frames
+    /// instead, see [`Frame::is_corrupt()`](crate::Frame::is_corrupt).
     #[inline]
     pub fn is_corrupt(&self) -> bool {
         self.flags().contains(Flags::CORRUPT)
@@ -179,11 +185,52 @@ impl Packet {
         self.0.convergence_duration as isize
     }
 
+    /// User data attached to the packet, e.g. propagated by an encoder from the
+    /// frame's [`opaque_ref()`](crate::Frame::opaque_ref) with
+    /// [`codec::Flags::COPY_OPAQUE`](crate::codec::Flags::COPY_OPAQUE).
+    #[inline]
+    #[cfg(feature = "ffmpeg_5_0")]
+    pub fn opaque_ref(&self) -> Option<&[u8]> {
+        unsafe { if self.0.opaque_ref.is_null() { None } else { Some(slice::from_raw_parts((*self.0.opaque_ref).data, (*self.0.opaque_ref).size as usize)) } }
+    }
+
+    /// Attaches a copy of `data` to the packet, replacing any previous one.
+    #[cfg(feature = "ffmpeg_5_0")]
+    pub fn set_opaque_ref<D: AsRef<[u8]>>(&mut self, data: D) {
+        let data = data.as_ref();
+
+        unsafe {
+            let buffer = av_buffer_alloc(data.len() as _);
+
+            if buffer.is_null() {
+                panic!("out of memory");
+            }
+
+            slice::from_raw_parts_mut((*buffer).data, data.len()).copy_from_slice(data);
+
+            av_buffer_unref(&mut self.0.opaque_ref);
+            self.0.opaque_ref = buffer;
+        }
+    }
+
     #[inline]
     pub fn side_data(&self) -> SideDataIter<'_> {
         SideDataIter::new(&self.0)
     }
 
+    /// Tags updated by this packet, from its [`MetadataUpdate`](side_data::Type::MetadataUpdate)
+    /// side data. Demuxers of chained and live streams (Ogg radio streams, for
+    /// one) attach it when the tags change mid-stream, e.g. on a new song.
+    pub fn metadata_update(&self) -> Option<Dictionary<'static>> {
+        self.side_data().find(|data| data.kind() == side_data::Type::MetadataUpdate).and_then(|data| data.dictionary())
+    }
+
+    /// Encoder statistics for the packet: picture type, quality and, with
+    /// [`codec::Flags::PSNR`](crate::codec::Flags::PSNR), the per-plane errors.
+    pub fn quality_stats(&self) -> Option<QualityStats> {
+        self.side_data().find(|data| data.kind() == side_data::Type::QualityStats).and_then(|data| QualityStats::parse(data.data()))
+    }
+
     #[inline]
     pub fn data(&self) -> Option<&[u8]> {
         unsafe { if self.0.data.is_null() { None } else { Some(slice::from_raw_parts(self.0.data, self.0.size as usize)) } }
@@ -196,14 +243,21 @@ impl Packet {
 
     #[inline]
     pub fn read(&mut self, format: &mut format::context::Input) -> Result<(), Error> {
+        format.progressed();
+
         unsafe {
             match av_read_frame(format.as_mut_ptr(), self.as_mut_ptr()) {
-                0 => Ok(()),
+                0 => {
+                    format.packet_read(self);
+                    Ok(())
+                }
                 e => Err(Error::from(e)),
             }
         }
     }
 
+    /// Muxes the packet as is. Fails with `EINVAL` unless the header of `format`
+    /// has been written and the trailer has not.
     #[inline]
     pub fn write(&self, format: &mut format::context::Output) -> Result<bool, Error> {
         unsafe {
@@ -211,6 +265,10 @@ impl Packet {
                 return Err(Error::InvalidData);
             }
 
+            if format.state() != format::context::State::HeaderWritten {
+                return Err(Error::Other { errno: libc::EINVAL });
+            }
+
             match av_write_frame(format.as_mut_ptr(), self.as_ptr() as *mut _) {
                 1 => Ok(true),
                 0 => Ok(false),
@@ -219,6 +277,8 @@ impl Packet {
         }
     }
 
+    /// Muxes the packet through the interleaving queue; the same ordering rules as
+    /// [`write()`](Self::write) apply.
     #[inline]
     pub fn write_interleaved(&self, format: &mut format::context::Output) -> Result<(), Error> {
         unsafe {
@@ -226,6 +286,10 @@ impl Packet {
                 return Err(Error::InvalidData);
             }
 
+            if format.state() != format::context::State::HeaderWritten {
+                return Err(Error::Other { errno: libc::EINVAL });
+            }
+
             match av_interleaved_write_frame(format.as_mut_ptr(), self.as_ptr() as *mut _) {
                 0 => Ok(()),
                 e => Err(Error::from(e)),
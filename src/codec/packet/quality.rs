@@ -0,0 +1,63 @@
+use crate::{ffi::*, picture};
+
+/// Encoder statistics attached to a packet (`AV_PKT_DATA_QUALITY_STATS`).
+///
+/// Encoders such as libx264, libx265, mpeg4 or the MPEG-1/2 encoders attach them
+/// to every packet. The picture type and quality are always present; the per
+/// plane errors are only computed when the encoder was opened with
+/// [`codec::Flags::PSNR`](crate::codec::Flags::PSNR), which costs an extra
+/// comparison of every reconstructed frame with its source.
+#[derive(PartialEq, Clone, Debug)]
+pub struct QualityStats {
+    /// Quality of the frame in lambda units; see [`qp()`](Self::qp).
+    pub quality: i32,
+    /// Picture type the encoder chose for the frame.
+    pub picture_type: picture::Type,
+    /// Sum of squared errors per plane (Y, U, V, ...), empty without the PSNR flag.
+    pub errors: Vec<u64>,
+}
+
+impl QualityStats {
+    /// Parses the payload of the side data. Returns `None` if it is truncated.
+    pub fn parse(data: &[u8]) -> Option<Self> {
+        if data.len() < 6 {
+            return None;
+        }
+
+        let quality = i32::from_le_bytes(data[0..4].try_into().unwrap());
+        let count = data[5] as usize;
+
+        let errors = data.get(8..8 + count * 8)?.chunks_exact(8).map(|error| u64::from_le_bytes(error.try_into().unwrap())).collect();
+
+        Some(QualityStats { quality, picture_type: picture_type(data[4]), errors })
+    }
+
+    /// Quantizer corresponding to [`quality`](Self::quality).
+    pub fn qp(&self) -> f32 {
+        self.quality as f32 / FF_QP2LAMBDA as f32
+    }
+
+    /// PSNR in dB of plane `index` (0 is luma), for 8-bit content.
+    ///
+    /// `samples` is the number of samples in that plane, e.g. `width * height / 4`
+    /// for the chroma planes of 4:2:0 video. Returns `None` if the error was not
+    /// reported, and infinity for a lossless plane.
+    pub fn psnr(&self, index: usize, samples: u64) -> Option<f64> {
+        let error = *self.errors.get(index)?;
+
+        if error == 0 { Some(f64::INFINITY) } else { Some(10.0 * (255.0 * 255.0 * samples as f64 / error as f64).log10()) }
+    }
+}
+
+fn picture_type(value: u8) -> picture::Type {
+    match value {
+        1 => picture::Type::I,
+        2 => picture::Type::P,
+        3 => picture::Type::B,
+        4 => picture::Type::S,
+        5 => picture::Type::SI,
+        6 => picture::Type::SP,
+        7 => picture::Type::BI,
+        _ => picture::Type::None,
+    }
+}
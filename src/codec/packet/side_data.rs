@@ -1,7 +1,10 @@
-use std::{marker::PhantomData, slice};
+use std::{marker::PhantomData, ptr, slice};
 
 use super::Packet;
-use crate::ffi::{AVPacketSideDataType::*, *};
+use crate::{
+    Dictionary,
+    ffi::{AVPacketSideDataType::*, *},
+};
 
 #[derive(Eq, PartialEq, Copy, Clone, Debug)]
 pub enum Type {
@@ -246,4 +249,24 @@ impl<'a> SideData<'a> {
             slice::from_raw_parts((*self.as_ptr()).data, (*self.as_ptr()).size as usize)
         }
     }
+
+    /// Unpacks the key/value pairs of [`Type::MetadataUpdate`] side data (new
+    /// stream or container tags, such as the title of the next song of a radio
+    /// stream) and [`Type::StringsMetadata`] side data (per-packet strings), with
+    /// `av_packet_unpack_dictionary`.
+    ///
+    /// Returns `None` for the other types and for malformed data.
+    pub fn dictionary(&self) -> Option<Dictionary<'static>> {
+        if self.kind() != Type::MetadataUpdate && self.kind() != Type::StringsMetadata {
+            return None;
+        }
+
+        unsafe {
+            let mut dictionary = ptr::null_mut();
+            let res = av_packet_unpack_dictionary((*self.as_ptr()).data, (*self.as_ptr()).size as _, &mut dictionary);
+            let dictionary = Dictionary::own(dictionary);
+
+            if res < 0 { None } else { Some(dictionary) }
+        }
+    }
 }
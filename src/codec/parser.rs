@@ -0,0 +1,127 @@
+use std::{ptr, slice};
+
+use libc::{ENOSYS, c_int};
+
+use super::{Context, Id};
+use crate::{Error, Packet, ffi::*, packet};
+
+/// Splits a raw elementary stream into packets, for input without a container such
+/// as Annex-B H.264/HEVC or ADTS AAC straight from a capture device.
+///
+/// Feed the bytes in chunks of any size with [`parse()`](Self::parse); it returns
+/// the packets that are complete so far, each holding exactly one frame, ready for
+/// [`decoder::Opened::send_packet()`](crate::decoder::Opened::send_packet). At the
+/// end of the input call [`flush()`](Self::flush) to get the frame still buffered.
+///
+/// ```ignore
+/// let mut parser = codec::Parser::new(codec::Id::H264)?;
+/// let mut decoder = codec::Context::new_with_codec(codec).decoder().video()?;
+///
+/// for chunk in chunks {
+///     for packet in parser.parse(&mut decoder, &chunk, None, None)? {
+///         decoder.send_packet(&packet)?;
+///     }
+/// }
+/// ```
+pub struct Parser {
+    ptr: *mut AVCodecParserContext,
+}
+
+unsafe impl Send for Parser {}
+
+impl Parser {
+    pub unsafe fn as_ptr(&self) -> *const AVCodecParserContext {
+        self.ptr as *const _
+    }
+
+    pub unsafe fn as_mut_ptr(&mut self) -> *mut AVCodecParserContext {
+        self.ptr
+    }
+}
+
+impl Parser {
+    /// Creates a parser for `id`. Fails with `ENOSYS` if FFmpeg has no parser for
+    /// that codec (or it was not compiled in).
+    pub fn new(id: Id) -> Result<Self, Error> {
+        unsafe {
+            let ptr = av_parser_init(AVCodecID::from(id) as c_int);
+
+            if ptr.is_null() { Err(Error::Other { errno: ENOSYS }) } else { Ok(Parser { ptr }) }
+        }
+    }
+
+    /// Parses `data` and returns the packets completed by it, in order.
+    ///
+    /// `pts` and `dts` are the timestamps of the chunk, if known (e.g. from the
+    /// capture device); the parser assigns them to the packet in which the chunk
+    /// starts. Packets of streams without timestamps have `None` ones. The codec
+    /// context is used to store what the parser finds out about the stream, such
+    /// as dimensions; pass the decoder the packets are meant for.
+    pub fn parse<C: AsMut<Context>>(&mut self, context: &mut C, data: &[u8], pts: Option<i64>, dts: Option<i64>) -> Result<Vec<Packet>, Error> {
+        let mut packets = Vec::new();
+        let mut offset = 0;
+        let (mut pts, mut dts) = (pts.unwrap_or(AV_NOPTS_VALUE), dts.unwrap_or(AV_NOPTS_VALUE));
+
+        while offset < data.len() {
+            let (consumed, packet) = self.parse_once(context.as_mut(), &data[offset..], pts, dts)?;
+
+            // the timestamps belong to the start of the chunk only
+            pts = AV_NOPTS_VALUE;
+            dts = AV_NOPTS_VALUE;
+
+            offset += consumed;
+
+            match packet {
+                Some(packet) => packets.push(packet),
+                None if consumed == 0 => break,
+                None => (),
+            }
+        }
+
+        Ok(packets)
+    }
+
+    /// Signals the end of the input and returns the last buffered packet, if any.
+    pub fn flush<C: AsMut<Context>>(&mut self, context: &mut C) -> Result<Option<Packet>, Error> {
+        self.parse_once(context.as_mut(), &[], AV_NOPTS_VALUE, AV_NOPTS_VALUE).map(|(_, packet)| packet)
+    }
+
+    fn parse_once(&mut self, context: &mut Context, data: &[u8], pts: i64, dts: i64) -> Result<(usize, Option<Packet>), Error> {
+        unsafe {
+            let mut out = ptr::null_mut();
+            let mut size: c_int = 0;
+
+            let input = if data.is_empty() { ptr::null() } else { data.as_ptr() };
+
+            let consumed = av_parser_parse2(self.as_mut_ptr(), context.as_mut_ptr(), &mut out, &mut size, input, data.len() as c_int, pts, dts, 0);
+
+            if consumed < 0 {
+                return Err(Error::from(consumed));
+            }
+
+            if size == 0 {
+                return Ok((consumed as usize, None));
+            }
+
+            let mut packet = Packet::copy(slice::from_raw_parts(out, size as usize));
+            let parser = self.as_ptr();
+
+            packet.set_pts(if (*parser).pts == AV_NOPTS_VALUE { None } else { Some((*parser).pts) });
+            packet.set_dts(if (*parser).dts == AV_NOPTS_VALUE { None } else { Some((*parser).dts) });
+
+            if (*parser).key_frame == 1 {
+                packet.set_flags(packet::Flags::KEY);
+            }
+
+            Ok((consumed as usize, Some(packet)))
+        }
+    }
+}
+
+impl Drop for Parser {
+    fn drop(&mut self) {
+        unsafe {
+            av_parser_close(self.as_mut_ptr());
+        }
+    }
+}
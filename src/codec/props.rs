@@ -0,0 +1,16 @@
+use crate::ffi::*;
+use libc::c_int;
+
+bitflags! {
+    #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+    pub struct Props: c_int {
+        const INTRA_ONLY = AV_CODEC_PROP_INTRA_ONLY as c_int;
+        const LOSSY      = AV_CODEC_PROP_LOSSY as c_int;
+        const LOSSLESS   = AV_CODEC_PROP_LOSSLESS as c_int;
+        const REORDER    = AV_CODEC_PROP_REORDER as c_int;
+        #[cfg(feature = "ffmpeg_6_1")]
+        const FIELDS     = AV_CODEC_PROP_FIELDS as c_int;
+        const BITMAP_SUB = AV_CODEC_PROP_BITMAP_SUB as c_int;
+        const TEXT_SUB   = AV_CODEC_PROP_TEXT_SUB as c_int;
+    }
+}
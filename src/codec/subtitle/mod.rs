@@ -9,7 +9,10 @@ pub use self::rect_mut::{AssMut, BitmapMut, RectMut, TextMut};
 
 use std::{marker::PhantomData, mem};
 
-use crate::ffi::{AVSubtitleType::*, *};
+use crate::{
+    Rational, Rescale,
+    ffi::{AVSubtitleType::*, *},
+};
 use libc::{c_uint, size_t};
 
 #[derive(Eq, PartialEq, Clone, Copy, Debug)]
@@ -42,6 +45,12 @@ impl From<Type> for AVSubtitleType {
     }
 }
 
+/// A decoded subtitle.
+///
+/// Timing is split in two parts: [`pts()`](Self::pts) is the presentation timestamp of
+/// the subtitle (in `AV_TIME_BASE` units when produced by a decoder), while
+/// [`start()`](Self::start) and [`end()`](Self::end) are display times in
+/// **milliseconds relative to `pts`**, not absolute timestamps.
 pub struct Subtitle(AVSubtitle);
 
 impl Subtitle {
@@ -86,6 +95,25 @@ impl Subtitle {
         self.0.end_display_time = value;
     }
 
+    /// Rescales the subtitle timing from time base `from` to time base `to`.
+    ///
+    /// The start display offset is folded into `pts` (rescaled from milliseconds
+    /// directly to `to`, so no precision is lost to millisecond rounding), `start()`
+    /// becomes 0 and `end()` becomes the display duration in milliseconds. This matches
+    /// what subtitle encoders expect. Nothing is changed when `pts` is unset.
+    pub fn rescale_timing<F: Into<Rational>, T: Into<Rational>>(&mut self, from: F, to: T) {
+        let Some(pts) = self.pts() else {
+            return;
+        };
+
+        let to = to.into();
+        let offset = i64::from(self.0.start_display_time).rescale((1, 1000), to);
+
+        self.0.pts = pts.rescale(from, to) + offset;
+        self.0.end_display_time = self.0.end_display_time.saturating_sub(self.0.start_display_time);
+        self.0.start_display_time = 0;
+    }
+
     pub fn rects(&self) -> RectIter<'_> {
         RectIter::new(&self.0)
     }
@@ -95,16 +123,67 @@ impl Subtitle {
     }
 
     pub fn add_rect(&mut self, kind: Type) -> RectMut<'_> {
+        unsafe { RectMut::wrap(self.push_rect(kind)) }
+    }
+
+    /// Appends a plain text rect.
+    ///
+    /// Most subtitle encoders (SubRip, ASS, WebVTT, mov_text) only serialize ASS
+    /// rects; use [`add_ass_rect()`](Self::add_ass_rect) for those.
+    pub fn add_text_rect(&mut self, text: &str) -> TextMut<'_> {
         unsafe {
-            self.0.num_rects += 1;
-            self.0.rects = av_realloc(self.0.rects as *mut _, (mem::size_of::<*const AVSubtitleRect>() * self.0.num_rects as usize) as size_t) as *mut _;
+            let mut rect = TextMut::wrap(self.push_rect(Type::Text));
+            rect.set(text);
+
+            rect
+        }
+    }
+
+    /// Appends an ASS rect holding one event, in the format decoders produce and
+    /// encoders expect: `ReadOrder,Layer,Style,Name,MarginL,MarginR,MarginV,Effect,Text`,
+    /// i.e. a `Dialogue:` line without its start and end times, which are taken from
+    /// the subtitle instead:
+    ///
+    /// ```ignore
+    /// let mut subtitle = Subtitle::new();
+    /// subtitle.set_pts(Some(pts));
+    /// subtitle.set_start(0);
+    /// subtitle.set_end(2500);
+    /// subtitle.add_ass_rect("0,0,Default,,0,0,0,,Hello\\Nworld");
+    /// ```
+    pub fn add_ass_rect(&mut self, ass: &str) -> AssMut<'_> {
+        unsafe {
+            let mut rect = AssMut::wrap(self.push_rect(Type::Ass));
+            rect.set(ass);
+
+            rect
+        }
+    }
+
+    // The rect array and the rects are allocated with the av_malloc family, so
+    // `avsubtitle_free` releases them in `drop`.
+    unsafe fn push_rect(&mut self, kind: Type) -> *mut AVSubtitleRect {
+        unsafe {
+            let rects = av_realloc(self.0.rects as *mut _, (mem::size_of::<*const AVSubtitleRect>() * (self.0.num_rects as usize + 1)) as size_t) as *mut *mut AVSubtitleRect;
+
+            if rects.is_null() {
+                panic!("out of memory");
+            }
+
+            self.0.rects = rects;
 
             let rect = av_mallocz(mem::size_of::<AVSubtitleRect>() as size_t) as *mut AVSubtitleRect;
+
+            if rect.is_null() {
+                panic!("out of memory");
+            }
+
             (*rect).type_ = kind.into();
 
-            *self.0.rects.offset((self.0.num_rects - 1) as isize) = rect;
+            *self.0.rects.offset(self.0.num_rects as isize) = rect;
+            self.0.num_rects += 1;
 
-            RectMut::wrap(rect)
+            rect
         }
     }
 }
@@ -115,6 +194,14 @@ impl Default for Subtitle {
     }
 }
 
+impl Drop for Subtitle {
+    fn drop(&mut self) {
+        unsafe {
+            avsubtitle_free(&mut self.0);
+        }
+    }
+}
+
 pub struct RectIter<'a> {
     ptr: *const AVSubtitle,
     cur: c_uint,
@@ -126,6 +126,7 @@ impl<'a> TextMut<'a> {
         let value = CString::new(value).unwrap();
 
         unsafe {
+            av_freep(&mut (*self.as_mut_ptr()).text as *mut *mut _ as *mut libc::c_void);
             (*self.as_mut_ptr()).text = av_strdup(value.as_ptr());
         }
     }
@@ -158,6 +159,7 @@ impl<'a> AssMut<'a> {
         let value = CString::new(value).unwrap();
 
         unsafe {
+            av_freep(&mut (*self.as_mut_ptr()).ass as *mut *mut _ as *mut libc::c_void);
             (*self.as_mut_ptr()).ass = av_strdup(value.as_ptr());
         }
     }
@@ -0,0 +1,302 @@
+//! Capabilities advertised by capture devices.
+//!
+//! Before FFmpeg 6.0 the devices are asked through the capabilities query API
+//! (`avdevice_capabilities_create`), which FFmpeg 6.0 removed. From then on, the
+//! settings are read from the listing the device prints when opened with its list
+//! option, which `v4l2` (`list_formats`) and `dshow` (`list_options`) support.
+
+use std::{ffi::CString, ptr};
+
+#[cfg(feature = "ffmpeg_6_0")]
+use crate::{Dictionary, log};
+use crate::{Error, codec, ffi::*, format};
+#[cfg(not(feature = "ffmpeg_6_0"))]
+use {crate::error::ENOSYS, libc::c_int, libc::c_void};
+
+/// One combination of settings a device supports.
+///
+/// Ranges are inclusive `(min, max)` pairs; a device with a fixed value reports
+/// `min == max`, and one that does not advertise a setting reports `(0, 0)`.
+#[derive(PartialEq, Clone, Debug)]
+pub struct Capability {
+    pub codec: Option<codec::Id>,
+    pub format: Option<format::Pixel>,
+    pub width: (u32, u32),
+    pub height: (u32, u32),
+    pub frame_rate: (f64, f64),
+}
+
+/// Queries the settings supported by a capture device.
+///
+/// `format_name` is the input device format (e.g. `"v4l2"`) and `device_name` the
+/// device to open (e.g. `"/dev/video0"`). The device is opened for the duration of
+/// the query. One entry is returned for every combination the device supports:
+/// codec and pixel format, frame size, and frame rate range.
+///
+/// Few devices can be queried; for the others the result is empty. From FFmpeg
+/// 6.0 on these are `v4l2`, which does not report frame rates, and `dshow`.
+pub fn capabilities(format_name: &str, device_name: &str) -> Result<Vec<Capability>, Error> {
+    unsafe {
+        let name = CString::new(format_name).unwrap();
+        let device_name = CString::new(device_name).unwrap();
+
+        let format = av_find_input_format(name.as_ptr());
+
+        if format.is_null() {
+            return Err(Error::DemuxerNotFound);
+        }
+
+        query(format, format_name, &device_name)
+    }
+}
+
+#[cfg(not(feature = "ffmpeg_6_0"))]
+unsafe fn query(format: *const AVInputFormat, _format_name: &str, device_name: &CString) -> Result<Vec<Capability>, Error> {
+    unsafe {
+        let mut context = ptr::null_mut();
+
+        match avformat_open_input(&mut context, device_name.as_ptr(), format as _, ptr::null_mut()) {
+            0 => (),
+            e => return Err(Error::from(e)),
+        }
+
+        let mut caps = ptr::null_mut();
+
+        match avdevice_capabilities_create(&mut caps, context, ptr::null_mut()) {
+            0 => (),
+            e => {
+                avformat_close_input(&mut context);
+                return if e == AVERROR(ENOSYS) { Ok(Vec::new()) } else { Err(Error::from(e)) };
+            }
+        }
+
+        let query = caps as *mut c_void;
+        let mut capabilities = Vec::new();
+
+        // Setting a value narrows the ranges of the others to the ones the device
+        // supports with it.
+        let narrow = |values: &[(&str, Option<f64>)]| {
+            av_opt_set_defaults(query);
+
+            for &(key, value) in values {
+                if let Some(value) = value {
+                    av_opt_set_int(query, CString::new(key).unwrap().as_ptr(), value as i64, 0);
+                }
+            }
+        };
+
+        for codec in advertised(query, "codec") {
+            let codec = codec.map(|(value, _)| value);
+            narrow(&[("codec", codec)]);
+
+            for pixel in advertised(query, "pixel_format") {
+                let pixel = pixel.map(|(value, _)| value);
+                narrow(&[("codec", codec), ("pixel_format", pixel)]);
+
+                let widths = ranges(query, "frame_width");
+                let heights = ranges(query, "frame_height");
+
+                // Width and height ranges come in pairs, one per frame size.
+                for size in 0..widths.len().max(heights.len()).max(1) {
+                    let width = widths.get(size).copied().unwrap_or((0.0, 0.0));
+                    let height = heights.get(size).copied().unwrap_or((0.0, 0.0));
+
+                    if width.0 > 0.0 && width.0 == width.1 && height.0 > 0.0 && height.0 == height.1 {
+                        narrow(&[("codec", codec), ("pixel_format", pixel), ("frame_width", Some(width.0)), ("frame_height", Some(height.0))]);
+                    }
+
+                    for frame_rate in advertised(query, "fps") {
+                        capabilities.push(Capability {
+                            codec: codec.and_then(|value| codec_id(value as c_int)),
+                            format: pixel.and_then(|value| pixel_format(value as c_int)),
+                            width: (width.0 as u32, width.1 as u32),
+                            height: (height.0 as u32, height.1 as u32),
+                            frame_rate: frame_rate.unwrap_or((0.0, 0.0)),
+                        });
+                    }
+                }
+            }
+        }
+
+        avdevice_capabilities_free(&mut caps, context);
+        avformat_close_input(&mut context);
+
+        Ok(capabilities)
+    }
+}
+
+// The ranges of `key`, or a single `None` when the device does not advertise it.
+#[cfg(not(feature = "ffmpeg_6_0"))]
+unsafe fn advertised(query: *mut c_void, key: &str) -> Vec<Option<(f64, f64)>> {
+    let ranges = unsafe { ranges(query, key) };
+
+    if ranges.is_empty() { vec![None] } else { ranges.into_iter().map(Some).collect() }
+}
+
+#[cfg(not(feature = "ffmpeg_6_0"))]
+unsafe fn ranges(query: *mut c_void, key: &str) -> Vec<(f64, f64)> {
+    unsafe {
+        let key = CString::new(key).unwrap();
+        let mut ranges = ptr::null_mut();
+
+        if av_opt_query_ranges(&mut ranges, query, key.as_ptr(), 0) < 0 {
+            return Vec::new();
+        }
+
+        let values = (0..(*ranges).nb_ranges as usize)
+            .map(|i| {
+                let range = *(*ranges).range.add(i);
+                ((*range).value_min, (*range).value_max)
+            })
+            .collect();
+
+        av_opt_freep_ranges(&mut ranges);
+
+        values
+    }
+}
+
+// Looked up rather than transmuted, devices may report values this build does
+// not know.
+#[cfg(not(feature = "ffmpeg_6_0"))]
+fn codec_id(value: c_int) -> Option<codec::Id> {
+    codec::descriptors().find(|descriptor| unsafe { (*descriptor.as_ptr()).id as c_int } == value).map(|descriptor| descriptor.id())
+}
+
+#[cfg(not(feature = "ffmpeg_6_0"))]
+fn pixel_format(value: c_int) -> Option<format::Pixel> {
+    unsafe {
+        let mut descriptor = av_pix_fmt_desc_next(ptr::null());
+
+        while !descriptor.is_null() {
+            let format = av_pix_fmt_desc_get_id(descriptor);
+
+            if format as c_int == value {
+                return Some(format::Pixel::from(format));
+            }
+
+            descriptor = av_pix_fmt_desc_next(descriptor);
+        }
+
+        None
+    }
+}
+
+#[cfg(feature = "ffmpeg_6_0")]
+unsafe fn query(format: *const AVInputFormat, format_name: &str, device_name: &CString) -> Result<Vec<Capability>, Error> {
+    let (option, value, parse): (&str, &str, fn(&str) -> Vec<Capability>) = match format_name {
+        "v4l2" | "video4linux2" => ("list_formats", "all", parse_v4l2),
+        "dshow" => ("list_options", "true", parse_dshow),
+        _ => return Ok(Vec::new()),
+    };
+
+    let mut options = Dictionary::new();
+    options.set(option, value);
+
+    unsafe {
+        let mut context = ptr::null_mut();
+        let mut opts = options.disown();
+        let (res, lines) = log::capture(log::Level::Info, || avformat_open_input(&mut context, device_name.as_ptr(), format, &mut opts));
+
+        Dictionary::own(opts);
+
+        match res {
+            // The device exits once it printed the listing.
+            AVERROR_EXIT => (),
+            0 => avformat_close_input(&mut context),
+            e => return Err(Error::from(e)),
+        }
+
+        Ok(lines.iter().flat_map(|line| parse(line)).collect())
+    }
+}
+
+// `Raw       :     yuyv422 :           YUYV 4:2:2 : 640x480 320x240`, or
+// `Compressed:       mjpeg :          Motion-JPEG : {32-4096, 2}x{32-2304, 2}`
+// for a stepwise size; frame rates are not listed.
+#[cfg(feature = "ffmpeg_6_0")]
+fn parse_v4l2(line: &str) -> Vec<Capability> {
+    let Some((kind, rest)) = line.split_once(':') else {
+        return Vec::new();
+    };
+
+    let name = rest.split(" : ").next().unwrap_or("").trim();
+
+    let (codec, format) = match kind.trim() {
+        "Raw" => (Some(codec::Id::RAWVIDEO), name.parse::<format::Pixel>().ok()),
+        "Compressed" => (codec_by_name(name), None),
+        _ => return Vec::new(),
+    };
+
+    if codec.is_none() || (kind.trim() == "Raw" && format.is_none()) {
+        return Vec::new();
+    }
+
+    // The descriptions may contain colons, the sizes come after the last one.
+    let sizes = rest.rsplit(':').next().unwrap_or("").trim();
+    let capability = |width, height| Capability { codec, format, width, height, frame_rate: (0.0, 0.0) };
+
+    if let Some((width, height)) = sizes.split_once("}x{") {
+        let range = |range: &str| {
+            let range = range.trim_matches(|c| c == '{' || c == '}').split(',').next()?;
+            let (min, max) = range.split_once('-')?;
+
+            Some((min.trim().parse().ok()?, max.trim().parse().ok()?))
+        };
+
+        return match (range(width), range(height)) {
+            (Some(width), Some(height)) => vec![capability(width, height)],
+            _ => Vec::new(),
+        };
+    }
+
+    let sizes = sizes
+        .split_whitespace()
+        .filter_map(|size| {
+            let (width, height) = size.split_once('x')?;
+            Some((width.parse::<u32>().ok()?, height.parse::<u32>().ok()?))
+        })
+        .map(|(width, height)| capability((width, width), (height, height)))
+        .collect::<Vec<_>>();
+
+    if sizes.is_empty() { vec![capability((0, 0), (0, 0))] } else { sizes }
+}
+
+// `  pixel_format=yuyv422  min s=640x480 fps=5 max s=640x480 fps=30`, or
+// `  vcodec=mjpeg  min s=...` for compressed formats.
+#[cfg(feature = "ffmpeg_6_0")]
+fn parse_dshow(line: &str) -> Vec<Capability> {
+    let mut codec = None;
+    let mut format = None;
+    let mut sizes = Vec::new();
+    let mut rates = Vec::new();
+
+    for token in line.split_whitespace() {
+        if let Some(name) = token.strip_prefix("pixel_format=") {
+            codec = Some(codec::Id::RAWVIDEO);
+            format = name.parse::<format::Pixel>().ok();
+        } else if let Some(name) = token.strip_prefix("vcodec=") {
+            codec = codec_by_name(name);
+        } else if let Some((width, height)) = token.strip_prefix("s=").and_then(|size| size.split_once('x')) {
+            sizes.extend(width.parse::<u32>().ok().zip(height.parse::<u32>().ok()));
+        } else if let Some(rate) = token.strip_prefix("fps=") {
+            rates.extend(rate.parse::<f64>().ok());
+        }
+    }
+
+    match (codec, &sizes[..], &rates[..]) {
+        (Some(_), &[min_size, max_size], &[min_rate, max_rate]) => vec![Capability { codec, format, width: (min_size.0, max_size.0), height: (min_size.1, max_size.1), frame_rate: (min_rate, max_rate) }],
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(feature = "ffmpeg_6_0")]
+fn codec_by_name(name: &str) -> Option<codec::Id> {
+    let name = CString::new(name).ok()?;
+
+    unsafe {
+        let descriptor = avcodec_descriptor_get_by_name(name.as_ptr());
+
+        if descriptor.is_null() { None } else { Some(codec::Id::from((*descriptor).id)) }
+    }
+}
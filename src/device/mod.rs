@@ -15,6 +15,7 @@
 //! - [`input`] - Input devices (capture)
 //! - [`output`] - Output devices (playback)
 //! - [`extensions`] - Device-specific extensions
+//! - [`mod@capabilities`] - Supported settings of capture devices
 //!
 //! # Platform Support
 //!
@@ -23,10 +24,13 @@
 //! - **Windows**: dshow (DirectShow), gdigrab (screen capture)
 //! - **macOS**: avfoundation (video/audio), screencapture
 
+pub mod capabilities;
 pub mod extensions;
 pub mod input;
 pub mod output;
 
+pub use self::capabilities::{Capability, capabilities};
+
 use std::{ffi::CStr, marker::PhantomData, str::from_utf8_unchecked};
 
 use crate::ffi::*;
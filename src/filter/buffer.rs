@@ -0,0 +1,63 @@
+//! Typed parameters for the `buffer` and `abuffer` source filters.
+//!
+//! The source filters are usually configured with an argument string such as
+//! `"video_size=1920x1080:pix_fmt=0:time_base=1/25"`, where a typo is silently ignored
+//! or only reported when the graph is configured. The structs here are applied through
+//! `av_buffersrc_parameters_set` instead; see [`Graph::add_video_source()`] and
+//! [`Graph::add_audio_source()`].
+//!
+//! [`Graph::add_video_source()`]: super::Graph::add_video_source
+//! [`Graph::add_audio_source()`]: super::Graph::add_audio_source
+
+use crate::{ChannelLayout, Rational, format};
+
+/// Parameters of a video buffer source (`buffer` filter).
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct Video {
+    pub format: format::Pixel,
+    pub width: u32,
+    pub height: u32,
+    /// Time base of the timestamps of the frames that will be pushed.
+    pub time_base: Rational,
+    /// Frame rate of the input; required by filters such as `fps` for constant rate input.
+    pub frame_rate: Option<Rational>,
+    pub aspect_ratio: Rational,
+}
+
+impl Video {
+    pub fn new(format: format::Pixel, width: u32, height: u32, time_base: Rational) -> Self {
+        Video { format, width, height, time_base, frame_rate: None, aspect_ratio: Rational(1, 1) }
+    }
+
+    pub fn frame_rate(mut self, value: Rational) -> Self {
+        self.frame_rate = Some(value);
+        self
+    }
+
+    pub fn aspect_ratio(mut self, value: Rational) -> Self {
+        self.aspect_ratio = value;
+        self
+    }
+}
+
+/// Parameters of an audio buffer source (`abuffer` filter).
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct Audio {
+    pub format: format::Sample,
+    pub rate: u32,
+    pub channel_layout: ChannelLayout,
+    /// Time base of the timestamps of the frames that will be pushed; usually `1/rate`.
+    pub time_base: Rational,
+}
+
+impl Audio {
+    /// Creates parameters with a `1/rate` time base.
+    pub fn new(format: format::Sample, rate: u32, channel_layout: ChannelLayout) -> Self {
+        Audio { format, rate, channel_layout, time_base: Rational(1, rate as i32) }
+    }
+
+    pub fn time_base(mut self, value: Rational) -> Self {
+        self.time_base = value;
+        self
+    }
+}
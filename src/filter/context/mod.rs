@@ -2,7 +2,7 @@ mod context;
 pub use self::context::Context;
 
 mod source;
-pub use self::source::Source;
+pub use self::source::{Mismatch, Source};
 
 mod sink;
 pub use self::sink::Sink;
@@ -1,5 +1,7 @@
 use super::Context;
-use crate::{Error, Frame, Rational, ffi::*};
+#[cfg(feature = "ffmpeg_7_0")]
+use crate::color;
+use crate::{Error, Frame, Rational, error::EAGAIN, ffi::*, format, frame, option::Settable};
 use libc::c_int;
 
 pub struct Sink<'a> {
@@ -22,6 +24,43 @@ impl<'a> Sink<'a> {
         }
     }
 
+    /// Takes every video frame the sink has ready, e.g. after adding a frame to
+    /// the source of a graph whose filters output zero or several frames per
+    /// input. Stops at `EAGAIN` and at the end of the stream, which are not
+    /// errors here.
+    pub fn drain_video(&mut self) -> Result<Vec<frame::Video>, Error> {
+        let mut frames = Vec::new();
+
+        loop {
+            let mut frame = frame::Video::empty();
+
+            match self.frame(&mut frame) {
+                Ok(()) => frames.push(frame),
+                Err(Error::Eof) | Err(Error::Other { errno: EAGAIN }) => return Ok(frames),
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Returns the next frame without removing it from the sink.
+    ///
+    /// `frame` receives a new reference to the buffered frame; the next call to
+    /// [`frame()`](Self::frame) produces that same frame again. Returns
+    /// `Error::Other { errno: EAGAIN }` when nothing is buffered.
+    pub fn peek(&mut self, frame: &mut Frame) -> Result<(), Error> {
+        unsafe {
+            match av_buffersink_get_frame_flags(self.ctx.as_mut_ptr(), frame.as_mut_ptr(), AV_BUFFERSINK_FLAG_PEEK as c_int) {
+                n if n >= 0 => Ok(()),
+                e => Err(Error::from(e)),
+            }
+        }
+    }
+
+    /// Pulls exactly `samples` audio samples per channel into `frame`.
+    ///
+    /// Useful to feed encoders with a fixed frame size without an intermediate FIFO.
+    /// Only the last frame before EOF may contain fewer samples. Must not be mixed
+    /// with [`frame()`](Self::frame) on the same sink.
     pub fn samples(&mut self, frame: &mut Frame, samples: usize) -> Result<(), Error> {
         unsafe {
             match av_buffersink_get_samples(self.ctx.as_mut_ptr(), frame.as_mut_ptr(), samples as c_int) {
@@ -37,7 +76,74 @@ impl<'a> Sink<'a> {
         }
     }
 
+    /// Restricts the pixel formats the sink accepts, so that format negotiation
+    /// makes the graph output one of `formats` (inserting a conversion if needed).
+    ///
+    /// Like the other constraints, this has to be set before the graph is
+    /// configured with [`Graph::validate()`](crate::filter::Graph::validate); later
+    /// changes have no effect.
+    pub fn set_pixel_formats(&mut self, formats: &[format::Pixel]) -> Result<(), Error> {
+        #[cfg(not(feature = "ffmpeg_8_0"))]
+        {
+            let formats = formats.iter().map(|&format| AVPixelFormat::from(format) as c_int).collect::<Vec<_>>();
+            self.ctx.set_bin("pix_fmts", &int_list(&formats))
+        }
+
+        #[cfg(feature = "ffmpeg_8_0")]
+        {
+            let names = formats.iter().filter_map(|format| format.descriptor()).map(|descriptor| descriptor.name()).collect::<Vec<_>>();
+            self.ctx.set_str("pixel_formats", &names.join("|"))
+        }
+    }
+
+    /// Restricts the color spaces (YUV matrices) of the graph output. Must be set
+    /// before the graph is configured.
+    #[cfg(feature = "ffmpeg_7_0")]
+    pub fn set_color_spaces(&mut self, spaces: &[color::Space]) -> Result<(), Error> {
+        let spaces = spaces.iter().map(|&space| AVColorSpace::from(space) as c_int).collect::<Vec<_>>();
+
+        #[cfg(not(feature = "ffmpeg_8_0"))]
+        {
+            self.ctx.set_bin("color_spaces", &int_list(&spaces))
+        }
+
+        #[cfg(feature = "ffmpeg_8_0")]
+        {
+            self.ctx.set_str("colorspaces", &array(&spaces))
+        }
+    }
+
+    /// Restricts the color ranges of the graph output. Must be set before the graph
+    /// is configured.
+    #[cfg(feature = "ffmpeg_7_0")]
+    pub fn set_color_ranges(&mut self, ranges: &[color::Range]) -> Result<(), Error> {
+        let ranges = ranges.iter().map(|&range| AVColorRange::from(range) as c_int).collect::<Vec<_>>();
+
+        #[cfg(not(feature = "ffmpeg_8_0"))]
+        {
+            self.ctx.set_bin("color_ranges", &int_list(&ranges))
+        }
+
+        #[cfg(feature = "ffmpeg_8_0")]
+        {
+            self.ctx.set_str("colorranges", &array(&ranges))
+        }
+    }
+
     pub fn time_base(&self) -> Rational {
         unsafe { av_buffersink_get_time_base(self.ctx.as_ptr()) }.into()
     }
 }
+
+// In-memory representation of an int list for the binary list options of older
+// FFmpeg versions.
+#[cfg(not(feature = "ffmpeg_8_0"))]
+fn int_list(values: &[c_int]) -> Vec<u8> {
+    values.iter().flat_map(|value| value.to_ne_bytes()).collect()
+}
+
+// String form of an array option (FFmpeg 7.1+).
+#[cfg(feature = "ffmpeg_8_0")]
+fn array(values: &[c_int]) -> String {
+    values.iter().map(|value| value.to_string()).collect::<Vec<_>>().join("|")
+}
@@ -1,7 +1,43 @@
-use std::ptr;
+use std::{error, fmt, mem, ptr};
 
 use super::Context;
-use crate::{Error, Frame, ffi::*};
+use crate::{Error, Frame, ffi::*, format};
+use libc::EINVAL;
+
+/// Why a frame does not match the parameters of a buffer source.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Mismatch {
+    /// The graph is not configured yet, so the source parameters are unknown.
+    Unconfigured,
+    /// A video frame differs in pixel format or dimensions, given as
+    /// `(format, width, height)`.
+    Video { expected: (format::Pixel, u32, u32), got: (format::Pixel, u32, u32) },
+    /// An audio frame differs in sample format, sample rate or channel count,
+    /// given as `(format, rate, channels)`.
+    Audio { expected: (format::Sample, u32, u16), got: (format::Sample, u32, u16) },
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Mismatch::Unconfigured => f.write_str("buffer source parameters are unknown, configure the filter graph first"),
+            Mismatch::Video { expected: (format, width, height), got: (got_format, got_width, got_height) } => {
+                write!(f, "expected {} {width}x{height}, got {} {got_width}x{got_height}", format.name(), got_format.name())
+            }
+            Mismatch::Audio { expected: (format, rate, channels), got: (got_format, got_rate, got_channels) } => {
+                write!(f, "expected {} {rate} Hz {channels} channels, got {} {got_rate} Hz {got_channels} channels", format.name(), got_format.name())
+            }
+        }
+    }
+}
+
+impl error::Error for Mismatch {}
+
+impl From<Mismatch> for Error {
+    fn from(_: Mismatch) -> Error {
+        Error::Other { errno: EINVAL }
+    }
+}
 
 pub struct Source<'a> {
     ctx: &'a mut Context,
@@ -18,6 +54,56 @@ impl<'a> Source<'a> {
         unsafe { av_buffersrc_get_nb_failed_requests(self.ctx.as_ptr() as *mut _) as usize }
     }
 
+    /// Checks that `frame` has the format, dimensions or sample rate and channel
+    /// count this source was configured with, before [`add()`](Self::add).
+    ///
+    /// Buffer sources take frames with other properties without complaint, and
+    /// the filters downstream then fail or produce garbage. Checking here points
+    /// at the frame instead; the [`Mismatch`] describes both sides, e.g.
+    /// `expected yuv420p 1920x1080, got nv12 1280x720`, and converts into an
+    /// `EINVAL` [`Error`] with `?`. The graph must be configured.
+    pub fn accepts(&self, frame: &Frame) -> Result<(), Mismatch> {
+        unsafe {
+            let context = self.ctx.as_ptr();
+
+            if (*context).nb_outputs == 0 || (*(*context).outputs).is_null() || (**(*context).outputs).format < 0 {
+                return Err(Mismatch::Unconfigured);
+            }
+
+            let link = *(*context).outputs;
+            let frame = frame.as_ptr();
+
+            match (*link).type_ {
+                AVMediaType::AVMEDIA_TYPE_VIDEO => {
+                    let expected = (format::Pixel::from(mem::transmute::<i32, AVPixelFormat>((*link).format)), (*link).w as u32, (*link).h as u32);
+                    let got = (format::Pixel::from(mem::transmute::<i32, AVPixelFormat>((*frame).format)), (*frame).width as u32, (*frame).height as u32);
+
+                    if expected != got {
+                        return Err(Mismatch::Video { expected, got });
+                    }
+                }
+
+                AVMediaType::AVMEDIA_TYPE_AUDIO => {
+                    #[cfg(not(feature = "ffmpeg_7_0"))]
+                    let (expected_channels, got_channels) = ((*link).channels, (*frame).channels);
+                    #[cfg(feature = "ffmpeg_7_0")]
+                    let (expected_channels, got_channels) = ((*link).ch_layout.nb_channels, (*frame).ch_layout.nb_channels);
+
+                    let expected = (format::Sample::from(mem::transmute::<i32, AVSampleFormat>((*link).format)), (*link).sample_rate as u32, expected_channels as u16);
+                    let got = (format::Sample::from(mem::transmute::<i32, AVSampleFormat>((*frame).format)), (*frame).sample_rate as u32, got_channels as u16);
+
+                    if expected != got {
+                        return Err(Mismatch::Audio { expected, got });
+                    }
+                }
+
+                _ => (),
+            }
+        }
+
+        Ok(())
+    }
+
     pub fn add(&mut self, frame: &Frame) -> Result<(), Error> {
         unsafe {
             match av_buffersrc_add_frame(self.ctx.as_mut_ptr(), frame.as_ptr() as *mut _) {
@@ -0,0 +1,100 @@
+use super::{Context, Graph, buffer};
+use crate::{Error, Rational, frame};
+
+/// Deinterlacing filter used by [`Deinterlacer`].
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Method {
+    /// `yadif`: fast, good quality.
+    Yadif,
+    /// `bwdif`: motion adaptive, sharper than `yadif` at a slightly higher cost.
+    Bwdif,
+}
+
+impl Method {
+    fn name(&self) -> &'static str {
+        match *self {
+            Method::Yadif => "yadif",
+            Method::Bwdif => "bwdif",
+        }
+    }
+}
+
+/// What the [`Deinterlacer`] outputs for each interlaced frame.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Mode {
+    /// One frame per frame, the frame rate is unchanged.
+    Frame,
+    /// One frame per field: every interlaced input frame produces two output frames,
+    /// which **doubles the frame rate** (e.g. 25i becomes 50p).
+    Field,
+}
+
+/// Deinterlaces video frames through a `buffer` → `yadif`/`bwdif` → `buffersink` graph.
+///
+/// Only frames flagged as interlaced are deinterlaced, progressive frames pass
+/// through unchanged, so mixed content can be fed as is. The filters need to look at
+/// neighbouring frames: the first call usually returns nothing and
+/// [`flush()`](Self::flush) returns the last frames at end of stream.
+///
+/// # Example
+///
+/// ```ignore
+/// let parameters = filter::buffer::Video::new(decoder.format(), decoder.width(), decoder.height(), stream.time_base());
+/// let mut deinterlacer = Deinterlacer::new(&parameters, Method::Bwdif, Mode::Field)?;
+///
+/// for frame in deinterlacer.process(&decoded)? {
+///     // timestamps are in deinterlacer.time_base()
+/// }
+/// ```
+pub struct Deinterlacer {
+    graph: Graph,
+    source: Context,
+    sink: Context,
+}
+
+unsafe impl Send for Deinterlacer {}
+
+impl Deinterlacer {
+    /// Creates a deinterlacer for frames described by `parameters`.
+    pub fn new(parameters: &buffer::Video, method: Method, mode: Mode) -> Result<Self, Error> {
+        let mut graph = Graph::new();
+
+        let args = match mode {
+            Mode::Frame => "mode=send_frame:parity=auto:deint=interlaced",
+            Mode::Field => "mode=send_field:parity=auto:deint=interlaced",
+        };
+
+        let mut source = graph.add_video_source("in", parameters)?;
+        let mut filter = graph.add(&super::find(method.name()).ok_or(Error::FilterNotFound)?, "deinterlace", args)?;
+        let mut sink = graph.add(&super::find("buffersink").ok_or(Error::FilterNotFound)?, "out", "")?;
+
+        source.link(0, &mut filter, 0);
+        filter.link(0, &mut sink, 0);
+
+        graph.validate()?;
+
+        Ok(Deinterlacer { graph, source, sink })
+    }
+
+    /// Time base of the output frames. In field mode it is finer than the input one.
+    pub fn time_base(&mut self) -> Rational {
+        self.sink.sink().time_base()
+    }
+
+    /// Feeds a frame and returns the frames that are ready (zero, one, or two in field mode).
+    pub fn process(&mut self, frame: &frame::Video) -> Result<Vec<frame::Video>, Error> {
+        self.source.source().add(frame)?;
+        self.sink.sink().drain_video()
+    }
+
+    /// Signals the end of the stream and returns the remaining frames.
+    pub fn flush(&mut self) -> Result<Vec<frame::Video>, Error> {
+        self.source.source().flush()?;
+        self.sink.sink().drain_video()
+    }
+
+    /// The underlying filter graph.
+    pub fn graph(&self) -> &Graph {
+        &self.graph
+    }
+}
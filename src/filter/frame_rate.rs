@@ -0,0 +1,88 @@
+use super::{Context, Graph, buffer};
+use crate::{Error, Rational, frame};
+
+/// Converts variable frame rate video to a constant frame rate through a
+/// `buffer` → `fps` → `buffersink` graph, duplicating and dropping frames.
+///
+/// The output has one frame per tick of the target rate, with timestamps
+/// counting the ticks in [`time_base()`](Self::time_base), which is `1/rate` and
+/// the time base to give the encoder. Each tick shows the input frame whose
+/// timestamp is nearest to it (`round=near`): when input frames come faster than
+/// the target rate the frames falling between two ticks are dropped, and when
+/// they come slower a frame is repeated until the next one is nearer. A frame
+/// exactly halfway between two ticks goes to the later one. At the end of the
+/// stream [`flush()`](Self::flush) outputs the last frame for the ticks it still
+/// covers.
+///
+/// Frames need a pts; the filter discards leading frames without one. It has to
+/// see the next frame before it knows how often to repeat the current one, so
+/// output lags the input by a frame.
+///
+/// ```ignore
+/// let parameters = filter::buffer::Video::new(decoder.format(), decoder.width(), decoder.height(), stream.time_base());
+/// let mut converter = FrameRateConverter::new(&parameters, Rational(30000, 1001))?;
+/// encoder.set_time_base(converter.time_base());
+///
+/// for frame in converter.convert(&decoded)? {
+///     encoder.send_frame(&frame)?;
+/// }
+/// ```
+pub struct FrameRateConverter {
+    graph: Graph,
+    source: Context,
+    sink: Context,
+    rate: Rational,
+}
+
+unsafe impl Send for FrameRateConverter {}
+
+impl FrameRateConverter {
+    /// Creates a converter to `rate` frames per second for frames described by
+    /// `parameters`. Fails with `EINVAL` unless `rate` is positive.
+    pub fn new(parameters: &buffer::Video, rate: Rational) -> Result<Self, Error> {
+        if rate.numerator() <= 0 || rate.denominator() <= 0 {
+            return Err(Error::Other { errno: libc::EINVAL });
+        }
+
+        let mut graph = Graph::new();
+
+        let mut source = graph.add_video_source("in", parameters)?;
+        let mut filter = graph.add(&super::find("fps").ok_or(Error::FilterNotFound)?, "fps", &format!("fps={}/{}:round=near", rate.numerator(), rate.denominator()))?;
+        let mut sink = graph.add(&super::find("buffersink").ok_or(Error::FilterNotFound)?, "out", "")?;
+
+        source.link(0, &mut filter, 0);
+        filter.link(0, &mut sink, 0);
+
+        graph.validate()?;
+
+        Ok(FrameRateConverter { graph, source, sink, rate })
+    }
+
+    /// The target frame rate.
+    pub fn rate(&self) -> Rational {
+        self.rate
+    }
+
+    /// Time base of the output frames, the inverse of the target rate.
+    pub fn time_base(&mut self) -> Rational {
+        self.sink.sink().time_base()
+    }
+
+    /// Feeds a frame and returns the output frames now known: none when it is
+    /// dropped, several when the previous frame is repeated.
+    pub fn convert(&mut self, frame: &frame::Video) -> Result<Vec<frame::Video>, Error> {
+        self.source.source().add(frame)?;
+        self.sink.sink().drain_video()
+    }
+
+    /// Signals the end of the stream and returns the remaining frames.
+    pub fn flush(&mut self) -> Result<Vec<frame::Video>, Error> {
+        self.source.source().flush()?;
+        self.sink.sink().drain_video()
+    }
+
+    /// The underlying filter graph.
+    pub fn graph(&self) -> &Graph {
+        &self.graph
+    }
+}
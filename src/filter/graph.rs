@@ -4,8 +4,8 @@ use std::{
     str::from_utf8_unchecked,
 };
 
-use super::{Context, Filter};
-use crate::{Error, ffi::*};
+use super::{Context, Filter, buffer};
+use crate::{Error, ffi::*, format};
 use libc::c_int;
 
 pub struct Graph {
@@ -64,6 +64,88 @@ impl Graph {
         }
     }
 
+    /// Adds a `buffer` source filter configured from typed parameters.
+    pub fn add_video_source(&mut self, name: &str, parameters: &buffer::Video) -> Result<Context, Error> {
+        unsafe {
+            self.add_source("buffer", name, |ptr| {
+                (*ptr).format = AVPixelFormat::from(parameters.format) as c_int;
+                (*ptr).width = parameters.width as c_int;
+                (*ptr).height = parameters.height as c_int;
+                (*ptr).time_base = parameters.time_base.into();
+                (*ptr).sample_aspect_ratio = parameters.aspect_ratio.into();
+
+                if let Some(frame_rate) = parameters.frame_rate {
+                    (*ptr).frame_rate = frame_rate.into();
+                }
+            })
+        }
+    }
+
+    /// Adds an `abuffer` source filter configured from typed parameters.
+    pub fn add_audio_source(&mut self, name: &str, parameters: &buffer::Audio) -> Result<Context, Error> {
+        unsafe {
+            self.add_source("abuffer", name, |ptr| {
+                (*ptr).format = AVSampleFormat::from(parameters.format) as c_int;
+                (*ptr).sample_rate = parameters.rate as c_int;
+                (*ptr).time_base = parameters.time_base.into();
+
+                #[cfg(not(feature = "ffmpeg_7_0"))]
+                {
+                    (*ptr).channel_layout = parameters.channel_layout.bits();
+                }
+
+                #[cfg(feature = "ffmpeg_7_0")]
+                {
+                    (*ptr).ch_layout = parameters.channel_layout.into();
+                }
+            })
+        }
+    }
+
+    /// Adds a `buffersink` filter that only accepts `formats`, for graphs whose output
+    /// goes to an encoder with a fixed set of supported pixel formats. An empty
+    /// slice leaves the sink unconstrained.
+    ///
+    /// See [`Sink::set_pixel_formats()`](super::context::Sink::set_pixel_formats) to
+    /// constrain an existing sink.
+    pub fn add_video_sink(&mut self, name: &str, formats: &[format::Pixel]) -> Result<Context, Error> {
+        let mut context = self.add(&super::find("buffersink").ok_or(Error::FilterNotFound)?, name, "")?;
+
+        if !formats.is_empty() {
+            context.sink().set_pixel_formats(formats)?;
+        }
+
+        Ok(context)
+    }
+
+    unsafe fn add_source<F: FnOnce(*mut AVBufferSrcParameters)>(&mut self, filter: &str, name: &str, configure: F) -> Result<Context, Error> {
+        unsafe {
+            let filter = super::find(filter).ok_or(Error::FilterNotFound)?;
+            let name = CString::new(name).unwrap();
+            let context = avfilter_graph_alloc_filter(self.as_mut_ptr(), filter.as_ptr(), name.as_ptr());
+            let parameters = av_buffersrc_parameters_alloc();
+
+            if context.is_null() || parameters.is_null() {
+                panic!("out of memory");
+            }
+
+            configure(parameters);
+
+            let res = av_buffersrc_parameters_set(context, parameters);
+            av_free(parameters as *mut _);
+
+            let res = if res < 0 { res } else { avfilter_init_str(context, ptr::null()) };
+
+            match res {
+                0 => Ok(Context::wrap(context)),
+                e => {
+                    avfilter_free(context);
+                    Err(Error::from(e))
+                }
+            }
+        }
+    }
+
     pub fn get(&mut self, name: &str) -> Option<Context> {
         unsafe {
             let name = CString::new(name).unwrap();
@@ -12,6 +12,9 @@
 //! - [`Filter`] - Individual filter definition (scale, crop, overlay, etc.)
 //! - [`Context`] - Instance of a filter within a graph
 //! - [`Pad`] - Input/output connection point on a filter
+//! - [`buffer`] - Typed parameters for buffer source filters
+//! - [`Deinterlacer`] - Ready-made `yadif`/`bwdif` deinterlacing graph
+//! - [`FrameRateConverter`] - Constant frame rate output through the `fps` filter
 //!
 //! # Usage
 //!
@@ -36,6 +39,14 @@ pub use self::context::{Context, Sink, Source};
 pub mod graph;
 pub use self::graph::Graph;
 
+pub mod buffer;
+
+pub mod deinterlacer;
+pub use self::deinterlacer::Deinterlacer;
+
+pub mod frame_rate;
+pub use self::frame_rate::FrameRateConverter;
+
 use std::{
     ffi::{CStr, CString},
     str::from_utf8_unchecked,
@@ -32,6 +32,19 @@ impl<'a> Pad<'a> {
         }
     }
 
+    /// Position of the pad among the inputs or outputs of its filter, as used when
+    /// linking filter contexts.
+    pub fn index(&self) -> usize {
+        self.idx as usize
+    }
+
+    /// Media type the pad carries (`avfilter_pad_get_type`). An output pad can only
+    /// be linked to an input pad of the same type.
+    ///
+    /// Filters flagged with [`DYNAMIC_INPUTS`](super::Flags::DYNAMIC_INPUTS) or
+    /// [`DYNAMIC_OUTPUTS`](super::Flags::DYNAMIC_OUTPUTS) (`amix`, `concat`,
+    /// `split`, ...) create their pads from their options, so their pads are only
+    /// known on an initialized filter context.
     pub fn medium(&self) -> media::Type {
         unsafe { media::Type::from(avfilter_pad_get_type(self.ptr, self.idx as i32)) }
     }
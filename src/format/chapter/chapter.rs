@@ -1,3 +1,9 @@
+use std::{
+    ffi::{CStr, CString},
+    ptr,
+    str::from_utf8_unchecked,
+};
+
 use crate::{DictionaryRef, Rational, ffi::*};
 
 use crate::format::context::common::Context;
@@ -43,9 +49,42 @@ impl<'a> Chapter<'a> {
         unsafe { (*self.as_ptr()).end }
     }
 
+    /// Start of the chapter in seconds, converted through [`time_base()`](Self::time_base).
+    pub fn start_seconds(&self) -> f64 {
+        self.start() as f64 * f64::from(self.time_base())
+    }
+
+    /// End of the chapter in seconds, converted through [`time_base()`](Self::time_base).
+    pub fn end_seconds(&self) -> f64 {
+        self.end() as f64 * f64::from(self.time_base())
+    }
+
+    /// Whether `timestamp`, in `time_base` (usually the time base of the stream a
+    /// packet belongs to), falls within `[start, end)` of the chapter.
+    pub fn contains(&self, timestamp: i64, time_base: Rational) -> bool {
+        unsafe { av_compare_ts(timestamp, time_base.into(), self.start(), self.time_base().into()) >= 0 && !self.ended_at(timestamp, time_base) }
+    }
+
+    /// Whether `timestamp`, in `time_base`, is at or past the end of the chapter,
+    /// i.e. a packet with that timestamp belongs to a following chapter. Comparing
+    /// across time bases is exact, with no rounding at the boundary.
+    pub fn ended_at(&self, timestamp: i64, time_base: Rational) -> bool {
+        unsafe { av_compare_ts(timestamp, time_base.into(), self.end(), self.time_base().into()) >= 0 }
+    }
+
     pub fn metadata(&self) -> DictionaryRef<'_> {
         unsafe { DictionaryRef::wrap((*self.as_ptr()).metadata) }
     }
+
+    /// Returns the `title` metadata tag, if the container provides one.
+    pub fn title(&self) -> Option<&str> {
+        unsafe {
+            let key = CString::new("title").unwrap();
+            let entry = av_dict_get((*self.as_ptr()).metadata, key.as_ptr(), ptr::null_mut(), 0);
+
+            if entry.is_null() { None } else { Some(from_utf8_unchecked(CStr::from_ptr((*entry).value).to_bytes())) }
+        }
+    }
 }
 
 impl<'a> PartialEq for Chapter<'a> {
@@ -1,7 +1,7 @@
 use std::{fmt, mem, ptr, rc::Rc};
 
 use super::destructor::{self, Destructor};
-use crate::{Chapter, ChapterMut, DictionaryRef, Stream, StreamMut, ffi::*, media};
+use crate::{Chapter, ChapterMut, DictionaryRef, Program, Stream, StreamMut, ffi::*, media, util::interrupt::SharedInterrupt};
 use libc::{c_int, c_uint};
 
 pub struct Context {
@@ -57,6 +57,23 @@ impl Context {
         StreamIterMut::new(self)
     }
 
+    /// Installs `interrupt` as the interrupt callback of the context, keeping a
+    /// clone of it until the context is freed.
+    ///
+    /// It applies to the operations started from now on and to the connections
+    /// the demuxer or muxer opens later (HLS segments, DASH fragments, ...). The
+    /// main I/O of an already opened context keeps the callback it was opened
+    /// with; use [`format::input_interruptible()`](crate::format::input_interruptible)
+    /// or [`format::output_interruptible()`](crate::format::output_interruptible)
+    /// to cover it, and opening and probing, as well.
+    pub fn set_interrupt(&mut self, interrupt: &SharedInterrupt) {
+        unsafe {
+            (*self.as_mut_ptr()).interrupt_callback = interrupt.as_callback();
+        }
+
+        self.dtor.keep(interrupt.clone());
+    }
+
     pub fn bit_rate(&self) -> i64 {
         unsafe { (*self.as_ptr()).bit_rate }
     }
@@ -92,6 +109,22 @@ impl Context {
         ChapterIterMut::new(self)
     }
 
+    #[inline]
+    pub fn nb_programs(&self) -> u32 {
+        unsafe { (*self.as_ptr()).nb_programs }
+    }
+
+    pub fn program<'a, 'b>(&'a self, index: usize) -> Option<Program<'b>>
+    where
+        'a: 'b,
+    {
+        unsafe { if index >= self.nb_programs() as usize { None } else { Some(Program::wrap(self, index)) } }
+    }
+
+    pub fn programs(&self) -> ProgramIter<'_> {
+        ProgramIter::new(self)
+    }
+
     pub fn metadata(&self) -> DictionaryRef<'_> {
         unsafe { DictionaryRef::wrap((*self.as_ptr()).metadata) }
     }
@@ -267,6 +300,43 @@ impl<'a> Iterator for ChapterIter<'a> {
 
 impl<'a> ExactSizeIterator for ChapterIter<'a> {}
 
+pub struct ProgramIter<'a> {
+    context: &'a Context,
+    current: c_uint,
+}
+
+impl<'a> ProgramIter<'a> {
+    pub fn new<'s, 'c: 's>(context: &'c Context) -> ProgramIter<'s> {
+        ProgramIter { context, current: 0 }
+    }
+}
+
+impl<'a> Iterator for ProgramIter<'a> {
+    type Item = Program<'a>;
+
+    fn next(&mut self) -> Option<<Self as Iterator>::Item> {
+        unsafe {
+            if self.current >= (*self.context.as_ptr()).nb_programs {
+                return None;
+            }
+
+            self.current += 1;
+
+            Some(Program::wrap(self.context, (self.current - 1) as usize))
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        unsafe {
+            let length = (*self.context.as_ptr()).nb_programs as usize;
+
+            (length - self.current as usize, Some(length - self.current as usize))
+        }
+    }
+}
+
+impl<'a> ExactSizeIterator for ProgramIter<'a> {}
+
 pub struct ChapterIterMut<'a> {
     context: &'a mut Context,
     current: c_uint,
@@ -314,3 +384,54 @@ impl fmt::Debug for Context {
         s.finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        env, fs,
+        sync::{
+            Arc,
+            atomic::{AtomicBool, Ordering},
+        },
+    };
+
+    use super::*;
+    use crate::format;
+
+    fn interrupted(context: &Context) -> bool {
+        unsafe {
+            let callback = (*context.as_ptr()).interrupt_callback;
+            callback.callback.unwrap()(callback.opaque) != 0
+        }
+    }
+
+    #[test]
+    fn test_shared_interrupt() {
+        let first = env::temp_dir().join(format!("playa-ffmpeg-interrupt-1-{}.mkv", std::process::id()));
+        let second = env::temp_dir().join(format!("playa-ffmpeg-interrupt-2-{}.mkv", std::process::id()));
+
+        let flag = Arc::new(AtomicBool::new(false));
+        let interrupt = SharedInterrupt::from_flag(flag.clone());
+
+        let mut output = format::output(&first).unwrap();
+        output.set_interrupt(&interrupt);
+        let other = format::output_interruptible(&second, &interrupt).unwrap();
+
+        // The contexts keep the callback alive on their own.
+        drop(interrupt);
+
+        assert!(!interrupted(&output));
+        assert!(!interrupted(&other));
+
+        flag.store(true, Ordering::Relaxed);
+
+        assert!(interrupted(&output));
+        assert!(interrupted(&other));
+
+        drop(output);
+        drop(other);
+
+        fs::remove_file(&first).unwrap();
+        fs::remove_file(&second).unwrap();
+    }
+}
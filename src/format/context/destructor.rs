@@ -1,4 +1,6 @@
-use crate::ffi::*;
+use std::cell::RefCell;
+
+use crate::{ffi::*, util::interrupt::SharedInterrupt};
 
 #[derive(Copy, Clone, Debug)]
 pub enum Mode {
@@ -9,11 +11,17 @@ pub enum Mode {
 pub struct Destructor {
     ptr: *mut AVFormatContext,
     mode: Mode,
+    // Interrupt callbacks the context or its I/O may still call, freed after it.
+    interrupts: RefCell<Vec<SharedInterrupt>>,
 }
 
 impl Destructor {
     pub unsafe fn new(ptr: *mut AVFormatContext, mode: Mode) -> Self {
-        Destructor { ptr, mode }
+        Destructor { ptr, mode, interrupts: RefCell::new(Vec::new()) }
+    }
+
+    pub(crate) fn keep(&self, interrupt: SharedInterrupt) {
+        self.interrupts.borrow_mut().push(interrupt);
     }
 }
 
@@ -0,0 +1,206 @@
+use std::{ffi::CString, path::Path, ptr};
+
+use super::{Input, Output};
+use crate::{Error, Packet, Rational, codec, encoder, ffi::*, format};
+
+// Copies the packets of stream `index` of `input` to a new file at `path`, see
+// `Input::extract_stream()`.
+pub fn extract(input: &mut Input, index: usize, path: &Path) -> Result<usize, Error> {
+    let stream = input.stream(index).ok_or(Error::StreamNotFound)?;
+    let parameters = stream.parameters().clone();
+    let time_base = stream.time_base();
+
+    let mut output = match format::output(path) {
+        Ok(output) => output,
+        Err(_) => format::output_as(path, raw_muxer(parameters.id()).ok_or(Error::MuxerNotFound)?)?,
+    };
+
+    let muxer = output.format().name().to_owned();
+    let mut filter = match filter_name(&parameters, &muxer) {
+        Some(name) => Some(Filter::new(name, &parameters, time_base)?),
+        None => None,
+    };
+    let source = filter.as_ref().map_or(time_base, Filter::time_base);
+
+    {
+        let mut target = output.add_stream(encoder::find(codec::Id::None))?;
+
+        match filter {
+            Some(ref filter) => unsafe {
+                let res = avcodec_parameters_copy((*target.as_mut_ptr()).codecpar, (*filter.as_ptr()).par_out);
+
+                if res < 0 {
+                    return Err(Error::from(res));
+                }
+            },
+
+            None => target.set_parameters(parameters),
+        }
+
+        target.set_time_base(source);
+
+        unsafe {
+            (*target.parameters().as_mut_ptr()).codec_tag = 0;
+        }
+    }
+
+    output.write_header()?;
+
+    let destination = output.stream(0).ok_or(Error::StreamNotFound)?.time_base();
+    let mut written = 0;
+    let mut packet = Packet::empty();
+
+    while input.read(&mut packet)? {
+        if packet.stream() != index {
+            continue;
+        }
+
+        match filter {
+            Some(ref mut filter) => {
+                filter.send(Some(&mut packet))?;
+                written += filter.drain(&mut output, source, destination)?;
+            }
+
+            None => {
+                write(&mut packet, source, destination, &mut output)?;
+                written += 1;
+            }
+        }
+    }
+
+    if let Some(ref mut filter) = filter {
+        filter.send(None)?;
+        written += filter.drain(&mut output, source, destination)?;
+    }
+
+    output.write_trailer()?;
+
+    Ok(written)
+}
+
+// Muxer storing the bare elementary stream of `id`, for output paths whose
+// extension does not identify a format.
+fn raw_muxer(id: codec::Id) -> Option<&'static str> {
+    match id {
+        codec::Id::AAC => Some("adts"),
+        codec::Id::MP3 => Some("mp3"),
+        codec::Id::AC3 => Some("ac3"),
+        codec::Id::EAC3 => Some("eac3"),
+        codec::Id::DTS => Some("dts"),
+        codec::Id::TRUEHD => Some("truehd"),
+        codec::Id::FLAC => Some("flac"),
+        codec::Id::OPUS => Some("opus"),
+        codec::Id::VORBIS => Some("ogg"),
+        codec::Id::H264 => Some("h264"),
+        codec::Id::HEVC => Some("hevc"),
+        codec::Id::MPEG2VIDEO => Some("mpeg2video"),
+        codec::Id::VP9 | codec::Id::AV1 => Some("ivf"),
+        codec::Id::SUBRIP => Some("srt"),
+        codec::Id::ASS => Some("ass"),
+        codec::Id::WEBVTT => Some("webvtt"),
+        _ => None,
+    }
+}
+
+// Bitstream filter converting the packets of a stream from the form of its
+// source container to the one `muxer` stores.
+fn filter_name(parameters: &codec::Parameters, muxer: &str) -> Option<&'static str> {
+    let extradata = unsafe { (*parameters.as_ptr()).extradata_size > 0 && !(*parameters.as_ptr()).extradata.is_null() };
+    // avcC and hvcC configuration records start with version 1, Annex B extradata
+    // with a start code.
+    let length_prefixed = extradata && unsafe { *(*parameters.as_ptr()).extradata } == 1;
+
+    match parameters.id() {
+        codec::Id::H264 if length_prefixed && matches!(muxer, "h264" | "mpegts") => Some("h264_mp4toannexb"),
+        codec::Id::HEVC if length_prefixed && matches!(muxer, "hevc" | "mpegts") => Some("hevc_mp4toannexb"),
+        codec::Id::AAC if !extradata && matches!(muxer, "mp4" | "mov" | "ipod" | "ismv" | "3gp") => Some("aac_adtstoasc"),
+        _ => None,
+    }
+}
+
+fn write(packet: &mut Packet, source: Rational, destination: Rational, output: &mut Output) -> Result<(), Error> {
+    packet.rescale_ts(source, destination);
+    packet.set_position(-1);
+    packet.set_stream(0);
+    packet.write_interleaved(output)
+}
+
+struct Filter {
+    ptr: *mut AVBSFContext,
+}
+
+impl Filter {
+    fn new(name: &str, parameters: &codec::Parameters, time_base: Rational) -> Result<Self, Error> {
+        unsafe {
+            let name = CString::new(name).unwrap();
+            let kind = av_bsf_get_by_name(name.as_ptr());
+
+            if kind.is_null() {
+                return Err(Error::BsfNotFound);
+            }
+
+            let mut filter = Filter { ptr: ptr::null_mut() };
+            let res = av_bsf_alloc(kind, &mut filter.ptr);
+
+            if res < 0 {
+                return Err(Error::from(res));
+            }
+
+            let res = avcodec_parameters_copy((*filter.ptr).par_in, parameters.as_ptr());
+
+            if res < 0 {
+                return Err(Error::from(res));
+            }
+
+            (*filter.ptr).time_base_in = time_base.into();
+
+            match av_bsf_init(filter.ptr) {
+                e if e < 0 => Err(Error::from(e)),
+                _ => Ok(filter),
+            }
+        }
+    }
+
+    fn as_ptr(&self) -> *const AVBSFContext {
+        self.ptr as *const _
+    }
+
+    fn time_base(&self) -> Rational {
+        unsafe { Rational::from((*self.ptr).time_base_out) }
+    }
+
+    fn send(&mut self, packet: Option<&mut Packet>) -> Result<(), Error> {
+        unsafe {
+            match av_bsf_send_packet(self.ptr, packet.map_or(ptr::null_mut(), |packet| packet.as_mut_ptr())) {
+                e if e < 0 => Err(Error::from(e)),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    // Writes the packets the filter has ready and returns how many.
+    fn drain(&mut self, output: &mut Output, source: Rational, destination: Rational) -> Result<usize, Error> {
+        let mut written = 0;
+        let mut packet = Packet::empty();
+
+        loop {
+            match unsafe { av_bsf_receive_packet(self.ptr, packet.as_mut_ptr()) } {
+                0 => {
+                    write(&mut packet, source, destination, output)?;
+                    written += 1;
+                }
+
+                e if e == AVERROR(libc::EAGAIN) || e == AVERROR_EOF => return Ok(written),
+                e => return Err(Error::from(e)),
+            }
+        }
+    }
+}
+
+impl Drop for Filter {
+    fn drop(&mut self) {
+        unsafe {
+            av_bsf_free(&mut self.ptr);
+        }
+    }
+}
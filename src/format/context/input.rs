@@ -2,23 +2,31 @@ use std::{
     ffi::CString,
     mem,
     ops::{Deref, DerefMut},
+    path::Path,
+    ptr,
+    sync::{
+        Arc,
+        atomic::{AtomicI64, Ordering},
+    },
 };
 
-use super::{common::Context, destructor};
+use super::{common::Context, destructor, extract, json};
 #[cfg(not(feature = "ffmpeg_5_0"))]
 use crate::Codec;
-use crate::{Error, Packet, Stream, ffi::*, format, util::range::Range};
+use crate::{Dictionary, Error, Packet, Program, Rational, Rescale, Rounding, Stream, decoder, ffi::*, format, frame, packet::Mut, rescale, util::range::Range};
 
 pub struct Input {
     ptr: *mut AVFormatContext,
     ctx: Context,
+    progress: Option<Arc<AtomicI64>>,
+    offset: Option<i64>,
 }
 
 unsafe impl Send for Input {}
 
 impl Input {
     pub unsafe fn wrap(ptr: *mut AVFormatContext) -> Self {
-        Input { ptr, ctx: unsafe { Context::wrap(ptr, destructor::Mode::Input) } }
+        Input { ptr, ctx: unsafe { Context::wrap(ptr, destructor::Mode::Input) }, progress: None, offset: None }
     }
 
     pub unsafe fn as_ptr(&self) -> *const AVFormatContext {
@@ -75,14 +83,193 @@ impl Input {
         }
     }
 
+    /// Serializes container and stream information to a JSON object, similar to a
+    /// minimal `ffprobe -show_format -show_streams`.
+    ///
+    /// The object has a `format` entry (name, start time, duration, bit rate and tags)
+    /// and a `streams` array (index, type, codec, time base, start time, duration, bit
+    /// rate, resolution and frame rate for video, sample rate and channels for audio,
+    /// and tags). Times are in seconds and unknown values are `null`. Fields always
+    /// appear in the same order and tags are sorted by key, so the output of two runs
+    /// can be diffed.
+    pub fn metadata_json(&self) -> String {
+        json::metadata(self, self.format().name())
+    }
+
+    /// Returns the first program containing the stream at `index`
+    /// (`av_find_program_from_stream`), or `None` if it belongs to no program.
+    ///
+    /// A stream can be shared by several programs; use
+    /// [`programs()`](super::common::Context::programs) to find all of them.
+    pub fn program_for_stream(&self, index: usize) -> Option<Program<'_>> {
+        unsafe {
+            let ptr = av_find_program_from_stream(self.as_ptr() as *mut _, ptr::null_mut(), index as _);
+
+            if ptr.is_null() {
+                return None;
+            }
+
+            (0..self.nb_programs() as usize).find(|&i| *(*self.as_ptr()).programs.add(i) == ptr).map(|i| Program::wrap(self, i))
+        }
+    }
+
+    // Start of the current blocking operation, watched by the interrupt callback
+    // of `format::input_with_timeout()`.
+    pub(crate) fn watch_progress(&mut self, progress: Arc<AtomicI64>) {
+        self.progress = Some(progress);
+    }
+
+    // Restarts the timeout; called before every read (by `read()` and
+    // `Packet::read()`) and seek, so that time spent by the caller in between
+    // does not count.
+    pub(crate) fn progressed(&self) {
+        if let Some(progress) = &self.progress {
+            progress.store(unsafe { av_gettime_relative() }, Ordering::Relaxed);
+        }
+    }
+
+    // Called on every packet successfully read, by `read()` and `Packet::read()`.
+    pub(crate) fn packet_read(&self, packet: &mut Packet) {
+        let Some(offset) = self.offset else {
+            return;
+        };
+
+        unsafe {
+            let packet = packet.as_mut_ptr();
+            let Some(stream) = self.stream((*packet).stream_index as usize) else {
+                return;
+            };
+            let offset = av_rescale_q(offset, AV_TIME_BASE_Q, (*stream.as_ptr()).time_base);
+
+            if (*packet).pts != AV_NOPTS_VALUE {
+                (*packet).pts -= offset;
+            }
+
+            if (*packet).dts != AV_NOPTS_VALUE {
+                (*packet).dts -= offset;
+            }
+        }
+    }
+
+    /// Position of the first frame of the file, in `AV_TIME_BASE` units, or
+    /// `AV_NOPTS_VALUE` when unknown.
+    ///
+    /// It is rarely zero for MPEG-TS and live captures (and can be negative), so
+    /// timestamps should be taken relative to it: subtract
+    /// `start_time().rescale(rescale::TIME_BASE, stream.time_base())` from the
+    /// packet timestamps of every stream. Using one offset for all the streams
+    /// preserves their synchronization, which subtracting the start time of each
+    /// stream would not. [`normalize_timestamps()`](Self::normalize_timestamps)
+    /// does it while reading.
+    pub fn start_time(&self) -> i64 {
+        unsafe { (*self.as_ptr()).start_time }
+    }
+
+    /// Shifts the timestamps of the packets read from now on so that the file
+    /// starts at zero, e.g. to concatenate files without gaps.
+    ///
+    /// The offset is [`start_time()`](Self::start_time), or the earliest start
+    /// time of the streams when the container has none, rescaled to the time base
+    /// of each stream. Streams starting later than the file keep their relative
+    /// delay, and the DTS of the first packets of a stream with B-frames may stay
+    /// negative. [`seek()`](Self::seek) and [`frame_at()`](Self::frame_at) then take
+    /// positions on the same shifted timeline.
+    pub fn normalize_timestamps(&mut self, value: bool) {
+        if !value {
+            self.offset = None;
+            return;
+        }
+
+        let start = self.start_time();
+        let start = if start != AV_NOPTS_VALUE { start } else { self.streams().filter(|stream| stream.start_time() != AV_NOPTS_VALUE).map(|stream| stream.start_time().rescale(stream.time_base(), rescale::TIME_BASE)).min().unwrap_or(0) };
+
+        self.offset = Some(start);
+    }
+
+    /// Offset subtracted from the timestamps by
+    /// [`normalize_timestamps()`](Self::normalize_timestamps), in `AV_TIME_BASE`
+    /// units, or `None` when they are not normalized.
+    pub fn timestamp_offset(&self) -> Option<i64> {
+        self.offset
+    }
+
     pub fn probe_score(&self) -> i32 {
         unsafe { (*self.as_ptr()).probe_score }
     }
 
+    /// Probes the streams by reading (and possibly decoding) packets.
+    ///
+    /// [`format::input()`] already does this; call it yourself only on a context opened
+    /// with [`format::input_without_probe()`].
+    pub fn find_stream_info(&mut self) -> Result<(), Error> {
+        unsafe {
+            match avformat_find_stream_info(self.as_mut_ptr(), ptr::null_mut()) {
+                r if r >= 0 => Ok(()),
+                e => Err(Error::from(e)),
+            }
+        }
+    }
+
+    /// Probes the streams with per-stream codec options.
+    ///
+    /// `options[i]` is passed to the decoder probing stream `i`; streams without an
+    /// entry get no options and extra entries are ignored.
+    pub fn find_stream_info_with(&mut self, options: Vec<Dictionary>) -> Result<(), Error> {
+        unsafe {
+            let count = (*self.as_ptr()).nb_streams as usize;
+            let mut options = options.into_iter();
+            let mut opts = (0..count).map(|_| options.next().map_or(ptr::null_mut(), |o| o.disown())).collect::<Vec<_>>();
+
+            let res = avformat_find_stream_info(self.as_mut_ptr(), opts.as_mut_ptr());
+
+            for opt in opts {
+                Dictionary::own(opt);
+            }
+
+            match res {
+                r if r >= 0 => Ok(()),
+                e => Err(Error::from(e)),
+            }
+        }
+    }
+
+    /// Reads the next packet into a caller-owned `packet`.
+    ///
+    /// The previous contents of `packet` are released first, so one packet can be
+    /// reused for the whole demux loop instead of allocating one per read like
+    /// [`packets()`](Self::packets) does. Use [`Packet::stream()`] to route the result.
+    ///
+    /// Returns `Ok(false)` at end of file.
+    pub fn read(&mut self, packet: &mut Packet) -> Result<bool, Error> {
+        unsafe {
+            av_packet_unref(packet.as_mut_ptr());
+            self.progressed();
+
+            match av_read_frame(self.as_mut_ptr(), packet.as_mut_ptr()) {
+                0 => {
+                    self.packet_read(packet);
+                    Ok(true)
+                }
+                AVERROR_EOF => Ok(false),
+                e => Err(Error::from(e)),
+            }
+        }
+    }
+
+    /// Iterates over the packets until the end of the file, skipping packets that
+    /// cannot be read. Iteration also stops when a blocking read is interrupted
+    /// ([`Error::Exit`]), e.g. by the timeout of [`format::input_with_timeout()`];
+    /// use [`read()`](Self::read) to tell both cases apart.
     pub fn packets(&mut self) -> PacketIter<'_> {
         PacketIter::new(self)
     }
 
+    /// Packets of the stream at `index` only, e.g. to feed
+    /// [`decoder::Video::decode_iter()`](crate::decoder::Video::decode_iter).
+    pub fn packets_for(&mut self, index: usize) -> impl Iterator<Item = Packet> + '_ {
+        self.packets().filter(move |(stream, _)| stream.index() == index).map(|(_, packet)| packet)
+    }
+
     pub fn pause(&mut self) -> Result<(), Error> {
         unsafe {
             match av_read_pause(self.as_mut_ptr()) {
@@ -101,14 +288,209 @@ impl Input {
         }
     }
 
+    /// Seeks to `ts`, in `AV_TIME_BASE` units, landing on a position within
+    /// `range`.
+    ///
+    /// With [`normalize_timestamps()`](Self::normalize_timestamps) on, `ts` and
+    /// `range` are on the normalized timeline, like the timestamps of the packets
+    /// read.
     pub fn seek<R: Range<i64>>(&mut self, ts: i64, range: R) -> Result<(), Error> {
+        let offset = self.offset.unwrap_or(0);
+        let shift = |ts: i64| if ts == i64::MIN || ts == i64::MAX { ts } else { ts.saturating_add(offset) };
+
+        self.seek_raw(range.start().cloned().map_or(i64::MIN, shift), shift(ts), range.end().cloned().map_or(i64::MAX, shift))
+    }
+
+    // Seeks on the timestamps of the file, whether they are normalized or not.
+    fn seek_raw(&mut self, min: i64, ts: i64, max: i64) -> Result<(), Error> {
+        self.progressed();
+
         unsafe {
-            match avformat_seek_file(self.as_mut_ptr(), -1, range.start().cloned().unwrap_or(i64::MIN), ts, range.end().cloned().unwrap_or(i64::MAX), 0) {
+            match avformat_seek_file(self.as_mut_ptr(), -1, min, ts, max, 0) {
                 s if s >= 0 => Ok(()),
                 e => Err(Error::from(e)),
             }
         }
     }
+
+    /// Copies the packets of the stream at `index`, without decoding, into a new
+    /// file at `path` holding only that stream, e.g. the AAC track of an MP4 as
+    /// an `.aac` file. Returns the number of packets written.
+    ///
+    /// The format is guessed from the extension of `path`; when it is unknown the
+    /// raw format of the codec is used (`adts` for AAC, `h264`, `ivf` for VP9 and
+    /// AV1, `srt`, ...). Packets are read from the current position to the end of
+    /// the input, the other streams are skipped.
+    ///
+    /// Containers store some codecs differently, so a bitstream filter is
+    /// inserted when needed:
+    ///
+    /// - H.264 and HEVC from MP4, MOV or Matroska (length-prefixed NAL units) to
+    ///   raw `.h264`/`.hevc` or MPEG-TS: `h264_mp4toannexb`/`hevc_mp4toannexb`;
+    /// - AAC from MPEG-TS or `.aac` (ADTS headers) to MP4, MOV or M4A:
+    ///   `aac_adtstoasc`.
+    ///
+    /// AAC from MP4 to `.aac` needs no filter: the `adts` muxer builds the ADTS
+    /// headers from the codec parameters. Other extractions copy the packets as is.
+    pub fn extract_stream<P: AsRef<Path> + ?Sized>(&mut self, index: usize, path: &P) -> Result<usize, Error> {
+        extract::extract(self, index, path.as_ref())
+    }
+
+    /// Sample aspect ratio of the video stream at `stream_index`, reconciling the
+    /// stream and codec values and, if given, the one of a decoded `frame` as
+    /// `av_guess_sample_aspect_ratio` does. Returns `0/1` when unknown, which
+    /// should be treated as square pixels.
+    ///
+    /// Fails with [`Error::StreamNotFound`] for an invalid index.
+    pub fn guess_aspect_ratio(&self, stream_index: usize, frame: Option<&frame::Video>) -> Result<Rational, Error> {
+        let stream = self.stream(stream_index).ok_or(Error::StreamNotFound)?;
+
+        unsafe {
+            let frame = frame.map_or(ptr::null_mut(), |frame| frame.as_ptr() as *mut _);
+
+            Ok(Rational::from(av_guess_sample_aspect_ratio(self.as_ptr() as *mut _, stream.as_ptr() as *mut _, frame)))
+        }
+    }
+
+    /// Seeks to the start of the chapter at `index` (its position in
+    /// [`chapters()`](Context::chapters), not its id).
+    ///
+    /// The chapter start is rescaled from the chapter time base to
+    /// `AV_TIME_BASE`, rounding down, and the seek lands on the last keyframe at
+    /// or before it so that no part of the chapter is skipped; packets just before
+    /// the start may be returned first. Use [`Chapter::ended_at()`](format::chapter::Chapter::ended_at)
+    /// with the packet's stream time base to stop at the end of the chapter.
+    ///
+    /// Fails with `EINVAL` if there is no chapter at `index`.
+    pub fn seek_to_chapter(&mut self, index: usize) -> Result<(), Error> {
+        let chapter = self.chapter(index).ok_or(Error::Other { errno: libc::EINVAL })?;
+        let target = chapter.start().rescale_with(chapter.time_base(), rescale::TIME_BASE, Rounding::Down);
+
+        // Chapters are on the timeline of the file, not the normalized one.
+        self.seek_raw(i64::MIN, target, target)
+    }
+
+    /// Decodes the frame of stream `stream_index` shown at `target`, a pts in the
+    /// stream time base (normalized if
+    /// [`normalize_timestamps()`](Self::normalize_timestamps) is on).
+    ///
+    /// Seeks to the last keyframe at or before `target`, flushes `decoder` and
+    /// decodes forward, discarding frames, until the frame covering `target`: the
+    /// one with the greatest timestamp not after it. Timestamps are FFmpeg's best
+    /// effort ones, which remain usable when the stream pts are missing or not
+    /// monotonic. When the seek lands after `target` (imprecise index, or `target`
+    /// before the first keyframe) decoding restarts from the beginning of the file;
+    /// a `target` before the first frame yields the first frame.
+    ///
+    /// `decoder` must decode that stream. Afterwards the input is positioned
+    /// somewhere after `target`, so seek again before reading packets.
+    pub fn frame_at(&mut self, decoder: &mut decoder::Video, stream_index: usize, target: i64) -> Result<frame::Video, Error> {
+        let mut from_start = false;
+        let offset = match (self.offset, self.stream(stream_index)) {
+            (Some(offset), Some(stream)) => offset.rescale(rescale::TIME_BASE, stream.time_base()),
+            _ => 0,
+        };
+
+        unsafe {
+            if av_seek_frame(self.as_mut_ptr(), stream_index as _, target.saturating_add(offset), AVSEEK_FLAG_BACKWARD as _) < 0 {
+                self.seek_to_start(stream_index)?;
+                from_start = true;
+            }
+        }
+
+        loop {
+            decoder.flush();
+
+            match self.decode_until(decoder, stream_index, target)? {
+                (frame, true) => return Ok(frame),
+                (frame, false) if from_start => return Ok(frame),
+
+                (_, false) => {
+                    self.seek_to_start(stream_index)?;
+                    from_start = true;
+                }
+            }
+        }
+    }
+
+    fn seek_to_start(&mut self, stream_index: usize) -> Result<(), Error> {
+        unsafe {
+            let start = self.stream(stream_index).map(|stream| stream.start_time()).filter(|&start| start != AV_NOPTS_VALUE).unwrap_or(0);
+
+            if av_seek_frame(self.as_mut_ptr(), stream_index as _, start, AVSEEK_FLAG_BACKWARD as _) >= 0 {
+                return Ok(());
+            }
+
+            match av_seek_frame(self.as_mut_ptr(), stream_index as _, 0, AVSEEK_FLAG_BYTE as _) {
+                e if e < 0 => Err(Error::from(e)),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    // Returns the frame covering `target` and `true`, or the first decoded frame and
+    // `false` if it is already past `target`.
+    fn decode_until(&mut self, decoder: &mut decoder::Video, stream_index: usize, target: i64) -> Result<(frame::Video, bool), Error> {
+        let mut candidate: Option<(frame::Video, i64)> = None;
+        let mut frame = frame::Video::empty();
+
+        while self.decode_next(decoder, stream_index, &mut frame)? {
+            let Some(timestamp) = frame.timestamp().or(frame.pts()) else {
+                continue;
+            };
+
+            if timestamp > target {
+                return Ok(match candidate {
+                    Some((candidate, _)) => (candidate, true),
+                    None => (frame, false),
+                });
+            }
+
+            if candidate.as_ref().is_none_or(|&(_, best)| timestamp >= best) {
+                candidate = Some((mem::replace(&mut frame, frame::Video::empty()), timestamp));
+            }
+
+            if timestamp == target {
+                return Ok((candidate.unwrap().0, true));
+            }
+        }
+
+        candidate.map(|(frame, _)| (frame, true)).ok_or(Error::Eof)
+    }
+
+    // Decodes the next frame of stream `stream_index` into `frame`, feeding
+    // `decoder` the packets of the stream as needed; `false` once the decoder is
+    // drained at the end of the input.
+    pub(crate) fn decode_next(&mut self, decoder: &mut decoder::Video, stream_index: usize, frame: &mut frame::Video) -> Result<bool, Error> {
+        let mut packet = Packet::empty();
+
+        loop {
+            match decoder.receive_frame(frame) {
+                Ok(()) => return Ok(true),
+
+                Err(Error::Other { errno: libc::EAGAIN }) => loop {
+                    match packet.read(self) {
+                        Ok(()) if packet.stream() == stream_index => {
+                            decoder.send_packet(&packet)?;
+                            break;
+                        }
+
+                        Ok(()) => (),
+
+                        Err(Error::Eof) => {
+                            decoder.send_eof()?;
+                            break;
+                        }
+
+                        Err(e) => return Err(e),
+                    }
+                },
+
+                Err(Error::Eof) => return Ok(false),
+                Err(e) => return Err(e),
+            }
+        }
+    }
 }
 
 impl Deref for Input {
@@ -147,7 +529,8 @@ impl<'a> Iterator for PacketIter<'a> {
                     return Some((Stream::wrap(mem::transmute_copy(&self.context), packet.stream()), packet));
                 },
 
-                Err(Error::Eof) => return None,
+                // An interrupted input keeps failing.
+                Err(Error::Eof) | Err(Error::Exit) => return None,
 
                 Err(..) => (),
             }
@@ -162,3 +545,71 @@ pub fn dump(ctx: &Input, index: i32, url: Option<&str>) {
         av_dump_format(ctx.as_ptr() as *mut _, index, url.unwrap_or_else(|| CString::new("").unwrap()).as_ptr(), 0);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+    use crate::{codec, encoder, packet};
+
+    // Writes two seconds of 2×2 gray video at 25 fps starting at 10 s.
+    fn write_late_start(path: &Path) {
+        let mut output = format::output(path).unwrap();
+
+        {
+            let mut stream = output.add_stream(encoder::find(codec::Id::None)).unwrap();
+            stream.set_time_base((1, 25));
+
+            unsafe {
+                let parameters = stream.parameters().as_mut_ptr();
+                (*parameters).codec_type = AVMediaType::AVMEDIA_TYPE_VIDEO;
+                (*parameters).codec_id = AVCodecID::AV_CODEC_ID_RAWVIDEO;
+                (*parameters).format = AVPixelFormat::AV_PIX_FMT_GRAY8 as _;
+                (*parameters).width = 2;
+                (*parameters).height = 2;
+            }
+        }
+
+        output.write_header().unwrap();
+        let time_base = output.stream(0).unwrap().time_base();
+
+        for index in 0..50 {
+            let mut packet = Packet::copy(&[index as u8; 4]);
+            packet.set_pts(Some(250 + index));
+            packet.set_dts(Some(250 + index));
+            packet.set_flags(packet::Flags::KEY);
+            packet.rescale_ts((1, 25), time_base);
+            packet.write_interleaved(&mut output).unwrap();
+        }
+
+        output.write_trailer().unwrap();
+    }
+
+    #[test]
+    fn test_normalized_seek() {
+        let path = env::temp_dir().join(format!("playa-ffmpeg-normalized-{}.nut", std::process::id()));
+        write_late_start(&path);
+
+        let mut input = format::input(&path).unwrap();
+        input.normalize_timestamps(true);
+        assert_eq!(input.timestamp_offset(), Some(10_000_000));
+
+        let time_base = input.stream(0).unwrap().time_base();
+        let mut packet = Packet::empty();
+        assert!(input.read(&mut packet).unwrap());
+        assert_eq!(packet.pts(), Some(0));
+
+        input.seek(1_000_000, ..1_000_000).unwrap();
+        assert!(input.read(&mut packet).unwrap());
+        assert_eq!(packet.pts().unwrap().rescale(time_base, rescale::TIME_BASE), 1_000_000);
+
+        let mut decoder = codec::Context::from_parameters(input.stream(0).unwrap().parameters()).unwrap().decoder().video().unwrap();
+        let target = 37.rescale((1, 25), time_base);
+        let frame = input.frame_at(&mut decoder, 0, target).unwrap();
+        assert_eq!(frame.pts(), Some(target));
+        assert_eq!(frame.data(0)[0], 37);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,189 @@
+//! Minimal JSON serialization used by [`Input::metadata_json()`](super::Input::metadata_json).
+
+use std::{collections::BTreeMap, fmt::Write};
+
+use super::common::Context;
+use crate::{DictionaryRef, Rational, ffi::*, media, rescale::TIME_BASE};
+
+enum Value {
+    Null,
+    Int(i64),
+    Float(f64),
+    Str(String),
+    Array(Vec<Value>),
+    Object(Vec<(&'static str, Value)>),
+    Tags(BTreeMap<String, String>),
+}
+
+impl Value {
+    fn write(&self, out: &mut String, indent: usize) {
+        match *self {
+            Value::Null => out.push_str("null"),
+            Value::Int(value) => write!(out, "{value}").unwrap(),
+            Value::Float(value) if value.is_finite() => write!(out, "{value}").unwrap(),
+            Value::Float(_) => out.push_str("null"),
+            Value::Str(ref value) => escape(out, value),
+
+            Value::Array(ref values) => {
+                if values.is_empty() {
+                    return out.push_str("[]");
+                }
+
+                out.push('[');
+                for (i, value) in values.iter().enumerate() {
+                    out.push_str(if i == 0 { "\n" } else { ",\n" });
+                    pad(out, indent + 1);
+                    value.write(out, indent + 1);
+                }
+                out.push('\n');
+                pad(out, indent);
+                out.push(']');
+            }
+
+            Value::Object(ref fields) => {
+                let fields = fields.iter().map(|(key, value)| (*key, value));
+                object(out, indent, fields, |out, value, indent| value.write(out, indent));
+            }
+
+            Value::Tags(ref tags) => {
+                let fields = tags.iter().map(|(key, value)| (key.as_str(), value));
+                object(out, indent, fields, |out, value, _| escape(out, value));
+            }
+        }
+    }
+}
+
+fn object<'a, T: 'a, I, F>(out: &mut String, indent: usize, fields: I, write: F)
+where
+    I: ExactSizeIterator<Item = (&'a str, &'a T)>,
+    F: Fn(&mut String, &T, usize),
+{
+    if fields.len() == 0 {
+        return out.push_str("{}");
+    }
+
+    out.push('{');
+    for (i, (key, value)) in fields.enumerate() {
+        out.push_str(if i == 0 { "\n" } else { ",\n" });
+        pad(out, indent + 1);
+        escape(out, key);
+        out.push_str(": ");
+        write(out, value, indent + 1);
+    }
+    out.push('\n');
+    pad(out, indent);
+    out.push('}');
+}
+
+fn pad(out: &mut String, indent: usize) {
+    for _ in 0..indent {
+        out.push_str("  ");
+    }
+}
+
+fn escape(out: &mut String, value: &str) {
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => write!(out, "\\u{:04x}", c as u32).unwrap(),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+}
+
+fn tags(dictionary: DictionaryRef) -> Value {
+    Value::Tags(dictionary.iter().map(|(key, value)| (key.to_owned(), value.to_owned())).collect())
+}
+
+fn seconds(value: i64, time_base: Rational) -> Value {
+    if value == AV_NOPTS_VALUE { Value::Null } else { Value::Float(value as f64 * f64::from(time_base)) }
+}
+
+fn rational(value: Rational) -> Value {
+    if value.denominator() == 0 || value.numerator() == 0 { Value::Null } else { Value::Str(value.to_string()) }
+}
+
+fn media_type(value: media::Type) -> &'static str {
+    match value {
+        media::Type::Unknown => "unknown",
+        media::Type::Video => "video",
+        media::Type::Audio => "audio",
+        media::Type::Data => "data",
+        media::Type::Subtitle => "subtitle",
+        media::Type::Attachment => "attachment",
+    }
+}
+
+pub fn metadata(context: &Context, format: &str) -> String {
+    let streams = context
+        .streams()
+        .map(|stream| {
+            let parameters = stream.parameters();
+            let medium = parameters.medium();
+
+            let mut fields = vec![
+                ("index", Value::Int(stream.index() as i64)),
+                ("type", Value::Str(media_type(medium).into())),
+                ("codec", Value::Str(parameters.id().name().into())),
+                ("time_base", rational(stream.time_base())),
+                ("start_time", seconds(stream.start_time(), stream.time_base())),
+                ("duration", seconds(stream.duration(), stream.time_base())),
+            ];
+
+            unsafe {
+                let par = parameters.as_ptr();
+
+                fields.push(("bit_rate", if (*par).bit_rate > 0 { Value::Int((*par).bit_rate) } else { Value::Null }));
+
+                match medium {
+                    media::Type::Video => {
+                        fields.push(("width", Value::Int((*par).width as i64)));
+                        fields.push(("height", Value::Int((*par).height as i64)));
+                        fields.push(("frame_rate", rational(stream.avg_frame_rate())));
+                    }
+
+                    media::Type::Audio => {
+                        #[cfg(not(feature = "ffmpeg_7_0"))]
+                        let channels = (*par).channels;
+                        #[cfg(feature = "ffmpeg_7_0")]
+                        let channels = (*par).ch_layout.nb_channels;
+
+                        fields.push(("sample_rate", Value::Int((*par).sample_rate as i64)));
+                        fields.push(("channels", Value::Int(channels as i64)));
+                    }
+
+                    _ => (),
+                }
+            }
+
+            fields.push(("tags", tags(stream.metadata())));
+
+            Value::Object(fields)
+        })
+        .collect();
+
+    let root = Value::Object(vec![
+        (
+            "format",
+            Value::Object(vec![
+                ("name", Value::Str(format.into())),
+                ("start_time", seconds(unsafe { (*context.as_ptr()).start_time }, TIME_BASE)),
+                ("duration", seconds(context.duration(), TIME_BASE)),
+                ("bit_rate", if context.bit_rate() > 0 { Value::Int(context.bit_rate()) } else { Value::Null }),
+                ("nb_streams", Value::Int(context.nb_streams() as i64)),
+                ("tags", tags(context.metadata())),
+            ]),
+        ),
+        ("streams", Value::Array(streams)),
+    ]);
+
+    let mut out = String::new();
+    root.write(&mut out, 0);
+    out
+}
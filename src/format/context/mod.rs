@@ -5,11 +5,14 @@ pub mod input;
 pub use self::input::Input;
 
 pub mod output;
-pub use self::output::Output;
+pub use self::output::{Fragmentation, Output, State};
 
 #[doc(hidden)]
 pub mod common;
 
+mod extract;
+mod json;
+
 pub enum Context {
     Input(Input),
     Output(Output),
@@ -3,23 +3,50 @@ use std::{
     mem::size_of,
     ops::{Deref, DerefMut},
     ptr,
+    time::Duration,
 };
 
 use libc;
 
 use super::{common::Context, destructor};
-use crate::{ChapterMut, Dictionary, Error, Rational, StreamMut, codec, codec::traits, ffi::*, format};
+use crate::{ChapterMut, Dictionary, Error, Rational, Stream, StreamMut, codec, codec::traits, ffi::*, format, media};
+
+/// Muxing progress of an [`Output`].
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum State {
+    /// Streams can still be added; the header has not been written.
+    NotStarted,
+    /// The header is written, packets can be muxed.
+    HeaderWritten,
+    /// The trailer is written (or was attempted), nothing more can be muxed.
+    TrailerWritten,
+}
+
+/// How [`Output::set_fragmented()`] cuts the output into fragments.
+///
+/// With no field set, MP4 fragments only end on
+/// [`Output::flush_fragment()`] and when the trailer is written.
+#[derive(Eq, PartialEq, Clone, Copy, Debug, Default)]
+pub struct Fragmentation {
+    /// Start a fragment at every video keyframe (MP4 `frag_keyframe`). Matroska
+    /// always starts its clusters at video keyframes.
+    pub keyframe: bool,
+    /// Start a fragment once the current one is this long (MP4 `frag_duration`,
+    /// Matroska `cluster_time_limit`).
+    pub duration: Option<Duration>,
+}
 
 pub struct Output {
     ptr: *mut AVFormatContext,
     ctx: Context,
+    state: State,
 }
 
 unsafe impl Send for Output {}
 
 impl Output {
     pub unsafe fn wrap(ptr: *mut AVFormatContext) -> Self {
-        Output { ptr, ctx: unsafe { Context::wrap(ptr, destructor::Mode::Output) } }
+        Output { ptr, ctx: unsafe { Context::wrap(ptr, destructor::Mode::Output) }, state: State::NotStarted }
     }
 
     pub unsafe fn as_ptr(&self) -> *const AVFormatContext {
@@ -40,28 +67,192 @@ impl Output {
         }
     }
 
+    /// Codec FFmpeg recommends for a stream of type `kind` in this output, based on
+    /// the muxer, its short name and the output file name (so that e.g. `.m4a`
+    /// picks AAC). `None` when the format cannot hold such a stream.
+    pub fn guess_codec(&self, kind: media::Type) -> Option<codec::Id> {
+        unsafe {
+            let format = (*self.as_ptr()).oformat;
+
+            #[cfg(feature = "ffmpeg_4_1")]
+            let url = (*self.as_ptr()).url as *const _;
+            #[cfg(not(feature = "ffmpeg_4_1"))]
+            let url = (*self.as_ptr()).filename.as_ptr();
+
+            let id = codec::Id::from(av_guess_codec(format as *mut _, (*format).name, url, ptr::null(), kind.into()));
+
+            if id == codec::Id::None { None } else { Some(id) }
+        }
+    }
+
+    /// Where the muxer stands between header and trailer.
+    ///
+    /// Writing the header twice, muxing packets outside of
+    /// [`State::HeaderWritten`] or writing the trailer before the header are
+    /// rejected with `EINVAL` instead of being passed on to FFmpeg.
+    pub fn state(&self) -> State {
+        self.state
+    }
+
+    /// Overrides the state tracked by this wrapper, e.g. after writing the header
+    /// of a context obtained with [`wrap()`](Self::wrap) through the FFI, since
+    /// `wrap()` always starts in [`State::NotStarted`].
+    ///
+    /// The state must match what was actually done with the context: the checks
+    /// relying on it are what keeps FFmpeg from being called out of order.
+    pub unsafe fn set_state(&mut self, state: State) {
+        self.state = state;
+    }
+
+    /// Writes the container header.
+    ///
+    /// Muxers may replace the time base requested with
+    /// [`StreamMut::set_time_base()`](crate::format::stream::StreamMut::set_time_base)
+    /// by one the container supports (MP4 derives it from the frame rate,
+    /// Matroska always uses 1/1000, MPEG-TS 1/90000). Read the stream time bases
+    /// afterwards, e.g. with [`time_bases()`](Self::time_bases), and rescale packets
+    /// to those, never to the requested ones.
     pub fn write_header(&mut self) -> Result<(), Error> {
+        if self.state != State::NotStarted {
+            return Err(Error::Other { errno: libc::EINVAL });
+        }
+
         unsafe {
             match avformat_write_header(self.as_mut_ptr(), ptr::null_mut()) {
-                0 => Ok(()),
-                e => Err(Error::from(e)),
+                e if e < 0 => Err(Error::from(e)),
+                _ => {
+                    self.state = State::HeaderWritten;
+                    Ok(())
+                }
             }
         }
     }
 
     pub fn write_header_with(&mut self, options: Dictionary) -> Result<Dictionary<'_>, Error> {
+        if self.state != State::NotStarted {
+            return Err(Error::Other { errno: libc::EINVAL });
+        }
+
         unsafe {
             let mut opts = options.disown();
             let res = avformat_write_header(self.as_mut_ptr(), &mut opts);
 
             match res {
-                0 => Ok(Dictionary::own(opts)),
-                e => Err(Error::from(e)),
+                e if e < 0 => Err(Error::from(e)),
+                _ => {
+                    self.state = State::HeaderWritten;
+                    Ok(Dictionary::own(opts))
+                }
             }
         }
     }
 
+    /// Makes the muxer write a fragmented file, in which every fragment can be
+    /// decoded on its own, e.g. for DASH or for appending to a live stream. Has
+    /// to be called before [`write_header()`](Self::write_header).
+    ///
+    /// For MP4 and MOV this sets `movflags=+empty_moov+default_base_moof` and the
+    /// flags of `fragmentation`: the header then writes the `ftyp` and an empty
+    /// `moov` box, which form the initialization segment, and each fragment is a
+    /// self-contained `moof` + `mdat` media segment. The index is no longer
+    /// written at the end, so neither a seekable output nor a second pass is
+    /// needed and the file can go to a pipe or a socket. The `faststart` flag, which
+    /// moves the index of an unfragmented file to the front in such a second pass,
+    /// has nothing to do there and is ignored by the muxer. Matroska and WebM
+    /// always write self-contained clusters; only their duration is set.
+    ///
+    /// Fails with `EINVAL` once the header is written and with `ENOSYS` for
+    /// other formats.
+    ///
+    /// ```ignore
+    /// let mut output = format::output_as(&"pipe:1", "mp4")?;
+    /// // ... add streams
+    /// output.set_fragmented(Fragmentation { keyframe: true, ..Default::default() })?;
+    /// output.write_header()?;
+    /// ```
+    pub fn set_fragmented(&mut self, fragmentation: Fragmentation) -> Result<(), Error> {
+        if self.state != State::NotStarted {
+            return Err(Error::Other { errno: libc::EINVAL });
+        }
+
+        let micros = fragmentation.duration.map(|duration| i64::try_from(duration.as_micros()).unwrap_or(i64::MAX));
+
+        match self.format().name() {
+            "mov" | "mp4" | "ismv" | "ipod" | "3gp" | "3g2" | "psp" | "f4v" => {
+                let flags = if fragmentation.keyframe { "+empty_moov+default_base_moof+frag_keyframe" } else { "+empty_moov+default_base_moof" };
+                self.set_muxer_option("movflags", flags)?;
+
+                match micros {
+                    Some(micros) => self.set_muxer_option("frag_duration", &micros.to_string()),
+                    None if !fragmentation.keyframe => self.set_muxer_option("movflags", "+frag_custom"),
+                    None => Ok(()),
+                }
+            }
+
+            "matroska" | "webm" => match micros {
+                Some(micros) => self.set_muxer_option("cluster_time_limit", &(micros / 1000).to_string()),
+                None => Ok(()),
+            },
+
+            _ => Err(Error::Other { errno: libc::ENOSYS }),
+        }
+    }
+
+    /// Ends the current fragment (or Matroska cluster) and flushes it to the
+    /// output, so that the bytes written since the previous flush form a
+    /// complete media segment. See [`set_fragmented()`](Self::set_fragmented).
+    ///
+    /// Packets still waiting in the interleaving queue of
+    /// [`Packet::write_interleaved()`](crate::Packet::write_interleaved) are
+    /// written first, so they are part of the fragment.
+    pub fn flush_fragment(&mut self) -> Result<(), Error> {
+        if self.state != State::HeaderWritten {
+            return Err(Error::Other { errno: libc::EINVAL });
+        }
+
+        unsafe {
+            let res = av_interleaved_write_frame(self.as_mut_ptr(), ptr::null_mut());
+
+            if res < 0 {
+                return Err(Error::from(res));
+            }
+
+            match av_write_frame(self.as_mut_ptr(), ptr::null_mut()) {
+                e if e < 0 => Err(Error::from(e)),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    fn set_muxer_option(&mut self, name: &str, value: &str) -> Result<(), Error> {
+        let name = CString::new(name).unwrap();
+        let value = CString::new(value).unwrap();
+
+        unsafe {
+            match av_opt_set(self.as_mut_ptr() as *mut _, name.as_ptr(), value.as_ptr(), AV_OPT_SEARCH_CHILDREN) {
+                e if e < 0 => Err(Error::from(e)),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    /// Time base of every stream, by index. Only final once the header has been
+    /// written, see [`write_header()`](Self::write_header).
+    pub fn time_bases(&self) -> Vec<Rational> {
+        self.streams().map(|stream| stream.time_base()).collect()
+    }
+
+    /// Writes the trailer and finishes muxing.
+    ///
+    /// FFmpeg releases the muxer state even when this fails, so the output is
+    /// considered finished either way.
     pub fn write_trailer(&mut self) -> Result<(), Error> {
+        if self.state != State::HeaderWritten {
+            return Err(Error::Other { errno: libc::EINVAL });
+        }
+
+        self.state = State::TrailerWritten;
+
         unsafe {
             match av_write_trailer(self.as_mut_ptr()) {
                 0 => Ok(()),
@@ -105,6 +296,35 @@ impl Output {
         }
     }
 
+    /// Whether the muxer wants codec headers out of band (`AVFMT_GLOBALHEADER`), as MP4
+    /// and Matroska do.
+    ///
+    /// When it does, set `codec::Flags::GLOBAL_HEADER` on the encoder **before** opening
+    /// it, otherwise the stream has no extradata.
+    pub fn needs_global_header(&self) -> bool {
+        self.format().flags().contains(format::Flags::GLOBAL_HEADER)
+    }
+
+    /// Starts building a new output stream.
+    ///
+    /// The builder replaces the usual `add_stream` → `set_parameters` →
+    /// `set_time_base` sequence:
+    ///
+    /// ```ignore
+    /// // Transcoding: configure the encoder, honouring the muxer's needs, then open it.
+    /// if output.needs_global_header() {
+    ///     encoder.set_flags(codec::Flags::GLOBAL_HEADER);
+    /// }
+    /// let encoder = encoder.open_as(codec)?;
+    /// let stream = output.stream_builder().encoder(&encoder).build()?;
+    ///
+    /// // Remuxing: copy everything from the input stream.
+    /// let stream = output.stream_builder().stream(&input_stream).build()?;
+    /// ```
+    pub fn stream_builder(&mut self) -> StreamBuilder<'_> {
+        StreamBuilder { output: self, parameters: None, time_base: None, avg_frame_rate: None, real_frame_rate: None, codec_tag: None, error: None }
+    }
+
     pub fn add_chapter<R: Into<Rational>, S: AsRef<str>>(&mut self, id: i64, time_base: R, start: i64, end: i64, title: S) -> Result<ChapterMut<'_>, Error> {
         // avpriv_new_chapter is private (libavformat/internal.h)
 
@@ -157,6 +377,40 @@ impl Output {
             (*self.as_mut_ptr()).metadata = dictionary.disown();
         }
     }
+
+    /// Copies the container metadata and the chapters of `input`, for remuxing
+    /// without losing titles, tags or chapter marks.
+    ///
+    /// Tags already set on the output are overwritten by those of the input.
+    /// Chapters keep their id, all their tags and their own time base, so their
+    /// start and end are exact whatever time base the output streams use; the
+    /// muxer converts them to whatever the container stores. Stream metadata is
+    /// copied per stream with [`StreamMut::copy_metadata_from()`].
+    pub fn copy_metadata_from(&mut self, input: &format::context::Input) -> Result<(), Error> {
+        unsafe {
+            let res = av_dict_copy(&mut (*self.as_mut_ptr()).metadata, (*input.as_ptr()).metadata, 0);
+
+            if res < 0 {
+                return Err(Error::from(res));
+            }
+        }
+
+        for chapter in input.chapters() {
+            let mut copy = self.add_chapter(chapter.id(), chapter.time_base(), chapter.start(), chapter.end(), "")?;
+
+            unsafe {
+                av_dict_free(&mut (*copy.as_mut_ptr()).metadata);
+
+                let res = av_dict_copy(&mut (*copy.as_mut_ptr()).metadata, (*chapter.as_ptr()).metadata, 0);
+
+                if res < 0 {
+                    return Err(Error::from(res));
+                }
+            }
+        }
+
+        Ok(())
+    }
 }
 
 impl Deref for Output {
@@ -173,6 +427,113 @@ impl DerefMut for Output {
     }
 }
 
+/// Builder for an output stream, see [`Output::stream_builder()`].
+pub struct StreamBuilder<'a> {
+    output: &'a mut Output,
+
+    parameters: Option<codec::Parameters>,
+    time_base: Option<Rational>,
+    avg_frame_rate: Option<Rational>,
+    real_frame_rate: Option<Rational>,
+    codec_tag: Option<u32>,
+    error: Option<Error>,
+}
+
+impl<'a> StreamBuilder<'a> {
+    /// Takes the codec parameters, time base and frame rate from an encoder.
+    ///
+    /// The encoder must already be opened, since encoders only produce their
+    /// extradata while opening. Passing an encoder that is not open makes
+    /// [`build()`](Self::build) fail with `EINVAL` when the muxer needs global headers.
+    pub fn encoder<C: AsRef<codec::Context>>(mut self, encoder: &C) -> Self {
+        let context = encoder.as_ref();
+
+        unsafe {
+            if avcodec_is_open(context.as_ptr() as *mut _) == 0 && self.output.needs_global_header() {
+                self.error = Some(Error::Other { errno: libc::EINVAL });
+            }
+        }
+
+        let frame_rate = context.frame_rate();
+
+        self.parameters = Some(codec::Parameters::from(context));
+        self.time_base = Some(context.time_base());
+        self.avg_frame_rate = if frame_rate.numerator() > 0 { Some(frame_rate) } else { None };
+        self
+    }
+
+    /// Takes the codec parameters, time base and frame rates from an input stream,
+    /// for remuxing without re-encoding.
+    ///
+    /// The codec tag is cleared so the muxer picks the one matching its container,
+    /// unless one is set with [`codec_tag()`](Self::codec_tag).
+    pub fn stream(mut self, stream: &Stream) -> Self {
+        self.parameters = Some(stream.parameters().clone());
+        self.time_base = Some(stream.time_base());
+        self.avg_frame_rate = Some(stream.avg_frame_rate());
+        self.real_frame_rate = Some(stream.real_frame_rate());
+        self.codec_tag = Some(0);
+        self
+    }
+
+    /// Overrides the stream time base. The muxer may still change it when the header is written.
+    pub fn time_base<R: Into<Rational>>(mut self, value: R) -> Self {
+        self.time_base = Some(value.into());
+        self
+    }
+
+    /// Overrides the codec tag (FourCC) written by the muxer.
+    pub fn codec_tag(mut self, value: u32) -> Self {
+        self.codec_tag = Some(value);
+        self
+    }
+
+    /// Creates the stream.
+    pub fn build(self) -> Result<StreamMut<'a>, Error> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+
+        let output = self.output;
+
+        unsafe {
+            let ptr = avformat_new_stream(output.as_mut_ptr(), ptr::null());
+
+            if ptr.is_null() {
+                return Err(Error::Unknown);
+            }
+
+            if let Some(parameters) = self.parameters {
+                let res = avcodec_parameters_copy((*ptr).codecpar, parameters.as_ptr());
+
+                if res < 0 {
+                    return Err(Error::from(res));
+                }
+            }
+
+            if let Some(codec_tag) = self.codec_tag {
+                (*(*ptr).codecpar).codec_tag = codec_tag;
+            }
+
+            if let Some(time_base) = self.time_base {
+                (*ptr).time_base = time_base.into();
+            }
+
+            if let Some(frame_rate) = self.avg_frame_rate {
+                (*ptr).avg_frame_rate = frame_rate.into();
+            }
+
+            if let Some(frame_rate) = self.real_frame_rate {
+                (*ptr).r_frame_rate = frame_rate.into();
+            }
+
+            let index = (*output.ctx.as_ptr()).nb_streams - 1;
+
+            Ok(StreamMut::wrap(&mut output.ctx, index as usize))
+        }
+    }
+}
+
 pub fn dump(ctx: &Output, index: i32, url: Option<&str>) {
     let url = url.map(|u| CString::new(u).unwrap());
 
@@ -0,0 +1,166 @@
+use std::{collections::VecDeque, io};
+
+use super::context::Output;
+use crate::{Error, Packet, Rational, codec, ffi::*};
+
+/// Turns a raw elementary stream (Annex-B H.264/HEVC, ADTS AAC, ...) received as
+/// bytes into timestamped packets ready to be muxed.
+///
+/// Bytes are pushed in chunks of any size, with [`push()`](Self::push) or through
+/// [`io::Write`], and split into one packet per frame by the
+/// [codec parser](codec::Parser). Raw streams rarely carry timestamps, so packets
+/// the parser leaves without them are stamped from the configured frame rate:
+/// the n-th packet gets `pts = dts = n` in a time base of `1/frame_rate`. That
+/// assumes frames arrive in presentation order; streams with B-frames need real
+/// timestamps from the source.
+///
+/// ```ignore
+/// let mut input = format::ElementaryStreamInput::new(codec::Id::H264, Rational(30, 1))?;
+/// let mut output = format::output(&"out.mp4")?;
+///
+/// // parse enough of the stream for the parameters (dimensions) to be known
+/// io::copy(&mut socket.by_ref().take(64 * 1024), &mut input)?;
+///
+/// let mut stream = output.add_stream(encoder::find(codec::Id::None))?;
+/// stream.set_parameters(input.parameters());
+/// stream.set_time_base(input.time_base());
+/// output.write_header()?;
+///
+/// loop {
+///     io::copy(&mut socket.by_ref().take(64 * 1024), &mut input)?;
+///     input.write_to(&mut output, 0)?;
+/// }
+/// ```
+pub struct ElementaryStreamInput {
+    parser: codec::Parser,
+    context: codec::Context,
+    time_base: Rational,
+    count: i64,
+    packets: VecDeque<Packet>,
+}
+
+impl ElementaryStreamInput {
+    /// Creates an input for a stream of codec `id` at `frame_rate` frames (or,
+    /// for audio, packets) per second.
+    ///
+    /// Fails with `ENOSYS` when FFmpeg has no parser for `id` and with `EINVAL`
+    /// for a frame rate that is not positive.
+    pub fn new(id: codec::Id, frame_rate: Rational) -> Result<Self, Error> {
+        if frame_rate.numerator() <= 0 || frame_rate.denominator() <= 0 {
+            return Err(Error::Other { errno: libc::EINVAL });
+        }
+
+        let parser = codec::Parser::new(id)?;
+        let mut context = codec::Context::new();
+
+        unsafe {
+            (*context.as_mut_ptr()).codec_id = id.into();
+            (*context.as_mut_ptr()).codec_type = avcodec_get_type(id.into());
+        }
+
+        Ok(ElementaryStreamInput { parser, context, time_base: frame_rate.invert(), count: 0, packets: VecDeque::new() })
+    }
+
+    /// Time base of the generated timestamps, `1/frame_rate`.
+    pub fn time_base(&self) -> Rational {
+        self.time_base
+    }
+
+    /// Parses `data`, queueing the packets it completes.
+    pub fn push(&mut self, data: &[u8]) -> Result<(), Error> {
+        let packets = self.parser.parse(&mut self.context, data, None, None)?;
+
+        for packet in packets {
+            self.enqueue(packet);
+        }
+
+        Ok(())
+    }
+
+    /// Signals the end of the stream, queueing the frame the parser still holds.
+    pub fn finish(&mut self) -> Result<(), Error> {
+        if let Some(packet) = self.parser.flush(&mut self.context)? {
+            self.enqueue(packet);
+        }
+
+        Ok(())
+    }
+
+    /// Takes the next complete packet, if any.
+    pub fn packet(&mut self) -> Option<Packet> {
+        self.packets.pop_front()
+    }
+
+    /// Number of packets waiting to be taken.
+    pub fn pending(&self) -> usize {
+        self.packets.len()
+    }
+
+    /// Codec parameters found so far, for the output stream.
+    ///
+    /// The parser learns the dimensions and pixel format of video streams from
+    /// the first headers (e.g. the H.264 SPS), so push some data first. Extradata
+    /// is left empty; muxers such as MP4 take it from the in-band headers of the
+    /// first packet.
+    pub fn parameters(&self) -> codec::Parameters {
+        let mut parameters = codec::Parameters::new();
+
+        unsafe {
+            avcodec_parameters_from_context(parameters.as_mut_ptr(), self.context.as_ptr());
+
+            let parser = self.parser.as_ptr();
+
+            if (*parser).width > 0 && (*parser).height > 0 {
+                (*parameters.as_mut_ptr()).width = (*parser).width;
+                (*parameters.as_mut_ptr()).height = (*parser).height;
+            }
+
+            if (*parser).format >= 0 {
+                (*parameters.as_mut_ptr()).format = (*parser).format;
+            }
+        }
+
+        parameters
+    }
+
+    /// Muxes the queued packets into stream `index` of `output`, rescaling them to
+    /// the stream's time base, and returns how many were written. The header
+    /// must have been written.
+    pub fn write_to(&mut self, output: &mut Output, index: usize) -> Result<usize, Error> {
+        let time_base = output.stream(index).ok_or(Error::StreamNotFound)?.time_base();
+        let mut written = 0;
+
+        while let Some(mut packet) = self.packets.pop_front() {
+            packet.set_stream(index);
+            packet.rescale_ts(self.time_base, time_base);
+            packet.write_interleaved(output)?;
+
+            written += 1;
+        }
+
+        Ok(written)
+    }
+
+    fn enqueue(&mut self, mut packet: Packet) {
+        if packet.pts().is_none() && packet.dts().is_none() {
+            packet.set_pts(Some(self.count));
+            packet.set_dts(Some(self.count));
+            packet.set_duration(1);
+        }
+
+        self.count += 1;
+        self.packets.push_back(packet);
+    }
+}
+
+impl io::Write for ElementaryStreamInput {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.push(buf).map_err(io::Error::other)?;
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
@@ -59,6 +59,20 @@ impl Output {
         unsafe { codec::Id::from(av_guess_codec(self.as_ptr() as *mut _, ptr::null(), path.as_ptr(), ptr::null(), kind.into())) }
     }
 
+    /// Default codec of this format for `kind` (e.g. H.264 video and AAC audio for
+    /// `mp4`), or `None` if the format has no stream of that type.
+    ///
+    /// Uses `av_guess_codec` with the format and its short name; see
+    /// [`context::Output::guess_codec()`](crate::format::context::Output::guess_codec)
+    /// to take the output file name into account too.
+    pub fn guess_codec(&self, kind: media::Type) -> Option<codec::Id> {
+        unsafe {
+            let id = codec::Id::from(av_guess_codec(self.as_ptr() as *mut _, (*self.as_ptr()).name, ptr::null(), ptr::null(), kind.into()));
+
+            if id == codec::Id::None { None } else { Some(id) }
+        }
+    }
+
     pub fn flags(&self) -> Flags {
         unsafe { Flags::from_bits_truncate((*self.as_ptr()).flags) }
     }
@@ -0,0 +1,120 @@
+use std::{cmp::Ordering, collections::VecDeque, iter};
+
+use crate::{Packet, Rational, ffi::*};
+
+/// Merges packets coming from several encoders into DTS order.
+///
+/// Muxers expect packets of all streams interleaved by decode timestamp. When each
+/// stream is produced by its own encoder, packets arrive in bursts per stream; push
+/// them here as they come and write out whatever [`pop()`](Self::pop) returns. A packet
+/// is only released once every stream that has not been [finished](Self::finish)
+/// has at least one packet buffered, so the output is ordered across streams.
+///
+/// Packets are compared with `av_compare_ts` on their DTS (or PTS when no DTS is
+/// set) in the time base given to [`push()`](Self::push). Packets without any
+/// timestamp are released as early as possible.
+///
+/// ```ignore
+/// let mut interleaver = format::Interleaver::new(octx.nb_streams() as usize);
+///
+/// // for every encoded packet, from any encoder:
+/// interleaver.push(packet, time_base);
+/// while let Some(packet) = interleaver.pop() {
+///     packet.write_interleaved(&mut octx)?;
+/// }
+///
+/// // at EOF:
+/// for packet in interleaver.flush() {
+///     packet.write_interleaved(&mut octx)?;
+/// }
+/// ```
+pub struct Interleaver {
+    queues: Vec<VecDeque<(Packet, Rational)>>,
+    finished: Vec<bool>,
+}
+
+impl Interleaver {
+    /// Creates an interleaver for `streams` streams, indexed like the output streams.
+    pub fn new(streams: usize) -> Self {
+        Interleaver { queues: (0..streams).map(|_| VecDeque::new()).collect(), finished: vec![false; streams] }
+    }
+
+    /// Buffers `packet` for the stream given by [`Packet::stream()`].
+    ///
+    /// `time_base` is the time base the packet timestamps are expressed in. Pushing to a
+    /// stream index beyond the ones given to [`new()`](Self::new) grows the interleaver.
+    pub fn push(&mut self, packet: Packet, time_base: Rational) {
+        let index = packet.stream();
+
+        if index >= self.queues.len() {
+            self.queues.resize_with(index + 1, VecDeque::new);
+            self.finished.resize(index + 1, false);
+        }
+
+        self.finished[index] = false;
+        self.queues[index].push_back((packet, time_base));
+    }
+
+    /// Marks a stream as ended; it no longer holds back the other streams.
+    pub fn finish(&mut self, stream: usize) {
+        if let Some(finished) = self.finished.get_mut(stream) {
+            *finished = true;
+        }
+    }
+
+    /// Returns the next packet in DTS order, if it can be determined yet.
+    ///
+    /// Returns `None` while some unfinished stream has no packet buffered, because a
+    /// packet still to come on that stream could be earlier than all buffered ones.
+    pub fn pop(&mut self) -> Option<Packet> {
+        let mut best: Option<usize> = None;
+
+        for (index, queue) in self.queues.iter().enumerate() {
+            let Some((packet, time_base)) = queue.front() else {
+                if self.finished[index] {
+                    continue;
+                }
+
+                return None;
+            };
+
+            best = match best {
+                Some(current) => {
+                    let (other, other_time_base) = self.queues[current].front().unwrap();
+
+                    if compare(packet, *time_base, other, *other_time_base) == Ordering::Less { Some(index) } else { Some(current) }
+                }
+
+                None => Some(index),
+            };
+        }
+
+        best.and_then(|index| self.queues[index].pop_front()).map(|(packet, _)| packet)
+    }
+
+    /// Finishes all streams and drains every buffered packet in DTS order.
+    pub fn flush(&mut self) -> impl Iterator<Item = Packet> + '_ {
+        for finished in &mut self.finished {
+            *finished = true;
+        }
+
+        iter::from_fn(move || self.pop())
+    }
+
+    /// Number of packets currently buffered across all streams.
+    pub fn len(&self) -> usize {
+        self.queues.iter().map(VecDeque::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.queues.iter().all(VecDeque::is_empty)
+    }
+}
+
+fn compare(a: &Packet, a_time_base: Rational, b: &Packet, b_time_base: Rational) -> Ordering {
+    match (a.dts().or(a.pts()), b.dts().or(b.pts())) {
+        (Some(a), Some(b)) => unsafe { av_compare_ts(a, a_time_base.into(), b, b_time_base.into()).cmp(&0) },
+        (None, Some(_)) => Ordering::Less,
+        _ => Ordering::Greater,
+    }
+}
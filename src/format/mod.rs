@@ -8,7 +8,13 @@
 //! - [`Context`] - Format context managing streams and container metadata
 //! - [`stream`] - Individual media streams within a container
 //! - [`chapter`] - Chapter/bookmark support for seekable formats
+//! - [`program`] - Programs of multi-program containers (MPEG-TS)
 //! - [`mod@format`] - Container format information and discovery
+//! - [`Interleaver`] - DTS-ordered merging of packets from several encoders
+//! - [`PacketBuffer`] - Size-bounded packet queue for jitter buffering
+//! - [`ElementaryStreamInput`] - Packetizing raw elementary streams for muxing
+//! - [`Trimmer`] - Lossless cutting of a time range by packet copy
+//! - [`TimestampValidator`] - Diagnostics for broken packet and frame timestamps
 //!
 //! # Common Operations
 //!
@@ -47,6 +53,8 @@ pub mod stream;
 
 pub mod chapter;
 
+pub mod program;
+
 pub mod context;
 pub use self::context::Context;
 
@@ -57,11 +65,31 @@ pub use self::format::{Flags, Input, Output, flag};
 
 pub mod network;
 
+pub mod interleaver;
+pub use self::interleaver::Interleaver;
+
+pub mod packet_buffer;
+pub use self::packet_buffer::{Overflow, PacketBuffer};
+
+pub mod elementary;
+pub use self::elementary::ElementaryStreamInput;
+
+pub mod trimmer;
+pub use self::trimmer::Trimmer;
+
+pub mod validator;
+pub use self::validator::TimestampValidator;
+
 use std::{
     ffi::{CStr, CString},
     path::Path,
     ptr,
     str::from_utf8_unchecked,
+    sync::{
+        Arc,
+        atomic::{AtomicI64, Ordering},
+    },
+    time::Duration,
 };
 
 use crate::{Dictionary, Error, Format, ffi::*};
@@ -294,6 +322,49 @@ pub fn input_with_dictionary<P: AsRef<Path> + ?Sized>(path: &P, options: Diction
     }
 }
 
+/// Opens a media file for reading, passing codec options to stream probing.
+///
+/// Unlike [`input_with_dictionary()`], whose options go to the demuxer and protocol,
+/// `options` here are handed to the decoders that `avformat_find_stream_info` opens
+/// while probing. The same options are applied to every stream. To bound the probe
+/// itself, set the demuxer options `probesize` / `analyzeduration` through
+/// [`input_without_probe()`] and call [`context::Input::find_stream_info_with()`].
+pub fn input_with_probe_options<P: AsRef<Path> + ?Sized>(path: &P, options: Dictionary) -> Result<context::Input, Error> {
+    let mut input = input_without_probe(path, Dictionary::new())?;
+    let count = input.nb_streams() as usize;
+
+    input.find_stream_info_with(vec![options; count])?;
+
+    Ok(input)
+}
+
+/// Opens a media file for reading without probing stream information.
+///
+/// Only the container header is parsed, so opening is cheap, but stream parameters
+/// may be incomplete (and for some formats streams may be missing entirely) until
+/// [`context::Input::find_stream_info()`] is called. Use this when the stream layout
+/// is already known or when probing must be configured per stream.
+///
+/// # Parameters
+///
+/// * `path` - Path to the media file
+/// * `options` - Dictionary of format/protocol options (e.g. `probesize`, `analyzeduration`)
+pub fn input_without_probe<P: AsRef<Path> + ?Sized>(path: &P, options: Dictionary) -> Result<context::Input, Error> {
+    unsafe {
+        let mut ps = ptr::null_mut();
+        let path = from_path(path);
+        let mut opts = options.disown();
+        let res = avformat_open_input(&mut ps, path.as_ptr(), ptr::null_mut(), &mut opts);
+
+        Dictionary::own(opts);
+
+        match res {
+            0 => Ok(context::Input::wrap(ps)),
+            e => Err(Error::from(e)),
+        }
+    }
+}
+
 /// Opens a media file for reading with interrupt callback.
 ///
 /// Allows cancellation of long-running operations (network streams, slow I/O).
@@ -341,6 +412,83 @@ where
     }
 }
 
+/// Opens a media file for reading, with `interrupt` installed on the context
+/// from the start so that it also covers opening the file and probing its
+/// streams.
+///
+/// The same handle can be installed on several contexts; see
+/// [`SharedInterrupt`](crate::util::interrupt::SharedInterrupt).
+pub fn input_interruptible<P: AsRef<Path> + ?Sized>(path: &P, interrupt: &interrupt::SharedInterrupt) -> Result<context::Input, Error> {
+    unsafe {
+        let mut ps = avformat_alloc_context();
+        let path = from_path(path);
+
+        (*ps).interrupt_callback = interrupt.as_callback();
+
+        match avformat_open_input(&mut ps, path.as_ptr(), ptr::null_mut(), ptr::null_mut()) {
+            0 => {
+                let mut input = context::Input::wrap(ps);
+                input.set_interrupt(interrupt);
+
+                match avformat_find_stream_info(ps, ptr::null_mut()) {
+                    r if r >= 0 => Ok(input),
+                    e => Err(Error::from(e)),
+                }
+            }
+
+            e => Err(Error::from(e)),
+        }
+    }
+}
+
+/// Opens a media file for reading, aborting blocking I/O after `timeout` without
+/// progress.
+///
+/// Unlike a flat deadline, the timeout applies to every read on its own
+/// ([`Input::read()`](context::Input::read), [`Packet::read()`](crate::Packet::read),
+/// [`packets()`](context::Input::packets)) and to every seek, so a slow but
+/// alive stream keeps going, however long the caller takes between reads, while
+/// a stalled one, e.g. a camera that stopped sending, fails with [`Error::Exit`]
+/// after `timeout`. Opening the input and probing the streams each have to
+/// complete within `timeout` as well.
+///
+/// Time is measured with FFmpeg's monotonic clock (`av_gettime_relative`). An
+/// interrupted input is usually left in an error state; reopen it to retry.
+/// [`packets()`](context::Input::packets) ends on the interruption.
+///
+/// ```ignore
+/// let mut input = ffmpeg::format::input_with_timeout(&"rtsp://camera/stream", Duration::from_secs(5))?;
+/// ```
+pub fn input_with_timeout<P: AsRef<Path> + ?Sized>(path: &P, timeout: Duration) -> Result<context::Input, Error> {
+    let limit = i64::try_from(timeout.as_micros()).unwrap_or(i64::MAX);
+    let progress = Arc::new(AtomicI64::new(unsafe { av_gettime_relative() }));
+    let watched = progress.clone();
+    let interrupt = interrupt::SharedInterrupt::from_fn(move || unsafe { av_gettime_relative() }.saturating_sub(watched.load(Ordering::Relaxed)) > limit);
+
+    unsafe {
+        let mut ps = avformat_alloc_context();
+        let path = from_path(path);
+
+        (*ps).interrupt_callback = interrupt.as_callback();
+
+        match avformat_open_input(&mut ps, path.as_ptr(), ptr::null_mut(), ptr::null_mut()) {
+            0 => {
+                let mut input = context::Input::wrap(ps);
+                input.set_interrupt(&interrupt);
+                input.watch_progress(progress);
+                input.progressed();
+
+                match avformat_find_stream_info(ps, ptr::null_mut()) {
+                    r if r >= 0 => Ok(input),
+                    e => Err(Error::from(e)),
+                }
+            }
+
+            e => Err(Error::from(e)),
+        }
+    }
+}
+
 /// Opens a media file for writing (muxing).
 ///
 /// Creates a new output file with format auto-detected from the file extension.
@@ -394,6 +542,33 @@ pub fn output<P: AsRef<Path> + ?Sized>(path: &P) -> Result<context::Output, Erro
     }
 }
 
+/// Opens a media file for writing with `interrupt` installed on the context
+/// and on the connection to `path`, so that it can abort a write blocked on a
+/// network peer.
+///
+/// The same handle can be installed on several contexts; see
+/// [`SharedInterrupt`](crate::util::interrupt::SharedInterrupt).
+pub fn output_interruptible<P: AsRef<Path> + ?Sized>(path: &P, interrupt: &interrupt::SharedInterrupt) -> Result<context::Output, Error> {
+    unsafe {
+        let mut ps = ptr::null_mut();
+        let path = from_path(path);
+
+        match avformat_alloc_output_context2(&mut ps, ptr::null_mut(), ptr::null(), path.as_ptr()) {
+            0 => {
+                let mut output = context::Output::wrap(ps);
+                output.set_interrupt(interrupt);
+
+                match avio_open2(&mut (*ps).pb, path.as_ptr(), AVIO_FLAG_WRITE, &(*ps).interrupt_callback, ptr::null_mut()) {
+                    0 => Ok(output),
+                    e => Err(Error::from(e)),
+                }
+            }
+
+            e => Err(Error::from(e)),
+        }
+    }
+}
+
 /// Opens a media file for writing with options dictionary.
 ///
 /// Like [`output()`] but allows passing I/O and format options.
@@ -490,3 +665,66 @@ pub fn output_as_with<P: AsRef<Path> + ?Sized>(path: &P, format: &str, options:
         }
     }
 }
+
+/// Opens an output context writing to the file descriptor `fd`, e.g. a pipe to
+/// another process.
+///
+/// The descriptor is opened through FFmpeg's `pipe:` protocol and is not closed
+/// when the context is dropped. On Windows it is a C runtime descriptor (as
+/// returned by `_open_osfhandle`, `1` for stdout), not a `HANDLE`.
+///
+/// Pipes cannot seek, so the container has to be streamable: MPEG-TS, Matroska,
+/// FLV, NUT and raw formats work, plain MP4/MOV do not since their index is written
+/// at the end. For those this fails with `ESPIPE` and logs a hint; use
+/// [`output_to_fd_with()`] with `movflags=frag_keyframe+empty_moov` to write
+/// fragmented MP4 instead.
+///
+/// ```ignore
+/// // mux MPEG-TS to stdout, e.g. `transcoder | ffplay -`
+/// let mut octx = ffmpeg::format::output_to_fd(1, "mpegts")?;
+/// ```
+pub fn output_to_fd(fd: i32, format: &str) -> Result<context::Output, Error> {
+    output_to_fd_with(fd, format, Dictionary::new())
+}
+
+/// Like [`output_to_fd()`], with options for the protocol and the muxer (such as
+/// `movflags`). Unknown options are ignored.
+pub fn output_to_fd_with(fd: i32, format: &str, options: Dictionary) -> Result<context::Output, Error> {
+    unsafe {
+        let mut ps = ptr::null_mut();
+        let url = CString::new(format!("pipe:{fd}")).unwrap();
+        let format = CString::new(format).unwrap();
+
+        match avformat_alloc_output_context2(&mut ps, ptr::null_mut(), format.as_ptr(), url.as_ptr()) {
+            0 => {
+                let name = CStr::from_ptr((*(*ps).oformat).name).to_str().unwrap_or("");
+                let fragmented = options.get("movflags").is_some_and(|flags| flags.contains("frag_") || flags.contains("empty_moov"));
+
+                if matches!(name, "mov" | "mp4" | "ipod" | "3gp" | "3g2" | "psp" | "ismv" | "f4v") && !fragmented {
+                    let message = CString::new(format!("{name} cannot be written to a pipe, use fragmented output (movflags=frag_keyframe+empty_moov) or a streamable format such as mpegts or matroska")).unwrap();
+                    let fmt = CString::new("%s\n").unwrap();
+                    av_log(ps as *mut _, AV_LOG_ERROR as _, fmt.as_ptr(), message.as_ptr());
+
+                    avformat_free_context(ps);
+                    return Err(Error::Other { errno: libc::ESPIPE });
+                }
+
+                let mut opts = options.disown();
+                av_opt_set_dict2(ps as *mut _, &mut opts, AV_OPT_SEARCH_CHILDREN as _);
+                let res = avio_open2(&mut (*ps).pb, url.as_ptr(), AVIO_FLAG_WRITE, ptr::null(), &mut opts);
+
+                Dictionary::own(opts);
+
+                match res {
+                    0 => Ok(context::Output::wrap(ps)),
+                    e => {
+                        avformat_free_context(ps);
+                        Err(Error::from(e))
+                    }
+                }
+            }
+
+            e => Err(Error::from(e)),
+        }
+    }
+}
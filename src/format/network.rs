@@ -1,4 +1,6 @@
-use crate::ffi::*;
+use std::time::Duration;
+
+use crate::{Dictionary, ffi::*};
 
 pub fn init() {
     unsafe {
@@ -11,3 +13,146 @@ pub fn deinit() {
         avformat_network_deinit();
     }
 }
+
+/// Lower transport used by the RTSP demuxer for the media streams.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum RtspTransport {
+    Udp,
+    Tcp,
+    UdpMulticast,
+    /// RTSP tunneled over HTTP.
+    Http,
+}
+
+impl RtspTransport {
+    fn name(self) -> &'static str {
+        match self {
+            RtspTransport::Udp => "udp",
+            RtspTransport::Tcp => "tcp",
+            RtspTransport::UdpMulticast => "udp_multicast",
+            RtspTransport::Http => "http",
+        }
+    }
+}
+
+/// Typed builder for the protocol options of network inputs (RTSP, HTTP, RTMP).
+///
+/// Produces the dictionary to pass to
+/// [`input_with_dictionary()`](crate::format::input_with_dictionary). Options a
+/// protocol does not know are left in the dictionary by FFmpeg and ignored, so
+/// one set of options can be reused across URLs of different schemes.
+///
+/// ```ignore
+/// let options = format::network::Options::new()
+///     .rtsp_transport(format::network::RtspTransport::Tcp)
+///     .timeout(Duration::from_secs(5));
+///
+/// let input = format::input_with_dictionary(&"rtsp://camera/stream", options.into())?;
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Options {
+    dictionary: Dictionary<'static>,
+}
+
+impl Options {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// RTSP: transport for the media streams (`rtsp_transport`). FFmpeg tries UDP
+    /// first and falls back to TCP by default; many cameras behind NAT only work
+    /// with [`RtspTransport::Tcp`].
+    pub fn rtsp_transport(mut self, value: RtspTransport) -> Self {
+        self.dictionary.set("rtsp_transport", value.name());
+        self
+    }
+
+    /// Connection and socket I/O timeout of RTSP, HTTP, TCP and UDP.
+    ///
+    /// Sets `timeout`, in microseconds. Before FFmpeg 5.0 RTSP used `stimeout`
+    /// for this, which is set as well.
+    pub fn timeout(mut self, value: Duration) -> Self {
+        let micros = value.as_micros().to_string();
+
+        self.dictionary.set("timeout", &micros);
+        #[cfg(not(feature = "ffmpeg_5_0"))]
+        self.dictionary.set("stimeout", &micros);
+        self
+    }
+
+    /// Timeout of a single read or write on the connection, for any protocol
+    /// (`rw_timeout`).
+    pub fn rw_timeout(mut self, value: Duration) -> Self {
+        self.dictionary.set("rw_timeout", &value.as_micros().to_string());
+        self
+    }
+
+    /// HTTP and RTSP: `User-Agent` header sent with requests.
+    pub fn user_agent(mut self, value: &str) -> Self {
+        self.dictionary.set("user_agent", value);
+        self
+    }
+
+    /// HTTP: reconnect when the connection drops before the end of the resource
+    /// (`reconnect`). Non-seekable streams also need
+    /// [`reconnect_streamed()`](Self::reconnect_streamed).
+    pub fn reconnect(mut self, value: bool) -> Self {
+        self.dictionary.set("reconnect", if value { "1" } else { "0" });
+        self
+    }
+
+    /// HTTP: also reconnect live, non-seekable streams (`reconnect_streamed`).
+    pub fn reconnect_streamed(mut self, value: bool) -> Self {
+        self.dictionary.set("reconnect_streamed", if value { "1" } else { "0" });
+        self
+    }
+
+    /// HTTP: upper bound of the growing delay between reconnection attempts
+    /// (`reconnect_delay_max`), at a resolution of one second.
+    pub fn reconnect_delay_max(mut self, value: Duration) -> Self {
+        self.dictionary.set("reconnect_delay_max", &value.as_secs().to_string());
+        self
+    }
+
+    /// RTSP and UDP: size of the socket receive buffer in bytes (`buffer_size`).
+    /// High bitrate streams over UDP lose packets with the system default.
+    pub fn buffer_size(mut self, value: usize) -> Self {
+        self.dictionary.set("buffer_size", &value.to_string());
+        self
+    }
+
+    /// RTSP: how long to wait for reordered UDP packets (`max_delay`).
+    pub fn max_delay(mut self, value: Duration) -> Self {
+        self.dictionary.set("max_delay", &value.as_micros().to_string());
+        self
+    }
+
+    /// RTMP: request a live stream (`true`) or a recorded one (`false`) instead of
+    /// letting the server pick (`rtmp_live`).
+    pub fn rtmp_live(mut self, value: bool) -> Self {
+        self.dictionary.set("rtmp_live", if value { "live" } else { "recorded" });
+        self
+    }
+
+    /// RTMP: client buffer time (`rtmp_buffer`), at a resolution of one millisecond.
+    pub fn rtmp_buffer(mut self, value: Duration) -> Self {
+        self.dictionary.set("rtmp_buffer", &value.as_millis().to_string());
+        self
+    }
+
+    /// Sets any other option by name, for what the typed methods do not cover.
+    pub fn set(mut self, key: &str, value: &str) -> Self {
+        self.dictionary.set(key, value);
+        self
+    }
+
+    pub fn into_dictionary(self) -> Dictionary<'static> {
+        self.dictionary
+    }
+}
+
+impl<'a> From<Options> for Dictionary<'a> {
+    fn from(value: Options) -> Self {
+        value.dictionary
+    }
+}
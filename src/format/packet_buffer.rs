@@ -0,0 +1,146 @@
+use std::collections::VecDeque;
+
+use crate::{Packet, Rational, Rescale};
+
+/// What a full [`PacketBuffer`] does with a new packet.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum Overflow {
+    /// Evict the oldest packets until the new one fits.
+    DropOldest,
+    /// Keep the buffered packets and hand the new one back.
+    Reject,
+}
+
+/// Bounded FIFO of packets, e.g. a jitter buffer between a network receiver and a
+/// decoder.
+///
+/// The buffer keeps track of the payload bytes and the summed durations of the
+/// packets it holds, and never holds more than the configured number of bytes;
+/// what happens to packets beyond that is chosen with [`Overflow`]. A packet that
+/// is larger than the limit on its own is still accepted into an empty buffer, so
+/// the buffer cannot get stuck.
+///
+/// Packets are moved in and out without copying their data. The buffer is `Send`;
+/// wrap it in a `Mutex` to share it between a receiving and a decoding thread.
+///
+/// ```ignore
+/// let buffer = Arc::new(Mutex::new(format::PacketBuffer::new(stream.time_base(), 4 * 1024 * 1024, format::Overflow::DropOldest)));
+///
+/// // receiver thread, with its own clone of `buffer`
+/// let _ = buffer.lock().unwrap().push(packet);
+///
+/// // decoder thread, once enough is buffered
+/// let mut buffer = buffer.lock().unwrap();
+///
+/// if buffer.duration((1, 1000)) >= 200 {
+///     while let Some(packet) = buffer.pop() {
+///         decoder.send_packet(&packet)?;
+///     }
+/// }
+/// ```
+pub struct PacketBuffer {
+    packets: VecDeque<Packet>,
+    time_base: Rational,
+    max_bytes: usize,
+    overflow: Overflow,
+    bytes: usize,
+    duration: i64,
+    dropped: usize,
+}
+
+impl PacketBuffer {
+    /// Creates a buffer for packets whose timestamps are in `time_base`, holding at
+    /// most `max_bytes` bytes of packet data.
+    pub fn new<R: Into<Rational>>(time_base: R, max_bytes: usize, overflow: Overflow) -> Self {
+        PacketBuffer { packets: VecDeque::new(), time_base: time_base.into(), max_bytes, overflow, bytes: 0, duration: 0, dropped: 0 }
+    }
+
+    /// Appends a packet.
+    ///
+    /// Returns the packet back when the buffer is full and the policy is
+    /// [`Overflow::Reject`].
+    pub fn push(&mut self, packet: Packet) -> Result<(), Packet> {
+        let size = packet.size();
+
+        if self.bytes + size > self.max_bytes && !self.packets.is_empty() {
+            match self.overflow {
+                Overflow::Reject => return Err(packet),
+
+                Overflow::DropOldest => {
+                    while self.bytes + size > self.max_bytes && self.pop().is_some() {
+                        self.dropped += 1;
+                    }
+                }
+            }
+        }
+
+        self.bytes += size;
+        self.duration += packet.duration();
+        self.packets.push_back(packet);
+
+        Ok(())
+    }
+
+    /// Removes and returns the oldest packet.
+    pub fn pop(&mut self) -> Option<Packet> {
+        let packet = self.packets.pop_front()?;
+
+        self.bytes -= packet.size();
+        self.duration -= packet.duration();
+
+        Some(packet)
+    }
+
+    /// Returns the oldest packet without removing it.
+    pub fn peek(&self) -> Option<&Packet> {
+        self.packets.front()
+    }
+
+    /// Drops every buffered packet and resets the [`dropped()`](Self::dropped)
+    /// count, e.g. after seeking.
+    pub fn clear(&mut self) {
+        self.packets.clear();
+        self.bytes = 0;
+        self.duration = 0;
+        self.dropped = 0;
+    }
+
+    /// Total size of the buffered packet data, in bytes.
+    pub fn byte_size(&self) -> usize {
+        self.bytes
+    }
+
+    /// Sum of the durations of the buffered packets, rescaled to `time_base`.
+    ///
+    /// Packets without a duration count as zero.
+    pub fn duration<R: Into<Rational>>(&self, time_base: R) -> i64 {
+        self.duration.rescale(self.time_base, time_base)
+    }
+
+    /// Time base the buffered packets are expressed in.
+    pub fn time_base(&self) -> Rational {
+        self.time_base
+    }
+
+    pub fn max_bytes(&self) -> usize {
+        self.max_bytes
+    }
+
+    pub fn overflow(&self) -> Overflow {
+        self.overflow
+    }
+
+    /// Number of packets evicted by [`Overflow::DropOldest`] since the buffer was
+    /// created or last [cleared](Self::clear).
+    pub fn dropped(&self) -> usize {
+        self.dropped
+    }
+
+    pub fn len(&self) -> usize {
+        self.packets.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.packets.is_empty()
+    }
+}
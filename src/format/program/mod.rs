@@ -0,0 +1,2 @@
+mod program;
+pub use self::program::{Program, StreamIter};
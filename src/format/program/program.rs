@@ -0,0 +1,93 @@
+use std::slice;
+
+use crate::{DictionaryRef, Discard, Stream, ffi::*};
+
+use crate::format::context::common::Context;
+
+/// A program of a multi-program container, e.g. a channel of an MPEG-TS multiplex.
+///
+/// The index is the offset in the programs array, not the program id.
+pub struct Program<'a> {
+    context: &'a Context,
+    index: usize,
+}
+
+impl<'a> Program<'a> {
+    pub unsafe fn wrap(context: &Context, index: usize) -> Program<'_> {
+        Program { context, index }
+    }
+
+    pub unsafe fn as_ptr(&self) -> *const AVProgram {
+        unsafe { *(*self.context.as_ptr()).programs.add(self.index) }
+    }
+}
+
+impl<'a> Program<'a> {
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn id(&self) -> i32 {
+        unsafe { (*self.as_ptr()).id }
+    }
+
+    /// Program number, as signalled in the MPEG-TS PAT.
+    pub fn number(&self) -> i32 {
+        unsafe { (*self.as_ptr()).program_num }
+    }
+
+    pub fn pmt_pid(&self) -> i32 {
+        unsafe { (*self.as_ptr()).pmt_pid }
+    }
+
+    pub fn pcr_pid(&self) -> i32 {
+        unsafe { (*self.as_ptr()).pcr_pid }
+    }
+
+    pub fn discard(&self) -> Discard {
+        unsafe { Discard::from((*self.as_ptr()).discard) }
+    }
+
+    pub fn metadata(&self) -> DictionaryRef<'a> {
+        unsafe { DictionaryRef::wrap((*self.as_ptr()).metadata) }
+    }
+
+    /// Indices of the streams belonging to the program.
+    pub fn stream_indices(&self) -> &'a [u32] {
+        unsafe {
+            let program = self.as_ptr();
+
+            if (*program).nb_stream_indexes == 0 { &[] } else { slice::from_raw_parts((*program).stream_index, (*program).nb_stream_indexes as usize) }
+        }
+    }
+
+    /// Streams belonging to the program.
+    pub fn streams(&self) -> StreamIter<'a> {
+        StreamIter { context: self.context, indices: self.stream_indices().iter() }
+    }
+}
+
+impl<'a> PartialEq for Program<'a> {
+    fn eq(&self, other: &Self) -> bool {
+        unsafe { self.as_ptr() == other.as_ptr() }
+    }
+}
+
+pub struct StreamIter<'a> {
+    context: &'a Context,
+    indices: slice::Iter<'a, u32>,
+}
+
+impl<'a> Iterator for StreamIter<'a> {
+    type Item = Stream<'a>;
+
+    fn next(&mut self) -> Option<<Self as Iterator>::Item> {
+        self.indices.next().map(|&index| unsafe { Stream::wrap(self.context, index as usize) })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.indices.size_hint()
+    }
+}
+
+impl<'a> ExactSizeIterator for StreamIter<'a> {}
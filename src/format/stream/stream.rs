@@ -1,9 +1,10 @@
 use super::Disposition;
 use crate::{
-    DictionaryRef, Discard, Rational,
+    DictionaryRef, Discard, Rational, Rescale,
     codec::{self, packet},
     ffi::*,
     format::context::common::Context,
+    rescale::TIME_BASE,
 };
 use libc::c_int;
 
@@ -33,6 +34,13 @@ impl<'a> Stream<'a> {
         unsafe { codec::Context::wrap((*self.as_ptr()).codec, Some(self.context.destructor())) }
     }
 
+    /// Codec parameters of the stream, as declared by the container.
+    ///
+    /// This is a live view of the demuxer's parameters: it reflects what
+    /// `Input::find_stream_info()` (run by `format::input()`) filled in, and is not
+    /// updated by decoding. When the container may be wrong, use
+    /// [`decoder::Opened::parameters()`](crate::decoder::Opened::parameters) after the
+    /// first decoded frame instead.
     pub fn parameters(&self) -> codec::Parameters {
         unsafe { codec::Parameters::wrap((*self.as_ptr()).codecpar, Some(self.context.destructor())) }
     }
@@ -53,10 +61,64 @@ impl<'a> Stream<'a> {
         unsafe { (*self.as_ptr()).duration }
     }
 
+    /// Number of frames in the stream as stored by the container (`nb_frames`),
+    /// 0 when unknown. See [`nb_frames()`](Self::nb_frames).
     pub fn frames(&self) -> i64 {
         unsafe { (*self.as_ptr()).nb_frames }
     }
 
+    /// Number of frames in the stream, if the container stores it.
+    ///
+    /// Containers with a sample table (MP4, MOV) give the exact count; most
+    /// others (Matroska, MPEG-TS, raw streams) do not, in which case
+    /// [`estimated_frame_count()`](Self::estimated_frame_count) can guess it.
+    pub fn nb_frames(&self) -> Option<i64> {
+        match self.frames() {
+            frames if frames > 0 => Some(frames),
+            _ => None,
+        }
+    }
+
+    /// Number of frames, exact when the container stores it and otherwise
+    /// computed from the duration and the average frame rate. The duration of
+    /// the stream is used when known, that of the file otherwise.
+    ///
+    /// Returns `None` when neither the count nor the duration and frame rate are
+    /// known. The estimate can be off by a few frames for variable frame rate
+    /// streams and files whose streams have different lengths.
+    pub fn estimated_frame_count(&self) -> Option<i64> {
+        if let Some(frames) = self.nb_frames() {
+            return Some(frames);
+        }
+
+        let rate = match self.avg_frame_rate() {
+            rate if rate.numerator() > 0 && rate.denominator() > 0 => rate,
+            _ => self.real_frame_rate(),
+        };
+
+        if rate.numerator() <= 0 || rate.denominator() <= 0 {
+            return None;
+        }
+
+        let (duration, time_base) = match self.duration() {
+            AV_NOPTS_VALUE => (unsafe { (*self.context.as_ptr()).duration }, TIME_BASE),
+            duration => (duration, self.time_base()),
+        };
+
+        // AV_NOPTS_VALUE is negative too.
+        if duration > 0 { Some(duration.rescale(time_base, rate.invert())) } else { None }
+    }
+
+    /// Average bit rate of the stream in bits per second, if known. Many
+    /// containers only store the bit rate of the whole file, see
+    /// `Input::bit_rate()`.
+    pub fn bit_rate(&self) -> Option<i64> {
+        match unsafe { (*(*self.as_ptr()).codecpar).bit_rate } {
+            bit_rate if bit_rate > 0 => Some(bit_rate),
+            _ => None,
+        }
+    }
+
     pub fn disposition(&self) -> Disposition {
         unsafe { Disposition::from_bits_truncate((*self.as_ptr()).disposition) }
     }
@@ -69,14 +131,40 @@ impl<'a> Stream<'a> {
         SideDataIter::new(self)
     }
 
+    /// Same as [`real_frame_rate()`](Self::real_frame_rate).
     pub fn rate(&self) -> Rational {
         unsafe { Rational::from((*self.as_ptr()).r_frame_rate) }
     }
 
+    /// Real base frame rate of the stream (`r_frame_rate`).
+    ///
+    /// This is the lowest frame rate with which all timestamps can be represented
+    /// exactly, i.e. the least common multiple of the frame durations. Use it for
+    /// frame-exact work such as stepping or cutting on frame boundaries. It is a guess
+    /// made by the demuxer and may be 0/0 when unknown.
+    pub fn real_frame_rate(&self) -> Rational {
+        unsafe { Rational::from((*self.as_ptr()).r_frame_rate) }
+    }
+
+    /// Average frame rate over the whole stream (`avg_frame_rate`).
+    ///
+    /// For variable frame rate content this is a fractional average and does not
+    /// correspond to any actual frame duration; use it for display and bitrate
+    /// estimates, and [`real_frame_rate()`](Self::real_frame_rate) for frame-exact work.
     pub fn avg_frame_rate(&self) -> Rational {
         unsafe { Rational::from((*self.as_ptr()).avg_frame_rate) }
     }
 
+    /// Time base of the codec as guessed by libavformat (`av_stream_get_codec_timebase`).
+    ///
+    /// Mostly useful when remuxing, see
+    /// [`StreamMut::transfer_timing_from()`](super::StreamMut::transfer_timing_from).
+    /// Removed in FFmpeg 8.0.
+    #[cfg(not(feature = "ffmpeg_8_0"))]
+    pub fn codec_time_base(&self) -> Rational {
+        unsafe { Rational::from(av_stream_get_codec_timebase(self.as_ptr())) }
+    }
+
     pub fn metadata(&self) -> DictionaryRef<'_> {
         unsafe { DictionaryRef::wrap((*self.as_ptr()).metadata) }
     }
@@ -1,7 +1,7 @@
 use std::{mem, ops::Deref};
 
 use super::Stream;
-use crate::{Dictionary, Rational, codec, ffi::*, format::context::common::Context};
+use crate::{Dictionary, Discard, Error, Rational, codec, ffi::*, format::context::common::Context};
 
 pub struct StreamMut<'a> {
     context: &'a mut Context,
@@ -21,6 +21,9 @@ impl<'a> StreamMut<'a> {
 }
 
 impl<'a> StreamMut<'a> {
+    /// Requests a time base for the stream. This is only a hint for muxers: the
+    /// one actually used is known once the header is written, see
+    /// [`Output::write_header()`](crate::format::context::Output::write_header).
     pub fn set_time_base<R: Into<Rational>>(&mut self, value: R) {
         unsafe {
             (*self.as_mut_ptr()).time_base = value.into().into();
@@ -47,12 +50,143 @@ impl<'a> StreamMut<'a> {
         }
     }
 
+    /// Copies the codec parameters of an encoder into this stream.
+    ///
+    /// Encoders only fill in their extradata (e.g. the global header requested with
+    /// `codec::Flags::GLOBAL_HEADER`, which MP4 and Matroska require) while being opened,
+    /// so the order must be:
+    ///
+    /// 1. configure and open the encoder,
+    /// 2. call this method,
+    /// 3. write the output header.
+    ///
+    /// Copying before the encoder is opened silently produces a stream without
+    /// extradata, and files that most players refuse to decode.
+    pub fn set_parameters_from_context<C: AsRef<codec::Context>>(&mut self, context: &C) -> Result<(), Error> {
+        unsafe {
+            match avcodec_parameters_from_context((*self.as_mut_ptr()).codecpar, context.as_ref().as_ptr()) {
+                e if e < 0 => Err(Error::from(e)),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    /// Transfers the internal timing information of an input stream to this stream
+    /// (`avformat_transfer_internal_stream_timing_info`).
+    ///
+    /// When remuxing, copying the codec parameters alone loses the codec time base the
+    /// demuxer derived, which can make frame durations in the output drift slightly.
+    /// This fills in the stream time base and frame rates the way `ffmpeg -c copy`
+    /// does, letting the muxer pick the best source automatically. It must be called
+    /// after the parameters are copied and before the output header is written.
+    ///
+    /// Removed in FFmpeg 8.0.
+    #[cfg(not(feature = "ffmpeg_8_0"))]
+    pub fn transfer_timing_from(&mut self, input: &Stream) -> Result<(), Error> {
+        unsafe {
+            let format = (*self.context.as_ptr()).oformat;
+
+            match avformat_transfer_internal_stream_timing_info(format, self.as_mut_ptr(), input.as_ptr(), AVTimebaseSource::AVFMT_TBCF_AUTO) {
+                e if e < 0 => Err(Error::from(e)),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    /// Attaches a display matrix rotating the video by `degrees` counterclockwise
+    /// when played, replacing any previous one. Any angle is accepted, not only
+    /// multiples of 90; the convention is the one of `ffmpeg -display_rotation`
+    /// and `av_display_rotation_get()`, so copying the rotation read from an input
+    /// stream preserves it.
+    ///
+    /// Copying the codec parameters does not carry the rotation of a remuxed
+    /// stream before FFmpeg 6.1, so it has to be set again before the header is
+    /// written. The side data lives in `AVStream.side_data` up to FFmpeg 6.0 and
+    /// in the `coded_side_data` of the codec parameters since 6.1 (the only
+    /// location from 8.0 on); this method writes to the appropriate one, but call
+    /// it after [`set_parameters()`](Self::set_parameters), which replaces the
+    /// codec parameters wholesale.
+    ///
+    /// Fails with `EINVAL` for an angle that is not finite.
+    pub fn set_display_matrix(&mut self, degrees: f64) -> Result<(), Error> {
+        if !degrees.is_finite() {
+            return Err(Error::Other { errno: libc::EINVAL });
+        }
+
+        unsafe {
+            let size = 9 * mem::size_of::<i32>();
+
+            #[cfg(feature = "ffmpeg_6_1")]
+            {
+                let parameters = (*self.as_mut_ptr()).codecpar;
+                let side_data = av_packet_side_data_new(&mut (*parameters).coded_side_data, &mut (*parameters).nb_coded_side_data, AVPacketSideDataType::AV_PKT_DATA_DISPLAYMATRIX, size, 0);
+
+                if side_data.is_null() {
+                    return Err(Error::Other { errno: libc::ENOMEM });
+                }
+
+                // av_display_rotation_set() takes a clockwise angle.
+                av_display_rotation_set((*side_data).data as *mut i32, -degrees);
+            }
+
+            #[cfg(not(feature = "ffmpeg_6_1"))]
+            {
+                let matrix = av_malloc(size) as *mut i32;
+
+                if matrix.is_null() {
+                    return Err(Error::Other { errno: libc::ENOMEM });
+                }
+
+                av_display_rotation_set(matrix, -degrees);
+
+                let res = av_stream_add_side_data(self.as_mut_ptr(), AVPacketSideDataType::AV_PKT_DATA_DISPLAYMATRIX, matrix as *mut u8, size as _);
+
+                if res < 0 {
+                    av_free(matrix as *mut libc::c_void);
+                    return Err(Error::from(res));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Sets which packets of the stream the demuxer drops (`AVStream::discard`).
+    ///
+    /// With [`Discard::All`] the packets of the stream are skipped inside
+    /// `av_read_frame`, before they are parsed or returned, which is cheaper than
+    /// filtering them by index after reading: a video-only analysis can discard
+    /// every other stream. Containers storing streams in separate places (MP4,
+    /// Matroska with cues, ...) may also skip reading their data; interleaved ones
+    /// still have to read through it.
+    ///
+    /// The demuxer checks the setting as it reads, so set it before reading
+    /// packets for it to apply from the start. The other values
+    /// (`NonKey`, `NonReference`, ...) are only honored by some demuxers and are
+    /// mostly meant for decoders.
+    pub fn set_discard(&mut self, value: Discard) {
+        unsafe {
+            (*self.as_mut_ptr()).discard = value.into();
+        }
+    }
+
     pub fn set_metadata(&mut self, metadata: Dictionary) {
         unsafe {
             let metadata = metadata.disown();
             (*self.as_mut_ptr()).metadata = metadata;
         }
     }
+
+    /// Copies all tags of `stream` (language, title, handler name, ...), replacing
+    /// tags with the same key.
+    pub fn copy_metadata_from(&mut self, stream: &Stream) -> Result<(), Error> {
+        unsafe {
+            match av_dict_copy(&mut (*self.as_mut_ptr()).metadata, (*stream.as_ptr()).metadata, 0) {
+                e if e < 0 => Err(Error::from(e)),
+                _ => Ok(()),
+            }
+        }
+    }
 }
 
 impl<'a> Deref for StreamMut<'a> {
@@ -62,3 +196,68 @@ impl<'a> Deref for StreamMut<'a> {
         &self.immutable
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+    #[cfg(not(feature = "ffmpeg_6_1"))]
+    use std::ptr;
+
+    use crate::{codec, encoder, ffi::*, format};
+
+    #[test]
+    fn test_display_matrix() {
+        let path = env::temp_dir().join(format!("playa-ffmpeg-rotation-{}.mkv", std::process::id()));
+        let mut output = format::output(&path).unwrap();
+        let mut stream = output.add_stream(encoder::find(codec::Id::None)).unwrap();
+        stream.set_display_matrix(90.0).unwrap();
+
+        unsafe {
+            #[cfg(feature = "ffmpeg_6_1")]
+            let matrix = {
+                let parameters = (*stream.as_ptr()).codecpar;
+                (*av_packet_side_data_get((*parameters).coded_side_data, (*parameters).nb_coded_side_data, AVPacketSideDataType::AV_PKT_DATA_DISPLAYMATRIX)).data
+            };
+            #[cfg(not(feature = "ffmpeg_6_1"))]
+            let matrix = av_stream_get_side_data(stream.as_ptr(), AVPacketSideDataType::AV_PKT_DATA_DISPLAYMATRIX, ptr::null_mut());
+
+            assert!((av_display_rotation_get(matrix as *const i32) - 90.0).abs() < 1e-6);
+        }
+
+        drop(output);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_parameters_from_opened_encoder() {
+        let path = env::temp_dir().join(format!("playa-ffmpeg-extradata-{}.mkv", std::process::id()));
+        let codec = encoder::find(codec::Id::MPEG4).unwrap();
+        let mut output = format::output(&path).unwrap();
+
+        let mut context = codec::Context::new_with_codec(codec);
+        context.set_flags(codec::Flags::GLOBAL_HEADER);
+
+        let mut video = context.encoder().video().unwrap();
+        video.set_width(64);
+        video.set_height(64);
+        video.set_format(format::Pixel::YUV420P);
+        video.set_time_base((1, 25));
+
+        let encoder = video.open_as(codec).unwrap();
+
+        {
+            let mut stream = output.add_stream(codec).unwrap();
+            stream.set_time_base((1, 25));
+            stream.set_parameters_from_context(&encoder).unwrap();
+        }
+
+        output.write_header().unwrap();
+        output.write_trailer().unwrap();
+
+        let input = format::input(&path).unwrap();
+        let parameters = input.stream(0).unwrap().parameters();
+        assert!(unsafe { (*parameters.as_ptr()).extradata_size } > 0);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,186 @@
+use std::path::Path;
+
+use super::context::{Input, Output};
+use crate::{Error, Packet, Rational, Rescale, codec, encoder, ffi::*, media, rescale::TIME_BASE};
+
+/// Cuts the part of a file between two times without re-encoding, like
+/// `ffmpeg -ss start -to end -c copy`.
+///
+/// Packets can only be copied from a keyframe on, so the start snaps backward
+/// to the keyframe of the video stream at or before the requested time: the clip
+/// may begin earlier than asked, never later. Audio packets can all be decoded on
+/// their own, so audio (and subtitles) start at the first packet at or after that
+/// keyframe, which keeps them in sync with the video. Files without video start
+/// at the requested time with the granularity of their packets, and so do the
+/// other streams when the video has no keyframe after the start.
+///
+/// Timestamps are rebased so that the clip starts at zero. The end is not
+/// aligned: packets are copied until their decode timestamp reaches it.
+///
+/// ```ignore
+/// // from 1:00 to 1:30, in microseconds
+/// let start = format::Trimmer::new(60_000_000, Some(90_000_000)).run(&"in.mp4", &"clip.mp4")?;
+/// ```
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct Trimmer {
+    start: i64,
+    end: Option<i64>,
+}
+
+impl Trimmer {
+    /// Creates a trimmer keeping `start..end`, in `AV_TIME_BASE` units
+    /// (microseconds) relative to the start of the file as reported by the
+    /// demuxer. `None` keeps everything after `start`.
+    pub fn new(start: i64, end: Option<i64>) -> Self {
+        Trimmer { start, end }
+    }
+
+    /// Trims the file at `input` into a new file at `output`, whose format is
+    /// guessed from its extension, and returns the actual start of the clip in
+    /// `AV_TIME_BASE` units.
+    pub fn run<P: AsRef<Path> + ?Sized, Q: AsRef<Path> + ?Sized>(&self, input: &P, output: &Q) -> Result<i64, Error> {
+        let mut input = super::input(input)?;
+        let mut output = super::output(output)?;
+
+        self.trim(&mut input, &mut output)
+    }
+
+    /// Copies the audio, video and subtitle streams of `input` to `output`, which
+    /// must not have any stream or header yet, then writes the header, the clip
+    /// and the trailer. Returns the actual start of the clip in `AV_TIME_BASE`
+    /// units.
+    ///
+    /// Fails with `EINVAL` if `start` is negative or `end` is not after it.
+    pub fn trim(&self, input: &mut Input, output: &mut Output) -> Result<i64, Error> {
+        if self.start < 0 || self.end.is_some_and(|end| end <= self.start) {
+            return Err(Error::Other { errno: libc::EINVAL });
+        }
+
+        let reference = input.streams().best(media::Type::Video).map(|stream| stream.index());
+        let mut mapping = vec![None; input.nb_streams() as usize];
+        let mut time_bases = vec![Rational(0, 1); input.nb_streams() as usize];
+
+        for stream in input.streams() {
+            let medium = stream.parameters().medium();
+
+            if medium != media::Type::Audio && medium != media::Type::Video && medium != media::Type::Subtitle {
+                continue;
+            }
+
+            let mut target = output.add_stream(encoder::find(codec::Id::None))?;
+            target.set_parameters(stream.parameters());
+            target.set_time_base(stream.time_base());
+
+            unsafe {
+                (*target.parameters().as_mut_ptr()).codec_tag = 0;
+            }
+
+            mapping[stream.index()] = Some(target.index());
+            time_bases[stream.index()] = stream.time_base();
+        }
+
+        output.set_metadata(input.metadata().to_owned());
+        output.write_header()?;
+
+        // The times are relative to the start of the file, the timestamps are not,
+        // unless the input normalizes them (which `seek()` takes into account too).
+        let base = if input.start_time() == AV_NOPTS_VALUE { 0 } else { input.start_time() };
+        let base = base - input.timestamp_offset().unwrap_or(0);
+        let end = self.end.map(|end| end.saturating_add(base));
+
+        if self.start > 0 {
+            let start = self.start.saturating_add(base);
+            input.seek(start, ..start)?;
+        }
+
+        // Start of the clip, known once the first video keyframe is read; packets
+        // of the other streams read before it wait in `pending`.
+        let mut offset = if reference.is_none() { Some(self.start.saturating_add(base)) } else { None };
+        let mut pending = Vec::new();
+        let mut ended = mapping.iter().map(Option::is_none).collect::<Vec<_>>();
+
+        while !ended.iter().all(|&ended| ended) {
+            let mut packet = Packet::empty();
+
+            if !input.read(&mut packet)? {
+                break;
+            }
+
+            let index = packet.stream();
+
+            if mapping[index].is_none() || ended[index] {
+                continue;
+            }
+
+            let Some(offset) = offset else {
+                if past_end(&packet, end, time_bases[index]) {
+                    ended[index] = true;
+                } else if Some(index) != reference {
+                    pending.push(packet);
+                } else if let (true, Some(pts)) = (packet.is_key(), packet.pts()) {
+                    let start = pts.rescale(time_bases[index], TIME_BASE);
+                    offset = Some(start);
+
+                    for packet in pending.drain(..).chain([packet]) {
+                        let index = packet.stream();
+                        ended[index] |= !copy(packet, start, end, time_bases[index], mapping[index].unwrap(), output)?;
+                    }
+                }
+
+                continue;
+            };
+
+            ended[index] = !copy(packet, offset, end, time_bases[index], mapping[index].unwrap(), output)?;
+        }
+
+        // No usable video keyframe: keep the other streams from the requested start.
+        if offset.is_none() && !pending.is_empty() {
+            let start = self.start.saturating_add(base);
+            offset = Some(start);
+
+            // Packets past the end never wait, so all of these belong to the clip.
+            for packet in pending {
+                let index = packet.stream();
+                copy(packet, start, end, time_bases[index], mapping[index].unwrap(), output)?;
+            }
+        }
+
+        output.write_trailer()?;
+
+        Ok(offset.map_or(self.start, |offset| offset - base))
+    }
+}
+
+// Writes `packet` to stream `stream` of `output`, rebased on `offset` (in
+// `AV_TIME_BASE`, like `end`). Packets before the offset are dropped; returns
+// `false` once the packet is past the end of the clip.
+fn copy(mut packet: Packet, offset: i64, end: Option<i64>, time_base: Rational, stream: usize, output: &mut Output) -> Result<bool, Error> {
+    if past_end(&packet, end, time_base) {
+        return Ok(false);
+    }
+
+    if packet.pts().is_some_and(|pts| unsafe { av_compare_ts(pts, time_base.into(), offset, AV_TIME_BASE_Q) } < 0) {
+        return Ok(true);
+    }
+
+    let shift = offset.rescale(TIME_BASE, time_base);
+    packet.set_pts(packet.pts().map(|pts| pts - shift));
+    packet.set_dts(packet.dts().map(|dts| dts - shift));
+
+    let destination = output.stream(stream).ok_or(Error::StreamNotFound)?.time_base();
+    packet.rescale_ts(time_base, destination);
+    packet.set_position(-1);
+    packet.set_stream(stream);
+    packet.write_interleaved(output)?;
+
+    Ok(true)
+}
+
+fn past_end(packet: &Packet, end: Option<i64>, time_base: Rational) -> bool {
+    let timestamp = packet.dts().or(packet.pts());
+
+    match (timestamp, end) {
+        (Some(timestamp), Some(end)) => unsafe { av_compare_ts(timestamp, time_base.into(), end, AV_TIME_BASE_Q) >= 0 },
+        _ => false,
+    }
+}
@@ -0,0 +1,188 @@
+use std::{error, fmt, time::Duration};
+
+use crate::{Frame, Packet, Rational, Rescale, rescale::TIME_BASE};
+
+bitflags! {
+    /// Checks run by a [`TimestampValidator`].
+    #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+    pub struct Checks: u32 {
+        /// DTS (pts for frames) not greater than the previous one of the stream.
+        const NON_MONOTONIC = 1 << 0;
+        /// Packet presented before it is decoded.
+        const PTS_BEFORE_DTS = 1 << 1;
+        /// Jump between consecutive timestamps of a stream larger than the
+        /// [maximum gap](TimestampValidator::set_max_gap).
+        const GAP = 1 << 2;
+        /// Packet or frame without any timestamp.
+        const MISSING = 1 << 3;
+    }
+}
+
+impl Default for Checks {
+    fn default() -> Self {
+        Checks::all()
+    }
+}
+
+/// Timestamp problem found by a [`TimestampValidator`].
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum Anomaly {
+    /// The timestamp is not greater than the previous one.
+    NonMonotonic { timestamp: i64, previous: i64 },
+    /// The pts is smaller than the dts.
+    PtsBeforeDts { pts: i64, dts: i64 },
+    /// The timestamp is this far from the previous one, in either direction.
+    Gap { timestamp: i64, previous: i64 },
+    /// Neither pts nor dts is set.
+    Missing,
+}
+
+/// An [`Anomaly`] on a packet or frame of a stream. Timestamps are in
+/// `time_base`; the [`Display`](fmt::Display) output gives them in seconds as
+/// well, ready to be logged.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct Issue {
+    pub stream: usize,
+    pub time_base: Rational,
+    pub anomaly: Anomaly,
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let seconds = |timestamp: i64| timestamp as f64 * f64::from(self.time_base);
+
+        write!(f, "stream {}: ", self.stream)?;
+
+        match self.anomaly {
+            Anomaly::NonMonotonic { timestamp, previous } => write!(f, "timestamp {timestamp} ({:.3}s) is not after the previous one {previous} ({:.3}s)", seconds(timestamp), seconds(previous)),
+            Anomaly::PtsBeforeDts { pts, dts } => write!(f, "pts {pts} ({:.3}s) is before dts {dts} ({:.3}s)", seconds(pts), seconds(dts)),
+            Anomaly::Gap { timestamp, previous } => write!(f, "timestamp jumps by {:.3}s from {previous} ({:.3}s) to {timestamp} ({:.3}s)", seconds(timestamp.saturating_sub(previous)), seconds(previous), seconds(timestamp)),
+            Anomaly::Missing => write!(f, "no timestamp"),
+        }
+    }
+}
+
+impl error::Error for Issue {}
+
+/// Inspects the timestamps of a sequence of packets or frames and reports
+/// anomalies, e.g. to find out where A/V sync goes wrong. Nothing is modified.
+///
+/// Packets are checked on their decode timestamps (or the pts when there is no
+/// dts), frames on their presentation timestamps, each stream on its own. Use
+/// one validator per stage, as the timestamps of the same stream before and
+/// after decoding do not follow the same order.
+///
+/// ```ignore
+/// let mut validator = format::TimestampValidator::new(Checks::default());
+///
+/// for (stream, packet) in input.packets() {
+///     for issue in validator.check_packet(&packet, stream.time_base()) {
+///         log::warn!("{issue}");
+///     }
+/// }
+/// ```
+pub struct TimestampValidator {
+    checks: Checks,
+    max_gap: i64,
+    last: Vec<Option<i64>>,
+}
+
+impl TimestampValidator {
+    /// Creates a validator running `checks`, with a maximum gap of 1 second.
+    pub fn new(checks: Checks) -> Self {
+        TimestampValidator { checks, max_gap: 1_000_000, last: Vec::new() }
+    }
+
+    /// The checks run.
+    pub fn checks(&self) -> Checks {
+        self.checks
+    }
+
+    /// Sets the largest jump between consecutive timestamps of a stream that is
+    /// not reported as a [`Anomaly::Gap`].
+    pub fn set_max_gap(&mut self, value: Duration) {
+        self.max_gap = i64::try_from(value.as_micros()).unwrap_or(i64::MAX);
+    }
+
+    /// Forgets the previous timestamps, e.g. after seeking.
+    pub fn reset(&mut self) {
+        self.last.clear();
+    }
+
+    /// Checks `packet` of stream [`Packet::stream()`], whose timestamps are in
+    /// `time_base`, and returns the anomalies found.
+    pub fn check_packet(&mut self, packet: &Packet, time_base: Rational) -> Vec<Issue> {
+        let mut issues = Vec::new();
+        let stream = packet.stream();
+
+        if let (Some(pts), Some(dts)) = (packet.pts(), packet.dts()) {
+            if self.checks.contains(Checks::PTS_BEFORE_DTS) && pts < dts {
+                issues.push(Issue { stream, time_base, anomaly: Anomaly::PtsBeforeDts { pts, dts } });
+            }
+        }
+
+        self.check(stream, packet.dts().or(packet.pts()), time_base, &mut issues);
+
+        issues
+    }
+
+    /// Checks `frame` of stream `stream`, whose pts is in `time_base`, and returns
+    /// the anomalies found.
+    pub fn check_frame(&mut self, stream: usize, frame: &Frame, time_base: Rational) -> Vec<Issue> {
+        let mut issues = Vec::new();
+
+        self.check(stream, frame.pts(), time_base, &mut issues);
+
+        issues
+    }
+
+    fn check(&mut self, stream: usize, timestamp: Option<i64>, time_base: Rational, issues: &mut Vec<Issue>) {
+        let Some(timestamp) = timestamp else {
+            if self.checks.contains(Checks::MISSING) {
+                issues.push(Issue { stream, time_base, anomaly: Anomaly::Missing });
+            }
+
+            return;
+        };
+
+        if stream >= self.last.len() {
+            self.last.resize(stream + 1, None);
+        }
+
+        if let Some(previous) = self.last[stream] {
+            if self.checks.contains(Checks::NON_MONOTONIC) && timestamp <= previous {
+                issues.push(Issue { stream, time_base, anomaly: Anomaly::NonMonotonic { timestamp, previous } });
+            }
+
+            if self.checks.contains(Checks::GAP) && timestamp.saturating_sub(previous).saturating_abs().rescale(time_base, TIME_BASE) > self.max_gap {
+                issues.push(Issue { stream, time_base, anomaly: Anomaly::Gap { timestamp, previous } });
+            }
+        }
+
+        self.last[stream] = Some(timestamp);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn packet(pts: i64, dts: i64) -> Packet {
+        let mut packet = Packet::empty();
+        packet.set_pts(Some(pts));
+        packet.set_dts(Some(dts));
+
+        packet
+    }
+
+    #[test]
+    fn test_check_packet() {
+        let mut validator = TimestampValidator::new(Checks::default());
+        let time_base = Rational(1, 1000);
+
+        assert!(validator.check_packet(&packet(0, 0), time_base).is_empty());
+        assert!(validator.check_packet(&packet(80, 40), time_base).is_empty());
+        assert_eq!(validator.check_packet(&packet(20, 40), time_base).iter().map(|issue| issue.anomaly).collect::<Vec<_>>(), vec![Anomaly::PtsBeforeDts { pts: 20, dts: 40 }, Anomaly::NonMonotonic { timestamp: 40, previous: 40 }]);
+        assert_eq!(validator.check_packet(&packet(5000, 5000), time_base).iter().map(|issue| issue.anomaly).collect::<Vec<_>>(), vec![Anomaly::Gap { timestamp: 5000, previous: 40 }]);
+    }
+}
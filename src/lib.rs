@@ -67,7 +67,7 @@ pub use crate::util::{
     dictionary::{Mut as DictionaryMut, Owned as Dictionary, Ref as DictionaryRef},
     error::{self, Error},
     frame::{self, Frame},
-    log,
+    hwaccel, interrupt, log,
     mathematics::{self, Rescale, Rounding, rescale},
     media, option, picture,
     rational::{self, Rational},
@@ -81,6 +81,8 @@ pub use crate::format::chapter::{Chapter, ChapterMut};
 #[cfg(feature = "format")]
 pub use crate::format::format::Format;
 #[cfg(feature = "format")]
+pub use crate::format::program::Program;
+#[cfg(feature = "format")]
 pub use crate::format::stream::{Stream, StreamMut};
 
 #[cfg(feature = "codec")]
@@ -167,7 +169,8 @@ fn init_filter() {}
 ///
 /// - In FFmpeg 5.0+, most subsystems auto-register, but this call is still required for
 ///   error handling and device initialization.
-/// - This function is thread-safe and can be called multiple times (subsequent calls are no-ops).
+/// - This function is thread-safe and can be called multiple times. Registration runs
+///   once; concurrent callers block until it has completed, and later calls are no-ops.
 /// - The `ffmpeg4`/`ffmpeg41`/`ffmpeg42`/`ffmpeg43` feature flags are deprecated as version
 ///   detection is now automatic.
 ///
@@ -194,12 +197,33 @@ fn init_filter() {}
         and will be removed in a future version")
 )]
 pub fn init() -> Result<(), Error> {
-    init_error();
-    #[cfg(not(feature = "ffmpeg_5_0"))]
-    init_format();
-    init_device();
-    #[cfg(not(feature = "ffmpeg_5_0"))]
-    init_filter();
+    static INIT: std::sync::Once = std::sync::Once::new();
+
+    INIT.call_once(|| {
+        init_error();
+        #[cfg(not(feature = "ffmpeg_5_0"))]
+        init_format();
+        init_device();
+        #[cfg(not(feature = "ffmpeg_5_0"))]
+        init_filter();
+    });
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+
+    #[test]
+    #[allow(deprecated)]
+    fn init_concurrently() {
+        let handles: Vec<_> = (0..16).map(|_| thread::spawn(super::init)).collect();
+
+        for handle in handles {
+            assert!(handle.join().unwrap().is_ok());
+        }
+
+        assert!(super::init().is_ok());
+    }
+}
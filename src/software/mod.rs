@@ -18,6 +18,13 @@
 //! - Changing sample rate (e.g., 48kHz → 44.1kHz)
 //! - Converting sample formats (s16 → f32, planar ↔ packed)
 //! - Channel layout conversion (stereo → 5.1)
+//!
+//! # Thumbnails
+//!
+//! [`to_srgb()`] converts decoded frames of any color space to sRGB RGB24.
+//!
+//! [`ThumbnailSheet`] combines seeking, keyframe decoding and scaling to build
+//! contact sheets of videos.
 
 #[cfg(feature = "software-scaling")]
 pub mod scaling;
@@ -55,6 +62,49 @@ pub fn converter((width, height): (u32, u32), input: crate::format::Pixel, outpu
     scaling::Context::get(input, width, height, output, width, height, scaling::flag::Flags::FAST_BILINEAR)
 }
 
+/// Display dimensions of a `width × height` picture with sample aspect ratio
+/// `aspect` in `format`, for square pixels.
+///
+/// The height is kept and the width stretched (or shrunk) by the aspect ratio, so
+/// a 720×576 DVD frame with a SAR of 16:11 becomes 1047×576, rounded to 1048×576
+/// since the width of subsampled formats such as YUV420P must be a multiple of
+/// the chroma subsampling. An unknown (zero) aspect ratio is taken as square.
+#[cfg(feature = "software-scaling")]
+pub fn square_pixel_dimensions(format: crate::format::Pixel, aspect: crate::Rational, (width, height): (u32, u32)) -> (u32, u32) {
+    let (align_width, align_height) = format.descriptor().map_or((1, 1), |descriptor| (1u32 << descriptor.log2_chroma_w(), 1u32 << descriptor.log2_chroma_h()));
+    let align = |value: u64, step: u32| ((value + u64::from(step) / 2) / u64::from(step) * u64::from(step)).max(u64::from(step)) as u32;
+
+    let width = if aspect.numerator() > 0 && aspect.denominator() > 0 { (u64::from(width) * aspect.numerator() as u64 + aspect.denominator() as u64 / 2) / aspect.denominator() as u64 } else { u64::from(width) };
+
+    (align(width, align_width), align(u64::from(height), align_height))
+}
+
+/// Creates a scaler turning anamorphic `(in_width, in_height)` frames with sample
+/// aspect ratio `aspect` into square pixel ones of the same pixel format, sized by
+/// [`square_pixel_dimensions()`].
+///
+/// Read the aspect ratio from the decoded frame
+/// ([`frame::Video::aspect_ratio()`](crate::frame::Video::aspect_ratio)) or, since
+/// containers and bitstreams can disagree, from
+/// [`Input::guess_aspect_ratio()`](crate::format::context::Input::guess_aspect_ratio).
+/// Frames produced by the scaler should be tagged with a `1:1` aspect ratio.
+#[cfg(feature = "software-scaling")]
+pub fn square_pixel_scaler(format: crate::format::Pixel, flags: scaling::Flags, aspect: crate::Rational, (in_width, in_height): (u32, u32)) -> Result<scaling::Context, crate::Error> {
+    let (out_width, out_height) = square_pixel_dimensions(format, aspect, (in_width, in_height));
+
+    scaling::Context::get(format, in_width, in_height, format, out_width, out_height, flags)
+}
+
+#[cfg(all(feature = "software-scaling", feature = "filter"))]
+pub mod srgb;
+#[cfg(all(feature = "software-scaling", feature = "filter"))]
+pub use self::srgb::to_srgb;
+
+#[cfg(all(feature = "software-scaling", feature = "format"))]
+pub mod thumbnail;
+#[cfg(all(feature = "software-scaling", feature = "format"))]
+pub use self::thumbnail::ThumbnailSheet;
+
 #[cfg(feature = "software-resampling")]
 pub mod resampling;
 
@@ -1,7 +1,7 @@
 use std::ptr;
 
 use super::Delay;
-use crate::{ChannelLayout, Dictionary, Error, ffi::*, frame, util::format};
+use crate::{ChannelLayout, Dictionary, Error, Rational, ffi::*, frame, util::format};
 use libc::c_int;
 use std::ffi::c_void;
 
@@ -17,6 +17,8 @@ pub struct Context {
 
     input: Definition,
     output: Definition,
+
+    next_pts: Option<i64>,
 }
 
 unsafe impl Send for Context {}
@@ -69,7 +71,7 @@ impl Context {
                 match swr_init(ptr) {
                     e if e < 0 => Err(Error::from(e)),
 
-                    _ => Ok(Context { ptr, input: Definition { format: src_format, channel_layout: src_channel_layout, rate: src_rate }, output: Definition { format: dst_format, channel_layout: dst_channel_layout, rate: dst_rate } }),
+                    _ => Ok(Context { ptr, input: Definition { format: src_format, channel_layout: src_channel_layout, rate: src_rate }, output: Definition { format: dst_format, channel_layout: dst_channel_layout, rate: dst_rate }, next_pts: None }),
                 }
             } else {
                 Err(Error::InvalidData)
@@ -118,6 +120,99 @@ impl Context {
         }
     }
 
+    /// Run the resampler like [`run()`](Self::run), computing the output timestamp from
+    /// the resampler state (`swr_next_pts`).
+    ///
+    /// `time_base` is the time base of the input frame's pts. The output pts is in
+    /// `1 / output rate` and is also set on `output`; it accounts for the samples
+    /// buffered inside the resampler, so consecutive output frames are contiguous and
+    /// gap-free even when the rates differ. Small drifts between input timestamps and
+    /// sample counts are compensated as well when `async` compensation is enabled in
+    /// the options. Returns `None` if the input frame has no pts.
+    pub fn run_with_pts<R: Into<Rational>>(&mut self, input: &frame::Audio, time_base: R, output: &mut frame::Audio) -> Result<Option<i64>, Error> {
+        let time_base = time_base.into();
+        let rates = i64::from(self.input.rate) * i64::from(self.output.rate);
+
+        let pts = input.pts().map(|pts| unsafe {
+            let pts = av_rescale(pts, i64::from(time_base.numerator()) * rates, i64::from(time_base.denominator()));
+            let next = swr_next_pts(self.as_mut_ptr(), pts);
+
+            // Rounded division back to 1 / output rate.
+            (next + i64::from(self.input.rate) / 2) / i64::from(self.input.rate)
+        });
+
+        self.run(input, output)?;
+
+        output.set_pts(pts);
+        self.next_pts = pts.map(|pts| pts + output.samples() as i64);
+
+        Ok(pts)
+    }
+
+    /// Convert one of the remaining internal frames, continuing the timestamps of
+    /// [`run_with_pts()`](Self::run_with_pts).
+    ///
+    /// Returns the pts set on `output`, if any.
+    pub fn flush_with_pts(&mut self, output: &mut frame::Audio) -> Result<Option<i64>, Error> {
+        self.flush(output)?;
+
+        let pts = self.next_pts;
+
+        output.set_pts(pts);
+        self.next_pts = pts.map(|pts| pts + output.samples() as i64);
+
+        Ok(pts)
+    }
+
+    /// Converts `input` into a newly allocated frame, stamped like with
+    /// [`run_with_pts()`](Self::run_with_pts) (`time_base` is the time base of the
+    /// input pts, the output pts is in `1 / output rate`).
+    ///
+    /// The frame is sized with `swr_get_out_samples` for the input and the
+    /// samples still buffered in the resampler, so it is always large enough. The
+    /// number of samples it holds varies from call to call: when changing rates
+    /// some samples stay buffered for the next call, and a small input can even
+    /// give an empty frame. Use [`resample_flush()`](Self::resample_flush) at the
+    /// end of the stream to get the buffered samples.
+    ///
+    /// ```ignore
+    /// let mut resampler = decoder.resampler(format::Sample::F32(Type::Planar), ChannelLayout::STEREO, 48000)?;
+    ///
+    /// while decoder.receive_frame(&mut decoded).is_ok() {
+    ///     let resampled = resampler.resample(&decoded, stream.time_base())?;
+    ///     encoder.send_frame(&resampled)?;
+    /// }
+    /// ```
+    pub fn resample<R: Into<Rational>>(&mut self, input: &frame::Audio, time_base: R) -> Result<frame::Audio, Error> {
+        let mut output = self.output_frame(input.samples())?;
+        self.run_with_pts(input, time_base, &mut output)?;
+
+        Ok(output)
+    }
+
+    /// Returns the samples left in the resampler after the last
+    /// [`resample()`](Self::resample) in a newly allocated frame continuing its
+    /// timestamps, or `None` if there are none.
+    pub fn resample_flush(&mut self) -> Result<Option<frame::Audio>, Error> {
+        let mut output = self.output_frame(0)?;
+        self.flush_with_pts(&mut output)?;
+
+        if output.samples() == 0 { Ok(None) } else { Ok(Some(output)) }
+    }
+
+    // Allocates a frame for the output of `samples` more input samples, along
+    // with the ones buffered.
+    fn output_frame(&mut self, samples: usize) -> Result<frame::Audio, Error> {
+        let count = unsafe { swr_get_out_samples(self.as_mut_ptr(), c_int::try_from(samples).map_err(|_| Error::Other { errno: libc::EINVAL })?) };
+
+        if count < 0 {
+            return Err(Error::from(count));
+        }
+
+        // av_frame_get_buffer() refuses frames without samples.
+        Ok(frame::Audio::new(self.output.format, (count as usize).max(1), self.output.channel_layout))
+    }
+
     /// Convert one of the remaining internal frames.
     ///
     /// When there are no more internal frames `Ok(None)` will be returned.
@@ -143,3 +238,48 @@ impl Drop for Context {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resample_timestamps() {
+        let sample = format::Sample::I16(format::sample::Type::Packed);
+        let mut context = Context::get(sample, ChannelLayout::STEREO, 48000, sample, ChannelLayout::STEREO, 44100).unwrap();
+        let mut frames = Vec::new();
+
+        for index in 0..20 {
+            let mut input = frame::Audio::new(sample, 1024, ChannelLayout::STEREO);
+            input.set_pts(Some(index * 1024));
+
+            let mut output = frame::Audio::empty();
+            let pts = context.run_with_pts(&input, (1, 48000), &mut output).unwrap();
+            assert_eq!(pts, output.pts());
+
+            frames.push(output);
+        }
+
+        loop {
+            let mut output = frame::Audio::new(sample, 1024, ChannelLayout::STEREO);
+            let pts = context.flush_with_pts(&mut output).unwrap();
+
+            if output.samples() == 0 {
+                break;
+            }
+
+            assert_eq!(pts, output.pts());
+            frames.push(output);
+        }
+
+        // Each frame starts where the previous one ended.
+        let mut next = 0;
+
+        for frame in &frames {
+            assert_eq!(frame.pts(), Some(next));
+            next += frame.samples() as i64;
+        }
+
+        assert!((next - 20 * 1024 * 44100 / 48000).abs() <= 16, "{next} samples");
+    }
+}
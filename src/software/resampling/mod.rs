@@ -16,6 +16,9 @@ pub use self::delay::Delay;
 pub mod context;
 pub use self::context::Context;
 
+pub mod reader;
+pub use self::reader::Reader;
+
 mod extensions;
 
 use std::{ffi::CStr, str::from_utf8_unchecked};
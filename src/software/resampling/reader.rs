@@ -0,0 +1,127 @@
+use std::io;
+
+use super::Context;
+use crate::{Error, frame, util::format};
+
+/// Exposes a stream of decoded audio frames as interleaved PCM bytes through
+/// [`std::io::Read`].
+///
+/// Frames are converted to the requested sample format (always packed, planar formats
+/// are turned into their packed counterpart) while keeping their channel layout and
+/// sample rate. Bytes that do not fit in the caller's buffer are kept for the next
+/// read, so reads may cross frame boundaries freely.
+///
+/// The source is any iterator of decoded frames, e.g. a closure-backed iterator that
+/// feeds packets to a [`decoder::Audio`](crate::decoder::Audio) and receives frames.
+///
+/// # Example
+///
+/// ```ignore
+/// let frames = std::iter::from_fn(|| next_decoded_frame());
+/// let mut pcm = resampling::Reader::new(frames, format::Sample::I16(format::sample::Type::Packed));
+/// std::io::copy(&mut pcm, &mut output)?;
+/// ```
+pub struct Reader<I> {
+    frames: I,
+    format: format::Sample,
+    context: Option<Context>,
+    buffer: Vec<u8>,
+    position: usize,
+    finished: bool,
+}
+
+impl<I> Reader<I>
+where
+    I: Iterator<Item = Result<frame::Audio, Error>>,
+{
+    /// Creates a reader producing samples in `format` (converted to packed if planar).
+    pub fn new(frames: I, format: format::Sample) -> Self {
+        Reader { frames, format: format.packed(), context: None, buffer: Vec::new(), position: 0, finished: false }
+    }
+
+    /// The packed sample format of the produced bytes.
+    pub fn format(&self) -> format::Sample {
+        self.format
+    }
+
+    /// Consumes the reader, returning the underlying frame source.
+    pub fn into_inner(self) -> I {
+        self.frames
+    }
+
+    fn fill(&mut self) -> Result<(), Error> {
+        self.buffer.clear();
+        self.position = 0;
+
+        while self.buffer.is_empty() && !self.finished {
+            match self.frames.next() {
+                Some(frame) => {
+                    let frame = frame?;
+
+                    let stale = match self.context {
+                        Some(ref context) => context.input().format != frame.format() || context.input().channel_layout != frame.channel_layout() || context.input().rate != frame.rate(),
+                        None => true,
+                    };
+
+                    if stale {
+                        self.drain()?;
+                        self.context = Some(frame.resampler(self.format, frame.channel_layout(), frame.rate())?);
+                    }
+
+                    let mut converted = frame::Audio::empty();
+                    self.context.as_mut().unwrap().run(&frame, &mut converted)?;
+                    self.append(&converted);
+                }
+
+                None => {
+                    self.finished = true;
+                    self.drain()?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn drain(&mut self) -> Result<(), Error> {
+        if let Some(mut context) = self.context.take() {
+            while context.delay().is_some() {
+                let mut converted = frame::Audio::new(self.format, 1024, context.output().channel_layout);
+
+                context.flush(&mut converted)?;
+
+                if converted.samples() == 0 {
+                    break;
+                }
+
+                self.append(&converted);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn append(&mut self, frame: &frame::Audio) {
+        let len = frame.samples() * frame.channels() as usize * self.format.bytes();
+        self.buffer.extend_from_slice(&frame.data(0)[..len]);
+    }
+}
+
+impl<I> io::Read for Reader<I>
+where
+    I: Iterator<Item = Result<frame::Audio, Error>>,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.position >= self.buffer.len() {
+            self.fill()?;
+        }
+
+        let available = &self.buffer[self.position..];
+        let len = available.len().min(buf.len());
+
+        buf[..len].copy_from_slice(&available[..len]);
+        self.position += len;
+
+        Ok(len)
+    }
+}
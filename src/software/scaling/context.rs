@@ -1,8 +1,14 @@
-use std::ptr;
+use std::{ffi::CString, ptr, slice};
 
-use super::Flags;
-use crate::{Error, ffi::*, frame, util::format};
-use libc::c_int;
+use super::{Dither, Flags};
+use crate::{
+    Error,
+    color::{Range, Space},
+    ffi::*,
+    frame,
+    util::format,
+};
+use libc::{c_int, c_void};
 
 #[derive(Eq, PartialEq, Copy, Clone, Debug)]
 pub struct Definition {
@@ -16,6 +22,7 @@ pub struct Context {
 
     input: Definition,
     output: Definition,
+    flags: Flags,
 }
 
 impl Context {
@@ -35,20 +42,108 @@ impl Context {
         unsafe {
             let ptr = sws_getContext(src_w as c_int, src_h as c_int, src_format.into(), dst_w as c_int, dst_h as c_int, dst_format.into(), flags.bits(), ptr::null_mut(), ptr::null_mut(), ptr::null_mut());
 
-            if !ptr.is_null() { Ok(Context { ptr, input: Definition { format: src_format, width: src_w, height: src_h }, output: Definition { format: dst_format, width: dst_w, height: dst_h } }) } else { Err(Error::InvalidData) }
+            if !ptr.is_null() { Ok(Context { ptr, input: Definition { format: src_format, width: src_w, height: src_h }, output: Definition { format: dst_format, width: dst_w, height: dst_h }, flags }) } else { Err(Error::InvalidData) }
         }
     }
 
+    /// Creates a context converting frames like `input` into frames like `output`.
+    ///
+    /// Only the format and dimensions of the frames are used, so `output` can be a
+    /// template that has no buffers yet.
+    pub fn from_frames(input: &frame::Video, output: &frame::Video, flags: Flags) -> Result<Self, Error> {
+        Self::get(input.format(), input.width(), input.height(), output.format(), output.width(), output.height(), flags)
+    }
+
     pub fn cached(&mut self, src_format: format::Pixel, src_w: u32, src_h: u32, dst_format: format::Pixel, dst_w: u32, dst_h: u32, flags: Flags) {
         self.input = Definition { format: src_format, width: src_w, height: src_h };
 
         self.output = Definition { format: dst_format, width: dst_w, height: dst_h };
+        self.flags = flags;
 
         unsafe {
             self.ptr = sws_getCachedContext(self.as_mut_ptr(), src_w as c_int, src_h as c_int, src_format.into(), dst_w as c_int, dst_h as c_int, dst_format.into(), flags.bits(), ptr::null_mut(), ptr::null_mut(), ptr::null());
         }
     }
 
+    /// Sets the dithering used when converting to a lower bit depth.
+    ///
+    /// swscale only reads the dither mode while initializing, so this rebuilds the
+    /// underlying context with the same definitions and settings. Calling
+    /// [`cached()`](Self::cached) afterwards resets the dither mode to the default.
+    pub fn set_dither(&mut self, dither: Dither) -> Result<(), Error> {
+        self.reinit(|ptr| unsafe {
+            av_opt_set_int(ptr as *mut c_void, CString::new("sws_dither").unwrap().as_ptr(), c_int::from(dither) as i64, 0);
+        })
+    }
+
+    /// Tunes the scaling algorithm selected by the flags, `None` keeping the
+    /// default of a parameter (`SWS_PARAM_DEFAULT`):
+    ///
+    /// - [`BICUBIC`](Flags::BICUBIC): `B` and `C` of the Mitchell-Netravali
+    ///   filter, 0 and 0.6 by default; `(1/3, 1/3)` is softer, `(0, 0.75)` sharper;
+    /// - [`LANCZOS`](Flags::LANCZOS): the first parameter is the window size in
+    ///   lobes, 3 by default; larger is sharper, with more ringing;
+    /// - [`GAUSS`](Flags::GAUSS): the first parameter is the sharpness, 3 by
+    ///   default;
+    /// - the other algorithms ignore them.
+    ///
+    /// Rebuilds the context, like [`set_dither()`](Self::set_dither).
+    pub fn set_parameters(&mut self, first: Option<f64>, second: Option<f64>) -> Result<(), Error> {
+        self.reinit(|ptr| unsafe {
+            let default = f64::from(Flags::PARAM_DEFAULT.bits());
+            av_opt_set_double(ptr as *mut c_void, CString::new("param0").unwrap().as_ptr(), first.unwrap_or(default), 0);
+            av_opt_set_double(ptr as *mut c_void, CString::new("param1").unwrap().as_ptr(), second.unwrap_or(default), 0);
+        })
+    }
+
+    /// Enables gamma correct scaling: pixels are converted to linear light before
+    /// being filtered and back afterwards, which keeps the brightness of fine
+    /// high-contrast detail (text, foliage) when downscaling. Slower; best
+    /// combined with [`Quality::HighQuality`](super::Quality::HighQuality).
+    ///
+    /// Rebuilds the context, like [`set_dither()`](Self::set_dither).
+    pub fn set_gamma(&mut self, value: bool) -> Result<(), Error> {
+        self.reinit(|ptr| unsafe {
+            av_opt_set_int(ptr as *mut c_void, CString::new("gamma").unwrap().as_ptr(), value as i64, 0);
+        })
+    }
+
+    // swscale only reads its options while initializing: builds a new context
+    // with the options of the current one changed by `configure`, and replaces the
+    // current one if it initializes.
+    fn reinit<F: FnOnce(*mut SwsContext)>(&mut self, configure: F) -> Result<(), Error> {
+        unsafe {
+            let ptr = sws_alloc_context();
+
+            if ptr.is_null() {
+                panic!("out of memory");
+            }
+
+            let res = av_opt_copy(ptr as *mut c_void, self.as_ptr() as *const c_void);
+
+            if res < 0 {
+                sws_freeContext(ptr);
+                return Err(Error::from(res));
+            }
+
+            configure(ptr);
+
+            match sws_init_context(ptr, ptr::null_mut(), ptr::null_mut()) {
+                e if e < 0 => {
+                    sws_freeContext(ptr);
+                    Err(Error::from(e))
+                }
+
+                _ => {
+                    sws_freeContext(self.as_mut_ptr());
+                    self.ptr = ptr;
+
+                    Ok(())
+                }
+            }
+        }
+    }
+
     #[inline]
     pub fn input(&self) -> &Definition {
         &self.input
@@ -59,6 +154,15 @@ impl Context {
         &self.output
     }
 
+    /// Scales and converts `input` into `output`.
+    ///
+    /// When a YUV input frame is tagged with a color space or range, the
+    /// conversion uses the matching YUV coefficients and range instead of
+    /// swscale's BT.601 limited-range default. The output frame is tagged
+    /// accordingly: primaries and transfer characteristic are copied, RGB outputs
+    /// are marked full range RGB, YUV outputs of a YUV input keep its color space
+    /// and range, and YUV outputs of an RGB input get the matrix and range the
+    /// context converts to.
     pub fn run(&mut self, input: &frame::Video, output: &mut frame::Video) -> Result<(), Error> {
         if input.format() != self.input.format || input.width() != self.input.width || input.height() != self.input.height {
             return Err(Error::InputChanged);
@@ -75,6 +179,8 @@ impl Context {
         }
 
         unsafe {
+            self.update_color_details(input);
+
             sws_scale(
                 self.as_mut_ptr(),
                 (*input.as_ptr()).data.as_ptr() as *const *const _,
@@ -86,8 +192,221 @@ impl Context {
             );
         }
 
+        let (space, range) = unsafe { self.output_color_details(input) };
+
+        output.set_color_primaries(input.color_primaries());
+        output.set_color_transfer_characteristic(input.color_transfer_characteristic());
+        output.set_color_space(space);
+        output.set_color_range(range);
+
         Ok(())
     }
+
+    /// Like [`run()`](Self::run), but follows changes of the input instead of
+    /// failing with [`Error::InputChanged`].
+    ///
+    /// When the format or dimensions of `input` differ from the ones the context
+    /// was built for (e.g. an adaptive stream switching resolution), the context is
+    /// rebuilt for them, keeping the output definition, flags and every other
+    /// setting (dithering, parameters, gamma, colorspace details), and reused for
+    /// the following frames. An error is only returned if the new context cannot
+    /// be created, in which case the previous one is kept.
+    pub fn run_adaptive(&mut self, input: &frame::Video, output: &mut frame::Video) -> Result<(), Error> {
+        let definition = Definition { format: input.format(), width: input.width(), height: input.height() };
+
+        if definition != self.input {
+            unsafe {
+                let details = self.color_details();
+
+                self.reinit(|ptr| {
+                    av_opt_set_int(ptr as *mut c_void, CString::new("srcw").unwrap().as_ptr(), i64::from(definition.width), 0);
+                    av_opt_set_int(ptr as *mut c_void, CString::new("srch").unwrap().as_ptr(), i64::from(definition.height), 0);
+                    av_opt_set_pixel_fmt(ptr as *mut c_void, CString::new("src_format").unwrap().as_ptr(), definition.format.into(), 0);
+                })?;
+
+                if let Some(details) = details {
+                    self.set_color_details(&details);
+                }
+            }
+
+            self.input = definition;
+        }
+
+        self.run(input, output)
+    }
+
+    /// Scales the horizontal band of `input` starting at row `src_slice_y` and
+    /// `src_slice_h` rows high, writing the matching rows of `output`.
+    ///
+    /// Returns the number of output rows written. swscale keeps state between
+    /// slices, so a context must be fed the slices of a frame in order, either top to
+    /// bottom or bottom to top, and always starting with the first or last row. A
+    /// context can therefore not be shared between threads: to scale stripes in
+    /// parallel, give every thread its own `Context`, each one scaling its own
+    /// stripe from the first row.
+    ///
+    /// Slice boundaries must respect the vertical chroma subsampling of the input:
+    /// `src_slice_y` and `src_slice_h` have to be multiples of 2 for 4:2:0 formats,
+    /// except for a last slice ending at the bottom of the frame. Misaligned slices
+    /// fail with `EINVAL`.
+    ///
+    /// Unlike [`run()`](Self::run), the color details of the output are not updated.
+    pub fn scale_slice(&mut self, input: &frame::Video, src_slice_y: u32, src_slice_h: u32, output: &mut frame::Video) -> Result<u32, Error> {
+        if input.format() != self.input.format || input.width() != self.input.width || input.height() != self.input.height {
+            return Err(Error::InputChanged);
+        }
+
+        let alignment = self.input.format.descriptor().map_or(1, |descriptor| 1 << descriptor.log2_chroma_h());
+        let end = src_slice_y.checked_add(src_slice_h).ok_or(Error::Other { errno: libc::EINVAL })?;
+
+        if src_slice_h == 0 || end > self.input.height || src_slice_y % alignment != 0 || (src_slice_h % alignment != 0 && end != self.input.height) {
+            return Err(Error::Other { errno: libc::EINVAL });
+        }
+
+        unsafe {
+            if output.is_empty() {
+                output.alloc(self.output.format, self.output.width, self.output.height);
+            }
+        }
+
+        if output.format() != self.output.format || output.width() != self.output.width || output.height() != self.output.height {
+            return Err(Error::OutputChanged);
+        }
+
+        // swscale wants the planes of the slice, not of the whole frame.
+        let descriptor = self.input.format.descriptor();
+        let palette = descriptor.is_some_and(|descriptor| unsafe { (*descriptor.as_ptr()).flags & AV_PIX_FMT_FLAG_PAL as u64 != 0 });
+        let chroma_shift = descriptor.map_or(0, |descriptor| descriptor.log2_chroma_h());
+        let mut planes = [ptr::null::<u8>(); 4];
+
+        for (plane, data) in planes.iter_mut().enumerate() {
+            unsafe {
+                let start = (*input.as_ptr()).data[plane];
+
+                if start.is_null() || (palette && plane == 1) {
+                    *data = start;
+                    continue;
+                }
+
+                let shift = if plane == 1 || plane == 2 { chroma_shift } else { 0 };
+                *data = start.offset((src_slice_y >> shift) as isize * (*input.as_ptr()).linesize[plane] as isize);
+            }
+        }
+
+        unsafe {
+            match sws_scale(
+                self.as_mut_ptr(),
+                planes.as_ptr() as *const *const _,
+                (*input.as_ptr()).linesize.as_ptr() as *const _,
+                src_slice_y as c_int,
+                src_slice_h as c_int,
+                (*output.as_mut_ptr()).data.as_ptr(),
+                (*output.as_mut_ptr()).linesize.as_ptr() as *mut _,
+            ) {
+                e if e < 0 => Err(Error::from(e)),
+                rows => Ok(rows as u32),
+            }
+        }
+    }
+
+    // Only a YUV input has coefficients to pick; RGB inputs keep the matrix the
+    // context converts to.
+    unsafe fn update_color_details(&mut self, input: &frame::Video) {
+        if is_rgb(self.input.format) || (input.color_space() == Space::Unspecified && input.color_range() == Range::Unspecified) {
+            return;
+        }
+
+        unsafe {
+            let Some(mut details) = self.color_details() else {
+                return;
+            };
+
+            let coefficients = slice::from_raw_parts(sws_getCoefficients(AVColorSpace::from(input.color_space()) as c_int), 4);
+            let full = (input.color_range() == Range::JPEG) as c_int;
+
+            if details.src_range == full && details.inv_table == coefficients {
+                return;
+            }
+
+            details.inv_table.copy_from_slice(coefficients);
+            details.src_range = full;
+
+            // YUV to YUV conversions keep the input matrix and range, RGB outputs are full range.
+            if is_rgb(self.output.format) {
+                details.dst_range = 1;
+            } else {
+                details.table.copy_from_slice(coefficients);
+                details.dst_range = full;
+            }
+
+            self.set_color_details(&details);
+        }
+    }
+
+    // The color space and range of the frames written, as set up by
+    // `update_color_details()`.
+    unsafe fn output_color_details(&mut self, input: &frame::Video) -> (Space, Range) {
+        if is_rgb(self.output.format) {
+            return (Space::RGB, Range::JPEG);
+        }
+
+        if !is_rgb(self.input.format) {
+            return (input.color_space(), input.color_range());
+        }
+
+        unsafe {
+            let Some(details) = self.color_details() else {
+                return (Space::Unspecified, Range::Unspecified);
+            };
+
+            // swscale has a single table for BT.470BG and SMPTE 170M, the first match wins.
+            let space = [Space::BT709, Space::SMPTE170M, Space::FCC, Space::SMPTE240M, Space::BT2020NCL]
+                .into_iter()
+                .find(|&space| slice::from_raw_parts(sws_getCoefficients(AVColorSpace::from(space) as c_int), 4) == details.table)
+                .unwrap_or(Space::Unspecified);
+
+            (space, if details.dst_range != 0 { Range::JPEG } else { Range::MPEG })
+        }
+    }
+
+    unsafe fn color_details(&mut self) -> Option<ColorDetails> {
+        unsafe {
+            let mut inv_table = ptr::null_mut();
+            let mut table = ptr::null_mut();
+            let mut details = ColorDetails { inv_table: [0; 4], src_range: 0, table: [0; 4], dst_range: 0, brightness: 0, contrast: 0, saturation: 0 };
+
+            if sws_getColorspaceDetails(self.as_mut_ptr(), &mut inv_table, &mut details.src_range, &mut table, &mut details.dst_range, &mut details.brightness, &mut details.contrast, &mut details.saturation) < 0 {
+                return None;
+            }
+
+            // The tables point into the context, copy them out.
+            details.inv_table.copy_from_slice(slice::from_raw_parts(inv_table, 4));
+            details.table.copy_from_slice(slice::from_raw_parts(table, 4));
+
+            Some(details)
+        }
+    }
+
+    unsafe fn set_color_details(&mut self, details: &ColorDetails) {
+        unsafe {
+            sws_setColorspaceDetails(self.as_mut_ptr(), details.inv_table.as_ptr(), details.src_range, details.table.as_ptr(), details.dst_range, details.brightness, details.contrast, details.saturation);
+        }
+    }
+}
+
+// Colorspace details of a context (`sws_getColorspaceDetails`).
+struct ColorDetails {
+    inv_table: [c_int; 4],
+    src_range: c_int,
+    table: [c_int; 4],
+    dst_range: c_int,
+    brightness: c_int,
+    contrast: c_int,
+    saturation: c_int,
+}
+
+fn is_rgb(format: format::Pixel) -> bool {
+    format.descriptor().is_some_and(|descriptor| unsafe { (*descriptor.as_ptr()).flags & AV_PIX_FMT_FLAG_RGB as u64 != 0 })
 }
 
 impl Drop for Context {
@@ -97,3 +416,139 @@ impl Drop for Context {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Red channel of row 4 of a horizontal gray ramp converted to `output`.
+    fn ramp(output: format::Pixel, dither: Option<Dither>) -> Vec<f64> {
+        let mut input = frame::Video::new(format::Pixel::GRAY8, 256, 8);
+        let stride = input.stride(0);
+
+        for row in 0..8 {
+            for (column, value) in input.data_mut(0)[row * stride..][..256].iter_mut().enumerate() {
+                *value = column as u8;
+            }
+        }
+
+        let mut context = Context::get(format::Pixel::GRAY8, 256, 8, output, 256, 8, Flags::POINT | Flags::FULL_CHR_H_INT | Flags::ACCURATE_RND).unwrap();
+
+        if let Some(dither) = dither {
+            context.set_dither(dither).unwrap();
+        }
+
+        let mut converted = frame::Video::empty();
+        context.run(&input, &mut converted).unwrap();
+
+        let row = &converted.data(0)[4 * converted.stride(0)..];
+
+        match output {
+            // 3 bits of red, in units of the 8-bit scale.
+            format::Pixel::RGB8 => row[..256].iter().map(|&pixel| f64::from(pixel >> 5) * 255.0 / 7.0).collect(),
+            _ => row[..256 * 3].chunks(3).map(|pixel| f64::from(pixel[0])).collect(),
+        }
+    }
+
+    // Mean distance between the averages over 16 pixels of `values` and of the
+    // 8-bit `reference`: how far the local brightness strays from the ramp.
+    fn banding(values: &[f64], reference: &[f64]) -> f64 {
+        let average = |window: &[f64]| window.iter().sum::<f64>() / window.len() as f64;
+        let errors = values.windows(16).zip(reference.windows(16)).map(|(values, reference)| (average(values) - average(reference)).abs()).collect::<Vec<_>>();
+
+        errors.iter().sum::<f64>() / errors.len() as f64
+    }
+
+    #[test]
+    fn test_scale_slice() {
+        let mut input = frame::Video::new(format::Pixel::YUV420P, 64, 48);
+
+        for plane in 0..3 {
+            let stride = input.stride(plane);
+            let (width, height) = (input.plane_width(plane) as usize, input.plane_height(plane) as usize);
+            let data = input.data_mut(plane);
+
+            for row in 0..height {
+                for column in 0..width {
+                    data[row * stride + column] = (row * 7 + column * 3 + plane * 50) as u8;
+                }
+            }
+        }
+
+        let flags = Flags::BILINEAR;
+        let mut whole = frame::Video::empty();
+        Context::get(format::Pixel::YUV420P, 64, 48, format::Pixel::RGB24, 32, 24, flags).unwrap().run(&input, &mut whole).unwrap();
+
+        let mut context = Context::get(format::Pixel::YUV420P, 64, 48, format::Pixel::RGB24, 32, 24, flags).unwrap();
+        let mut banded = frame::Video::empty();
+
+        for band in 0..3 {
+            context.scale_slice(&input, band * 16, 16, &mut banded).unwrap();
+        }
+
+        for row in 0..24 {
+            assert_eq!(whole.data(0)[row * whole.stride(0)..][..32 * 3], banded.data(0)[row * banded.stride(0)..][..32 * 3], "row {row}");
+        }
+    }
+
+    #[test]
+    fn test_rgb_to_yuv_color_details() {
+        let mut input = frame::Video::new(format::Pixel::RGB24, 16, 16);
+        input.data_mut(0).fill(255);
+        input.set_color_space(Space::RGB);
+        input.set_color_range(Range::JPEG);
+
+        let mut context = Context::get(format::Pixel::RGB24, 16, 16, format::Pixel::YUV420P, 16, 16, Flags::BILINEAR).unwrap();
+        let mut output = frame::Video::empty();
+        context.run(&input, &mut output).unwrap();
+
+        // swscale's default: BT.601, limited range, where white is 235.
+        assert_eq!(output.color_space(), Space::SMPTE170M);
+        assert_eq!(output.color_range(), Range::MPEG);
+        assert_eq!(output.data(0)[0], 235);
+    }
+
+    #[test]
+    fn test_run_adaptive_keeps_settings() {
+        let mut context = Context::get(format::Pixel::YUV420P, 64, 48, format::Pixel::RGB24, 32, 24, Flags::BICUBIC).unwrap();
+        context.set_dither(Dither::ErrorDiffusion).unwrap();
+        context.set_parameters(Some(0.0), Some(0.75)).unwrap();
+
+        unsafe {
+            let mut details = context.color_details().unwrap();
+            details.inv_table.copy_from_slice(slice::from_raw_parts(sws_getCoefficients(SWS_CS_ITU709), 4));
+            details.saturation = 1 << 15;
+            context.set_color_details(&details);
+        }
+
+        let mut output = frame::Video::empty();
+        context.run_adaptive(&frame::Video::new(format::Pixel::YUV420P, 128, 96), &mut output).unwrap();
+
+        assert_eq!(context.input(), &Definition { format: format::Pixel::YUV420P, width: 128, height: 96 });
+
+        unsafe {
+            let option = |name: &str| {
+                let mut value = 0.0;
+                av_opt_get_double(context.as_ptr() as *mut c_void, CString::new(name).unwrap().as_ptr(), 0, &mut value);
+                value
+            };
+
+            assert_eq!(option("sws_dither"), f64::from(c_int::from(Dither::ErrorDiffusion)));
+            assert_eq!(option("param0"), 0.0);
+            assert_eq!(option("param1"), 0.75);
+
+            let details = context.color_details().unwrap();
+            assert_eq!(details.inv_table, slice::from_raw_parts(sws_getCoefficients(SWS_CS_ITU709), 4));
+            assert_eq!(details.saturation, 1 << 15);
+        }
+    }
+
+    #[test]
+    fn test_error_diffusion_banding() {
+        let reference = ramp(format::Pixel::RGB24, None);
+        let plain = banding(&ramp(format::Pixel::RGB8, Some(Dither::None)), &reference);
+        let diffused = banding(&ramp(format::Pixel::RGB8, Some(Dither::ErrorDiffusion)), &reference);
+
+        assert!(diffused * 2.0 < plain, "error diffusion {diffused} vs none {plain}");
+    }
+}
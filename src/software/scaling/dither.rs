@@ -0,0 +1,34 @@
+use libc::c_int;
+
+/// Dithering applied by swscale when reducing bit depth (e.g. 10-bit to 8-bit).
+///
+/// Without dithering, smooth gradients quantized to fewer bits show visible banding.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Dither {
+    None,
+    /// Let swscale choose (currently ordered dithering for most conversions).
+    Auto,
+    /// Ordered dithering with a Bayer matrix.
+    Bayer,
+    /// Error diffusion; best quality, slowest.
+    ErrorDiffusion,
+    /// Arithmetic dither, additive.
+    ADither,
+    /// Arithmetic dither, based on xor.
+    XDither,
+}
+
+impl From<Dither> for c_int {
+    fn from(value: Dither) -> c_int {
+        // Mirrors the (stable) values of `SwsDither`, which is not part of the public
+        // swscale headers on all supported versions.
+        match value {
+            Dither::None => 0,
+            Dither::Auto => 1,
+            Dither::Bayer => 2,
+            Dither::ErrorDiffusion => 3,
+            Dither::ADither => 4,
+            Dither::XDither => 5,
+        }
+    }
+}
@@ -6,25 +6,36 @@ use libc::c_int;
 bitflags! {
     #[derive(Copy, Clone, Debug, PartialEq, Eq)]
     pub struct Flags: c_int {
+        /// Fastest algorithm, bilinear with reduced precision.
         const FAST_BILINEAR        = SWS_FAST_BILINEAR as _;
         const BILINEAR             = SWS_BILINEAR as _;
+        /// Bicubic, tunable with [`Context::set_parameters()`](super::Context::set_parameters).
         const BICUBIC              = SWS_BICUBIC as _;
         const X                    = SWS_X as _;
+        /// Nearest neighbor.
         const POINT                = SWS_POINT as _;
+        /// Averaging area, good for downscaling by large factors.
         const AREA                 = SWS_AREA as _;
+        /// Bicubic for luma, bilinear for chroma.
         const BICUBLIN             = SWS_BICUBLIN as _;
+        /// Gaussian, with tunable sharpness.
         const GAUSS                = SWS_GAUSS as _;
         const SINC                 = SWS_SINC as _;
+        /// Lanczos, with a tunable window; sharpest of the common algorithms.
         const LANCZOS              = SWS_LANCZOS as _;
         const SPLINE               = SWS_SPLINE as _;
         const SRC_V_CHR_DROP_MASK  = SWS_SRC_V_CHR_DROP_MASK as _;
         const SRC_V_CHR_DROP_SHIFT = SWS_SRC_V_CHR_DROP_SHIFT as _;
         const PARAM_DEFAULT        = SWS_PARAM_DEFAULT as _;
         const PRINT_INFO           = SWS_PRINT_INFO as _;
+        /// Full chroma resolution when converting to RGB, instead of interpolating it.
         const FULL_CHR_H_INT       = SWS_FULL_CHR_H_INT as _;
+        /// Full chroma resolution read from RGB input, instead of averaging neighboring pixels.
         const FULL_CHR_H_INP       = SWS_FULL_CHR_H_INP as _;
         const DIRECT_BGR           = SWS_DIRECT_BGR as _;
+        /// Exact rounding in the vertical scaler and YUV/RGB conversion, at some speed cost.
         const ACCURATE_RND         = SWS_ACCURATE_RND as _;
+        /// Output identical on every platform, for tests.
         const BITEXACT             = SWS_BITEXACT as _;
         const ERROR_DIFFUSION      = SWS_ERROR_DIFFUSION as _;
     }
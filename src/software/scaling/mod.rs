@@ -1,6 +1,12 @@
 pub mod flag;
 pub use self::flag::Flags;
 
+pub mod dither;
+pub use self::dither::Dither;
+
+pub mod quality;
+pub use self::quality::Quality;
+
 pub mod color_space;
 pub use self::color_space::ColorSpace;
 
@@ -0,0 +1,25 @@
+use super::Flags;
+
+/// Sensible combinations of [`Flags`] trading speed for quality.
+#[derive(Eq, PartialEq, Copy, Clone, Debug)]
+pub enum Quality {
+    /// Fast bilinear, for previews and real-time paths; visibly soft and
+    /// aliased when downscaling a lot.
+    Fast,
+    /// Bicubic, the default of the `ffmpeg` tool.
+    Balanced,
+    /// Lanczos with accurate rounding and full chroma resolution during
+    /// conversion, for archival or final output. Several times slower than
+    /// [`Fast`](Self::Fast).
+    HighQuality,
+}
+
+impl From<Quality> for Flags {
+    fn from(value: Quality) -> Flags {
+        match value {
+            Quality::Fast => Flags::FAST_BILINEAR,
+            Quality::Balanced => Flags::BICUBIC,
+            Quality::HighQuality => Flags::LANCZOS | Flags::ACCURATE_RND | Flags::FULL_CHR_H_INT | Flags::FULL_CHR_H_INP,
+        }
+    }
+}
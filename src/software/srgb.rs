@@ -0,0 +1,147 @@
+use super::scaling;
+use crate::{
+    Error, Rational,
+    color::{Primaries, Range, Space, TransferCharacteristic},
+    filter,
+    format::Pixel,
+    frame,
+};
+
+/// Converts a decoded frame to RGB24 in sRGB, e.g. for thumbnails, taking its
+/// color properties into account.
+///
+/// YUV to RGB conversion alone only undoes the matrix, which is enough for
+/// BT.709 material since sRGB shares its primaries: those frames (and untagged
+/// ones) go straight through swscale with the matrix and range they are tagged
+/// with. Other primaries (BT.2020, BT.601, ...) are first mapped to BT.709 ones
+/// by the `colorspace` filter; skipping that step leaves BT.2020 content
+/// desaturated. Colors outside the sRGB gamut are clipped.
+///
+/// As players do, BT.709-style transfer functions are displayed as sRGB
+/// without conversion, so both sources of a scene look the same. HDR frames
+/// (PQ, HLG) need tone mapping and are rejected by the filter with an error. The
+/// output is tagged as full range sRGB and keeps the timestamp of the input.
+///
+/// A filter graph is built for every call; convert many frames with a
+/// dedicated graph instead.
+pub fn to_srgb(frame: &frame::Video) -> Result<frame::Video, Error> {
+    let converted;
+    let source = match frame.color_primaries() {
+        Primaries::BT709 | Primaries::Unspecified | Primaries::Reserved | Primaries::Reserved0 => frame,
+
+        _ => {
+            converted = to_bt709(frame)?;
+            &converted
+        }
+    };
+
+    let mut context = scaling::Context::get(source.format(), source.width(), source.height(), Pixel::RGB24, source.width(), source.height(), scaling::Flags::BICUBIC | scaling::Flags::ACCURATE_RND | scaling::Flags::FULL_CHR_H_INT)?;
+    let mut output = frame::Video::empty();
+    context.run(source, &mut output)?;
+
+    output.set_color_primaries(Primaries::BT709);
+    output.set_color_transfer_characteristic(TransferCharacteristic::IEC61966_2_1);
+    output.set_pts(frame.pts());
+
+    Ok(output)
+}
+
+// Maps the primaries of `frame` to BT.709 through the `colorspace` filter,
+// keeping a YUV format.
+fn to_bt709(frame: &frame::Video) -> Result<frame::Video, Error> {
+    let mut input = frame.clone();
+
+    // The filter refuses unspecified properties: assume the ones that usually go
+    // with the primaries.
+    if input.color_space() == Space::Unspecified {
+        input.set_color_space(match input.color_primaries() {
+            Primaries::BT2020 => Space::BT2020NCL,
+            Primaries::BT470BG => Space::BT470BG,
+            _ => Space::SMPTE170M,
+        });
+    }
+
+    if input.color_transfer_characteristic() == TransferCharacteristic::Unspecified {
+        input.set_color_transfer_characteristic(TransferCharacteristic::BT709);
+    }
+
+    if input.color_range() == Range::Unspecified {
+        input.set_color_range(Range::MPEG);
+    }
+
+    let mut graph = filter::Graph::new();
+    let parameters = filter::buffer::Video::new(input.format(), input.width(), input.height(), Rational(1, 1)).aspect_ratio(input.aspect_ratio());
+
+    let mut source = graph.add_video_source("in", &parameters)?;
+    let mut colorspace = graph.add(&filter::find("colorspace").ok_or(Error::FilterNotFound)?, "colorspace", "all=bt709")?;
+    let mut sink = graph.add(&filter::find("buffersink").ok_or(Error::FilterNotFound)?, "out", "")?;
+
+    source.link(0, &mut colorspace, 0);
+    colorspace.link(0, &mut sink, 0);
+
+    graph.validate()?;
+
+    source.source().add(&input)?;
+    source.source().flush()?;
+
+    let mut output = frame::Video::empty();
+    sink.sink().frame(&mut output)?;
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 16×16 YUV444P10 frame of the color with linear RGB `rgb` in the given
+    // primaries, BT.709 transfer and limited range.
+    fn frame(primaries: Primaries, space: Space, (kr, kb): (f64, f64), rgb: [f64; 3]) -> frame::Video {
+        let oetf = |l: f64| if l < 0.018 { 4.5 * l } else { 1.099 * l.powf(0.45) - 0.099 };
+        let [r, g, b] = rgb.map(oetf);
+        let y = kr * r + (1.0 - kr - kb) * g + kb * b;
+        let values = [64.0 + 876.0 * y, 512.0 + 896.0 * (b - y) / (2.0 * (1.0 - kb)), 512.0 + 896.0 * (r - y) / (2.0 * (1.0 - kr))];
+
+        let mut frame = frame::Video::new(Pixel::YUV444P10LE, 16, 16);
+
+        for (plane, value) in values.iter().enumerate() {
+            let stride = frame.stride(plane);
+            let data = frame.data_mut(plane);
+
+            for row in 0..16 {
+                for column in 0..16 {
+                    data[row * stride + column * 2..][..2].copy_from_slice(&(value.round() as u16).to_le_bytes());
+                }
+            }
+        }
+
+        frame.set_color_primaries(primaries);
+        frame.set_color_space(space);
+        frame.set_color_transfer_characteristic(TransferCharacteristic::BT709);
+        frame.set_color_range(Range::MPEG);
+
+        frame
+    }
+
+    fn pixel(frame: &frame::Video) -> [i32; 3] {
+        let data = frame.data(0);
+        let offset = 8 * frame.stride(0) + 8 * 3;
+
+        [data[offset] as i32, data[offset + 1] as i32, data[offset + 2] as i32]
+    }
+
+    #[test]
+    fn test_to_srgb() {
+        // The same orange in BT.709 primaries and, converted with the BT.709 to
+        // BT.2020 matrix of ITU-R BT.2087, in BT.2020 ones.
+        let bt709 = [0.8, 0.3, 0.05];
+        let bt2020 = [0.6274 * bt709[0] + 0.3293 * bt709[1] + 0.0433 * bt709[2], 0.0691 * bt709[0] + 0.9195 * bt709[1] + 0.0114 * bt709[2], 0.0164 * bt709[0] + 0.0880 * bt709[1] + 0.8956 * bt709[2]];
+
+        let reference = pixel(&to_srgb(&frame(Primaries::BT709, Space::BT709, (0.2126, 0.0722), bt709)).unwrap());
+        let converted = pixel(&to_srgb(&frame(Primaries::BT2020, Space::BT2020NCL, (0.2627, 0.0593), bt2020)).unwrap());
+
+        for (reference, converted) in reference.iter().zip(converted) {
+            assert!((reference - converted).abs() <= 3, "{reference:?} != {converted:?}");
+        }
+    }
+}
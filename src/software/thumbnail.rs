@@ -0,0 +1,195 @@
+//! Contact sheets: a grid of thumbnails taken at evenly spaced times of a video.
+
+use std::path::Path;
+
+use super::scaling;
+use crate::{Discard, Error, Rescale, codec, ffi::*, format, frame, media};
+
+/// Builds a contact sheet of a video: `columns × rows` thumbnails composited into
+/// one frame, read left to right and top to bottom.
+///
+/// The video is split into as many equal segments as there are tiles, and each
+/// tile shows the keyframe at or before the middle of its segment. Only keyframes
+/// are decoded (the decoder skips the rest), so building a sheet costs one seek
+/// and one decoded frame per tile. Thumbnails keep the display aspect ratio of
+/// the video and are centered in their tile.
+///
+/// Videos with fewer keyframes than tiles (short clips, long GOPs) yield each
+/// keyframe once: a seek landing on the keyframe of the previous tile is skipped,
+/// and the sheet ends with black tiles. When the duration is unknown the first
+/// keyframes of the video are used.
+///
+/// ```ignore
+/// let sheet = ThumbnailSheet::new(4, 3, 320, 180).generate(&"movie.mkv")?;
+/// ```
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub struct ThumbnailSheet {
+    columns: u32,
+    rows: u32,
+    width: u32,
+    height: u32,
+    format: format::Pixel,
+    flags: scaling::Flags,
+}
+
+impl ThumbnailSheet {
+    /// Creates a sheet of `columns × rows` tiles of `width × height` pixels each,
+    /// in RGB24.
+    pub fn new(columns: u32, rows: u32, width: u32, height: u32) -> Self {
+        ThumbnailSheet { columns, rows, width, height, format: format::Pixel::RGB24, flags: scaling::Flags::BILINEAR }
+    }
+
+    /// Pixel format of the sheet. Only packed formats without chroma subsampling
+    /// (RGB variants and GRAY8) are supported.
+    pub fn format(mut self, value: format::Pixel) -> Self {
+        self.format = value;
+        self
+    }
+
+    /// Scaling algorithm for the thumbnails.
+    pub fn flags(mut self, value: scaling::Flags) -> Self {
+        self.flags = value;
+        self
+    }
+
+    /// Builds the sheet from the best video stream of the file at `path`.
+    ///
+    /// Fails with `EINVAL` for an empty grid or an unsupported pixel format, and
+    /// with [`Error::StreamNotFound`] if the file has no video stream.
+    pub fn generate<P: AsRef<Path> + ?Sized>(&self, path: &P) -> Result<frame::Video, Error> {
+        let bytes_per_pixel = packed_bytes_per_pixel(self.format).ok_or(Error::Other { errno: libc::EINVAL })?;
+
+        if self.columns == 0 || self.rows == 0 || self.width == 0 || self.height == 0 {
+            return Err(Error::Other { errno: libc::EINVAL });
+        }
+
+        let mut input = format::input(path)?;
+        let stream = input.streams().best(media::Type::Video).ok_or(Error::StreamNotFound)?;
+        let index = stream.index();
+        let start = if stream.start_time() == AV_NOPTS_VALUE { 0 } else { stream.start_time() };
+        let duration = if stream.duration() > 0 { stream.duration() } else { input.duration().max(0).rescale(crate::rescale::TIME_BASE, stream.time_base()) };
+
+        let mut decoder = codec::Context::from_parameters(stream.parameters())?.decoder();
+        decoder.skip_frame(Discard::NonKey);
+        let mut decoder = decoder.video()?;
+
+        let mut sheet = frame::Video::new(self.format, self.columns * self.width, self.rows * self.height);
+        sheet.data_mut(0).fill(0);
+
+        let tiles = u64::from(self.columns) * u64::from(self.rows);
+        let mut scaler: Option<scaling::Context> = None;
+        let mut frame = frame::Video::empty();
+        let mut thumbnail = frame::Video::empty();
+        let mut previous = None;
+        let mut tile = 0;
+
+        for segment in 0..tiles {
+            if duration > 0 {
+                let target = start + (i128::from(duration) * i128::from(2 * segment + 1) / i128::from(2 * tiles)) as i64;
+
+                unsafe {
+                    if av_seek_frame(input.as_mut_ptr(), index as _, target, AVSEEK_FLAG_BACKWARD as _) < 0 {
+                        break;
+                    }
+                }
+
+                decoder.flush();
+            }
+
+            if !input.decode_next(&mut decoder, index, &mut frame)? {
+                break;
+            }
+
+            let timestamp = frame.timestamp().or(frame.pts());
+
+            if timestamp.is_some() && timestamp == previous {
+                continue;
+            }
+
+            previous = timestamp;
+
+            let (width, height) = self.fit(&frame);
+
+            if scaler.as_ref().is_none_or(|scaler| scaler.output().width != width || scaler.output().height != height) {
+                scaler = Some(scaling::Context::get(frame.format(), frame.width(), frame.height(), self.format, width, height, self.flags)?);
+                thumbnail = frame::Video::empty();
+            }
+
+            scaler.as_mut().unwrap().run_adaptive(&frame, &mut thumbnail)?;
+
+            let x = (tile % self.columns) * self.width + (self.width - width) / 2;
+            let y = (tile / self.columns) * self.height + (self.height - height) / 2;
+
+            blit(&mut sheet, &thumbnail, x, y, bytes_per_pixel);
+            tile += 1;
+        }
+
+        Ok(sheet)
+    }
+
+    /// Like [`generate()`](Self::generate), but returns the sheet as an RGB image
+    /// regardless of the configured pixel format.
+    #[cfg(feature = "image")]
+    pub fn generate_image<P: AsRef<Path> + ?Sized>(&self, path: &P) -> Result<crate::image::RgbImage, Error> {
+        let sheet = self.format(format::Pixel::RGB24).generate(path)?;
+        let (width, height) = (sheet.width(), sheet.height());
+        let stride = sheet.stride(0);
+        let data = sheet.data(0);
+        let mut pixels = Vec::with_capacity(width as usize * height as usize * 3);
+
+        for y in 0..height as usize {
+            pixels.extend_from_slice(&data[y * stride..y * stride + width as usize * 3]);
+        }
+
+        Ok(crate::image::RgbImage::from_raw(width, height, pixels).unwrap())
+    }
+
+    // Largest size with the display aspect ratio of `frame` that fits in a tile.
+    fn fit(&self, frame: &frame::Video) -> (u32, u32) {
+        let aspect = frame.aspect_ratio();
+        let aspect = if aspect.numerator() > 0 && aspect.denominator() > 0 { f64::from(aspect) } else { 1.0 };
+        let display_width = f64::from(frame.width()) * aspect;
+        let display_height = f64::from(frame.height());
+
+        let scale = (f64::from(self.width) / display_width).min(f64::from(self.height) / display_height);
+        let width = ((display_width * scale).round() as u32).clamp(1, self.width);
+        let height = ((display_height * scale).round() as u32).clamp(1, self.height);
+
+        (width, height)
+    }
+}
+
+// Bytes per pixel of packed formats whose pixels are whole bytes, for which tile
+// offsets are plain byte offsets and zeroed data is black.
+fn packed_bytes_per_pixel(format: format::Pixel) -> Option<usize> {
+    let descriptor = format.descriptor()?;
+
+    unsafe {
+        let flags = (*descriptor.as_ptr()).flags;
+        let unsupported = (AV_PIX_FMT_FLAG_PLANAR | AV_PIX_FMT_FLAG_PAL | AV_PIX_FMT_FLAG_BITSTREAM | AV_PIX_FMT_FLAG_HWACCEL) as u64;
+        let bits = av_get_bits_per_pixel(descriptor.as_ptr());
+
+        if flags & unsupported != 0 || descriptor.log2_chroma_w() != 0 || descriptor.log2_chroma_h() != 0 || bits <= 0 || bits % 8 != 0 {
+            return None;
+        }
+
+        Some(bits as usize / 8)
+    }
+}
+
+// Copies `thumbnail` into `sheet` with its top-left corner at (`x`, `y`), line by
+// line since the two frames have unrelated strides.
+fn blit(sheet: &mut frame::Video, thumbnail: &frame::Video, x: u32, y: u32, bytes_per_pixel: usize) {
+    let length = thumbnail.width() as usize * bytes_per_pixel;
+    let offset = x as usize * bytes_per_pixel;
+    let source_stride = thumbnail.stride(0);
+    let destination_stride = sheet.stride(0);
+    let source = thumbnail.data(0);
+    let destination = sheet.data_mut(0);
+
+    for row in 0..thumbnail.height() as usize {
+        let target = (y as usize + row) * destination_stride + offset;
+
+        destination[target..target + length].copy_from_slice(&source[row * source_stride..row * source_stride + length]);
+    }
+}
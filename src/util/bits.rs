@@ -0,0 +1,272 @@
+//! Bit-level reading and writing, as needed to parse or rewrite codec headers
+//! (e.g. H.264/HEVC parameter sets found in extradata).
+//!
+//! Bits are read and written MSB first, as in all MPEG bitstreams. Exp-Golomb codes
+//! (`ue(v)` / `se(v)`) are supported. Emulation prevention bytes are not handled:
+//! strip them from NAL units before reading.
+
+use crate::Error;
+
+/// Reads bits from a byte slice.
+///
+/// Reads past the end fail with `Error::Eof` and leave the position unchanged.
+#[derive(Clone, Debug)]
+pub struct BitReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        BitReader { data, position: 0 }
+    }
+
+    /// Position in bits from the start of the data.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Number of bits left.
+    pub fn remaining(&self) -> usize {
+        self.data.len() * 8 - self.position
+    }
+
+    pub fn is_byte_aligned(&self) -> bool {
+        self.position % 8 == 0
+    }
+
+    pub fn read_bit(&mut self) -> Result<bool, Error> {
+        Ok(self.read_bits(1)? == 1)
+    }
+
+    /// Reads `count` bits (at most 32) as an unsigned integer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `count` is greater than 32.
+    pub fn read_bits(&mut self, count: u32) -> Result<u32, Error> {
+        assert!(count <= 32, "cannot read more than 32 bits at once");
+
+        if count as usize > self.remaining() {
+            return Err(Error::Eof);
+        }
+
+        let mut value = 0u64;
+
+        for _ in 0..count {
+            let byte = self.data[self.position / 8];
+            let bit = (byte >> (7 - self.position % 8)) & 1;
+
+            value = (value << 1) | u64::from(bit);
+            self.position += 1;
+        }
+
+        Ok(value as u32)
+    }
+
+    pub fn skip_bits(&mut self, count: usize) -> Result<(), Error> {
+        if count > self.remaining() {
+            return Err(Error::Eof);
+        }
+
+        self.position += count;
+
+        Ok(())
+    }
+
+    /// Skips to the next byte boundary.
+    pub fn byte_align(&mut self) {
+        self.position = self.position.div_ceil(8) * 8;
+    }
+
+    /// Reads an unsigned Exp-Golomb code (`ue(v)`).
+    pub fn read_ue(&mut self) -> Result<u32, Error> {
+        let start = self.position;
+        let value = self.read_exp_golomb()?;
+
+        u32::try_from(value).map_err(|_| {
+            self.position = start;
+            Error::InvalidData
+        })
+    }
+
+    /// Reads a signed Exp-Golomb code (`se(v)`).
+    pub fn read_se(&mut self) -> Result<i32, Error> {
+        let start = self.position;
+        let value = self.read_exp_golomb()?;
+        let value = if value % 2 == 1 { value.div_ceil(2) as i64 } else { -((value / 2) as i64) };
+
+        i32::try_from(value).map_err(|_| {
+            self.position = start;
+            Error::InvalidData
+        })
+    }
+
+    // Up to 32 leading zeros, enough for every `u32` and for the `2^32` that
+    // `i32::MIN` maps to.
+    fn read_exp_golomb(&mut self) -> Result<u64, Error> {
+        let start = self.position;
+        let mut zeros = 0;
+
+        while !self.read_bit().inspect_err(|_| self.position = start)? {
+            zeros += 1;
+
+            if zeros > 32 {
+                self.position = start;
+                return Err(Error::InvalidData);
+            }
+        }
+
+        match self.read_bits(zeros) {
+            Ok(suffix) => Ok((1u64 << zeros) - 1 + u64::from(suffix)),
+            Err(e) => {
+                self.position = start;
+                Err(e)
+            }
+        }
+    }
+}
+
+/// Writes bits into a growable buffer.
+#[derive(Clone, Debug, Default)]
+pub struct BitWriter {
+    data: Vec<u8>,
+    position: usize,
+}
+
+impl BitWriter {
+    pub fn new() -> Self {
+        BitWriter { data: Vec::new(), position: 0 }
+    }
+
+    /// Number of bits written.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn is_byte_aligned(&self) -> bool {
+        self.position % 8 == 0
+    }
+
+    pub fn write_bit(&mut self, value: bool) {
+        if self.position % 8 == 0 {
+            self.data.push(0);
+        }
+
+        if value {
+            *self.data.last_mut().unwrap() |= 1 << (7 - self.position % 8);
+        }
+
+        self.position += 1;
+    }
+
+    /// Writes the `count` low bits of `value` (at most 32).
+    ///
+    /// # Panics
+    ///
+    /// Panics if `count` is greater than 32.
+    pub fn write_bits(&mut self, value: u32, count: u32) {
+        assert!(count <= 32, "cannot write more than 32 bits at once");
+
+        for i in (0..count).rev() {
+            self.write_bit((value >> i) & 1 == 1);
+        }
+    }
+
+    /// Pads with zero bits up to the next byte boundary.
+    pub fn byte_align(&mut self) {
+        while !self.is_byte_aligned() {
+            self.write_bit(false);
+        }
+    }
+
+    /// Writes an unsigned Exp-Golomb code (`ue(v)`).
+    pub fn write_ue(&mut self, value: u32) {
+        self.write_exp_golomb(u64::from(value));
+    }
+
+    /// Writes a signed Exp-Golomb code (`se(v)`).
+    pub fn write_se(&mut self, value: i32) {
+        let value = i64::from(value);
+        let mapped = if value > 0 { value * 2 - 1 } else { -value * 2 };
+
+        self.write_exp_golomb(mapped as u64);
+    }
+
+    fn write_exp_golomb(&mut self, value: u64) {
+        let value = value + 1;
+        let bits = 64 - value.leading_zeros();
+
+        for _ in 1..bits {
+            self.write_bit(false);
+        }
+
+        for i in (0..bits).rev() {
+            self.write_bit((value >> i) & 1 == 1);
+        }
+    }
+
+    /// The written bytes; a partial last byte is zero padded.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.data
+    }
+
+    pub fn into_inner(self) -> Vec<u8> {
+        self.data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exp_golomb_round_trip() {
+        let unsigned = [0, 1, 2, 255, u32::MAX - 1, u32::MAX];
+        let signed = [0, 1, -1, 2, -2, i32::MAX, i32::MIN];
+
+        let mut writer = BitWriter::new();
+        unsigned.iter().for_each(|&value| writer.write_ue(value));
+        signed.iter().for_each(|&value| writer.write_se(value));
+
+        let data = writer.into_inner();
+        let mut reader = BitReader::new(&data);
+
+        for value in unsigned {
+            assert_eq!(reader.read_ue(), Ok(value));
+        }
+
+        for value in signed {
+            assert_eq!(reader.read_se(), Ok(value));
+        }
+    }
+
+    #[test]
+    fn test_exp_golomb_codes() {
+        let mut writer = BitWriter::new();
+        writer.write_ue(0);
+        writer.write_ue(3);
+        writer.write_se(-1);
+        writer.byte_align();
+
+        // 1 00100 011 + padding
+        assert_eq!(writer.as_bytes(), [0b1001_0001, 0b1000_0000]);
+    }
+
+    #[test]
+    fn test_exp_golomb_out_of_range() {
+        // 2^32 + 1, beyond both u32 and se(v) of an i32: 32 zeros, then 2^32 + 2
+        // on 33 bits.
+        let mut writer = BitWriter::new();
+        writer.write_bits(0, 32);
+        writer.write_bit(true);
+        writer.write_bits(2, 32);
+
+        let data = writer.into_inner();
+        let mut reader = BitReader::new(&data);
+
+        assert_eq!(reader.read_ue(), Err(Error::InvalidData));
+        assert_eq!(reader.position(), 0);
+        assert_eq!(reader.read_se(), Err(Error::InvalidData));
+    }
+}
@@ -81,6 +81,26 @@ impl ChannelLayout {
         unsafe { self.0.u.mask }
     }
 
+    /// Returns the channel mask, whatever the channel order of the layout.
+    ///
+    /// Unlike [`bits()`](Self::bits), which reads the raw mask and is only meaningful for
+    /// native-order layouts, this also works for custom orders. Channels that have no
+    /// mask bit are left out.
+    pub fn mask(&self) -> u64 {
+        unsafe { av_channel_layout_subset(&self.0, u64::MAX) }
+    }
+
+    /// Builds a native-order layout from a channel mask.
+    ///
+    /// A mask of `0` yields an empty (unspecified) layout.
+    pub fn from_mask(mask: u64) -> ChannelLayout {
+        unsafe {
+            let mut channel_layout = std::mem::zeroed();
+            av_channel_layout_from_mask(&mut channel_layout, mask);
+            ChannelLayout(channel_layout)
+        }
+    }
+
     pub fn default(number: i32) -> ChannelLayout {
         unsafe {
             let mut channel_layout = std::mem::zeroed();
@@ -89,6 +109,11 @@ impl ChannelLayout {
         }
     }
 
+    /// Same as [`default()`](Self::default); named so it does not read as `Default::default`.
+    pub fn default_for(channels: i32) -> ChannelLayout {
+        Self::default(channels)
+    }
+
     // See https://ffmpeg.org/doxygen/trunk/group__lavu__audio__channels.html#gaa4a685b5c38835392552a7f96ee24a3e,
     // AV_CH_UNUSED
     pub fn is_empty(&self) -> bool {
@@ -0,0 +1,13 @@
+use crate::ffi::*;
+use libc::c_int;
+
+bitflags! {
+    /// Key matching flags for [`Ref::get_with()`](super::Ref::get_with).
+    #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+    pub struct Flags: c_int {
+        /// Compare keys case-sensitively; by default `title` matches `TITLE`.
+        const MATCH_CASE    = AV_DICT_MATCH_CASE;
+        /// Match any key starting with the given one.
+        const IGNORE_SUFFIX = AV_DICT_IGNORE_SUFFIX;
+    }
+}
@@ -6,7 +6,7 @@ use std::{
     str::from_utf8_unchecked,
 };
 
-use super::{Iter, Owned};
+use super::{Flags, Iter, Owned};
 use crate::ffi::*;
 
 pub struct Ref<'a> {
@@ -35,6 +35,50 @@ impl<'a> Ref<'a> {
         }
     }
 
+    /// Looks `key` up with explicit matching flags.
+    ///
+    /// Without [`Flags::MATCH_CASE`] keys are compared case-insensitively, which is
+    /// also what [`get()`](Self::get) does; with [`Flags::IGNORE_SUFFIX`] the first
+    /// key starting with `key` matches.
+    pub fn get_with(&'a self, key: &str, flags: Flags) -> Option<&'a str> {
+        unsafe {
+            let key = CString::new(key).unwrap();
+            let entry = av_dict_get(self.as_ptr(), key.as_ptr(), ptr::null_mut(), flags.bits());
+
+            if entry.is_null() { None } else { Some(from_utf8_unchecked(CStr::from_ptr((*entry).value).to_bytes())) }
+        }
+    }
+
+    /// Looks `key` up regardless of case, so `title` also finds the `TITLE` tag
+    /// written by some Matroska and FLAC muxers. Same as [`get()`](Self::get),
+    /// spelled out for readers expecting exact matching.
+    pub fn get_ignore_case(&'a self, key: &str) -> Option<&'a str> {
+        self.get_with(key, Flags::empty())
+    }
+
+    /// Returns every entry whose key starts with `prefix` (compared
+    /// case-insensitively), e.g. all `title-*` variants or the `com.apple.*` keys.
+    pub fn get_with_prefix(&'a self, prefix: &str) -> Vec<(&'a str, &'a str)> {
+        let mut entries = Vec::new();
+
+        unsafe {
+            let prefix = CString::new(prefix).unwrap();
+            let mut entry = ptr::null_mut();
+
+            loop {
+                entry = av_dict_get(self.as_ptr(), prefix.as_ptr(), entry, AV_DICT_IGNORE_SUFFIX);
+
+                if entry.is_null() {
+                    break;
+                }
+
+                entries.push((from_utf8_unchecked(CStr::from_ptr((*entry).key).to_bytes()), from_utf8_unchecked(CStr::from_ptr((*entry).value).to_bytes())));
+            }
+        }
+
+        entries
+    }
+
     pub fn iter(&self) -> Iter<'_> {
         unsafe { Iter::new(self.as_ptr()) }
     }
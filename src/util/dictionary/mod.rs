@@ -10,6 +10,9 @@ pub use self::owned::Owned;
 mod iter;
 pub use self::iter::Iter;
 
+mod flag;
+pub use self::flag::Flags;
+
 #[macro_export]
 macro_rules! dict {
 	( $($key:expr => $value:expr),* $(,)*) => ({
@@ -0,0 +1,147 @@
+//! Typed FIFO over FFmpeg's element-based `AVFifo` (FFmpeg 5.1+).
+
+use std::{marker::PhantomData, mem};
+
+use crate::{Error, ffi::*};
+
+/// First-in first-out queue of `T` values, backed by `AVFifo`.
+///
+/// Elements are copied in and out bytewise, hence the `Copy` bound: store plain
+/// values (indices, timestamps, small structs), not types owning resources.
+///
+/// The FIFO is created with a capacity; [`write()`](Self::write) fails with
+/// `ENOSPC` once it is full, unless auto-grow was requested, in which case it
+/// grows up to the [limit](Self::set_auto_grow_limit).
+pub struct Fifo<T: Copy> {
+    ptr: *mut AVFifo,
+    _marker: PhantomData<T>,
+}
+
+unsafe impl<T: Copy + Send> Send for Fifo<T> {}
+unsafe impl<T: Copy + Sync> Sync for Fifo<T> {}
+
+impl<T: Copy> Fifo<T> {
+    /// Creates a FIFO with room for `capacity` elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `T` is zero-sized.
+    pub fn new(capacity: usize, auto_grow: bool) -> Self {
+        assert!(mem::size_of::<T>() > 0, "zero-sized elements are not supported");
+
+        unsafe {
+            let ptr = av_fifo_alloc2(capacity, mem::size_of::<T>(), if auto_grow { AV_FIFO_FLAG_AUTO_GROW as _ } else { 0 });
+
+            if ptr.is_null() {
+                panic!("out of memory");
+            }
+
+            Fifo { ptr, _marker: PhantomData }
+        }
+    }
+
+    /// Bounds automatic growth to `max` elements in total (FFmpeg's default is
+    /// about 1 MiB worth of elements).
+    pub fn set_auto_grow_limit(&mut self, max: usize) {
+        unsafe {
+            av_fifo_auto_grow_limit(self.ptr, max);
+        }
+    }
+
+    /// Number of elements ready to be read.
+    pub fn size(&self) -> usize {
+        unsafe { av_fifo_can_read(self.ptr) }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size() == 0
+    }
+
+    /// Number of elements that can be written without growing.
+    pub fn space(&self) -> usize {
+        unsafe { av_fifo_can_write(self.ptr) }
+    }
+
+    /// Makes room for `additional` more elements.
+    pub fn grow(&mut self, additional: usize) -> Result<(), Error> {
+        unsafe {
+            match av_fifo_grow2(self.ptr, additional) {
+                e if e < 0 => Err(Error::from(e)),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    /// Appends all of `items`, or none of them if they do not fit.
+    pub fn write(&mut self, items: &[T]) -> Result<(), Error> {
+        unsafe {
+            match av_fifo_write(self.ptr, items.as_ptr() as *const _, items.len()) {
+                e if e < 0 => Err(Error::from(e)),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    /// Appends a single element.
+    pub fn push(&mut self, item: T) -> Result<(), Error> {
+        self.write(&[item])
+    }
+
+    /// Removes the oldest `out.len()` elements into `out`.
+    ///
+    /// Fails with `EINVAL`, leaving the FIFO untouched, if fewer are available.
+    pub fn read(&mut self, out: &mut [T]) -> Result<(), Error> {
+        unsafe {
+            match av_fifo_read(self.ptr, out.as_mut_ptr() as *mut _, out.len()) {
+                e if e < 0 => Err(Error::from(e)),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    /// Removes and returns the oldest element.
+    pub fn pop(&mut self) -> Option<T> {
+        let mut item = mem::MaybeUninit::<T>::uninit();
+
+        unsafe { if av_fifo_read(self.ptr, item.as_mut_ptr() as *mut _, 1) < 0 { None } else { Some(item.assume_init()) } }
+    }
+
+    /// Copies `out.len()` elements starting `offset` elements after the oldest
+    /// one, without removing them.
+    pub fn peek(&self, out: &mut [T], offset: usize) -> Result<(), Error> {
+        unsafe {
+            match av_fifo_peek(self.ptr, out.as_mut_ptr() as *mut _, out.len(), offset) {
+                e if e < 0 => Err(Error::from(e)),
+                _ => Ok(()),
+            }
+        }
+    }
+
+    /// Discards the `count` oldest elements.
+    ///
+    /// # Panics
+    ///
+    /// Panics if fewer than `count` elements are available.
+    pub fn drain(&mut self, count: usize) {
+        assert!(count <= self.size(), "draining more elements than available");
+
+        unsafe {
+            av_fifo_drain2(self.ptr, count);
+        }
+    }
+
+    /// Discards every element.
+    pub fn clear(&mut self) {
+        unsafe {
+            av_fifo_reset2(self.ptr);
+        }
+    }
+}
+
+impl<T: Copy> Drop for Fifo<T> {
+    fn drop(&mut self) {
+        unsafe {
+            av_fifo_freep2(&mut self.ptr);
+        }
+    }
+}
@@ -5,7 +5,7 @@ use std::{
 };
 
 use super::Frame;
-use crate::{ChannelLayout, ffi::*, util::format};
+use crate::{ChannelLayout, Rational, Rescale, ffi::*, util::format};
 use libc::c_int;
 
 #[derive(PartialEq, Eq)]
@@ -132,6 +132,15 @@ impl Audio {
         }
     }
 
+    /// Sets the pts from the position of the frame's first sample, i.e. the number
+    /// of samples emitted before it at `rate` Hz, rescaled to `time_base`.
+    ///
+    /// Passing a running sample count keeps the pts of generated frames exact; with
+    /// a `1/rate` time base the pts is the sample count itself.
+    pub fn set_pts_from_sample_count(&mut self, samples: i64, rate: u32, time_base: Rational) {
+        self.set_pts(Some(samples.rescale((1, rate as i32), time_base)));
+    }
+
     #[inline]
     pub fn is_planar(&self) -> bool {
         self.format().is_planar()
@@ -179,6 +188,47 @@ impl Audio {
         unsafe { slice::from_raw_parts_mut((*self.as_mut_ptr()).data[index] as *mut T, self.samples()) }
     }
 
+    /// Returns the interleaved samples of a packed frame.
+    ///
+    /// `T` is either the scalar sample type (yielding `samples() * channels()` values)
+    /// or a tuple with one element per channel (yielding `samples()` values).
+    ///
+    /// # Panics
+    ///
+    /// Panics if the frame is planar or `T` does not match the frame's sample format.
+    #[inline]
+    pub fn as_slice<T: Sample>(&self) -> &[T] {
+        if !self.is_packed() {
+            panic!("frame is not packed");
+        }
+
+        self.plane_slices::<T>().pop().unwrap_or_default()
+    }
+
+    /// Returns every plane of the frame as a typed slice.
+    ///
+    /// Planar frames yield one slice of `samples()` values per channel; packed frames
+    /// yield a single interleaved slice, as [`as_slice()`](Self::as_slice) does.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `T` does not match the frame's sample format.
+    #[inline]
+    pub fn plane_slices<T: Sample>(&self) -> Vec<&[T]> {
+        if !<T as Sample>::is_valid(self.format(), self.channels()) {
+            panic!("unsupported type");
+        }
+
+        let interleaved = if self.is_packed() { self.channels() as usize } else { 1 };
+        let len = self.samples() * interleaved * self.format().bytes() / mem::size_of::<T>();
+
+        (0..self.planes()).map(|index| unsafe { slice::from_raw_parts(*(*self.as_ptr()).extended_data.add(index) as *const T, len) }).collect()
+    }
+
+    /// Raw bytes of plane `index`.
+    ///
+    /// Like the other plane accessors, the slice is tied to a shared borrow of the
+    /// frame, so the frame cannot be handed to `receive_frame` again while it is held.
     #[inline]
     pub fn data(&self, index: usize) -> &[u8] {
         if index >= self.planes() {
@@ -528,3 +578,29 @@ unsafe impl Sample for (f64, f64, f64, f64, f64, f64, f64, f64) {
         channels == 8 && format == format::Sample::F64(format::sample::Type::Packed)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pts_from_sample_count() {
+        let mut frame = Audio::new(format::Sample::I16(format::sample::Type::Packed), 1024, ChannelLayout::STEREO);
+        let mut samples = 0;
+        let mut last = None;
+
+        for _ in 0..10 {
+            frame.set_pts_from_sample_count(samples, 48000, Rational(1, 90000));
+
+            let pts = frame.pts().unwrap();
+            assert_eq!(pts, samples * 90000 / 48000);
+            assert!(last.is_none_or(|last| pts > last));
+
+            last = Some(pts);
+            samples += frame.samples() as i64;
+        }
+
+        frame.set_pts_from_sample_count(4096, 48000, Rational(1, 48000));
+        assert_eq!(frame.pts(), Some(4096));
+    }
+}
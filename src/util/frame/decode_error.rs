@@ -0,0 +1,14 @@
+use crate::ffi::*;
+use libc::c_int;
+
+bitflags! {
+    /// Errors the decoder ran into while producing a frame (`decode_error_flags`).
+    #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+    pub struct DecodeErrors: c_int {
+        const INVALID_BITSTREAM  = FF_DECODE_ERROR_INVALID_BITSTREAM as c_int;
+        const MISSING_REFERENCE  = FF_DECODE_ERROR_MISSING_REFERENCE as c_int;
+        const CONCEALMENT_ACTIVE = FF_DECODE_ERROR_CONCEALMENT_ACTIVE as c_int;
+        #[cfg(feature = "ffmpeg_4_2")]
+        const DECODE_SLICES      = FF_DECODE_ERROR_DECODE_SLICES as c_int;
+    }
+}
@@ -0,0 +1,17 @@
+use crate::ffi::*;
+use libc::c_int;
+
+bitflags! {
+    /// Access requested when mapping a hardware frame, see
+    /// [`Frame::hwframe_map()`](super::Frame::hwframe_map).
+    #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+    pub struct MapFlags: c_int {
+        const READ = AV_HWFRAME_MAP_READ as c_int;
+        const WRITE = AV_HWFRAME_MAP_WRITE as c_int;
+        /// The previous contents need not be preserved; combined with `WRITE`, lets
+        /// the mapping skip a download.
+        const OVERWRITE = AV_HWFRAME_MAP_OVERWRITE as c_int;
+        /// Fail rather than map through an intermediate copy.
+        const DIRECT = AV_HWFRAME_MAP_DIRECT as c_int;
+    }
+}
@@ -10,7 +10,18 @@ pub use self::audio::Audio;
 pub mod flag;
 pub use self::flag::Flags;
 
-use crate::{Dictionary, DictionaryRef, ffi::*};
+pub mod decode_error;
+pub use self::decode_error::DecodeErrors;
+
+pub mod pool;
+pub use self::pool::{FramePool, PooledFrame};
+
+pub mod map;
+pub use self::map::MapFlags;
+
+use std::slice;
+
+use crate::{Dictionary, DictionaryRef, Error, ffi::*};
 
 #[derive(PartialEq, Eq, Copy, Clone, Debug)]
 pub struct Packet {
@@ -75,11 +86,43 @@ impl Frame {
         }
     }
 
+    /// Whether the decoder flagged the frame as corrupt.
+    ///
+    /// Decoders only output corrupt frames when `codec::Flags::OUTPUT_CORRUPT` is set
+    /// (the default), and only detect corruption to the extent requested with
+    /// [`Decoder::check()`](crate::decoder::Decoder::check). See
+    /// [`decode_errors()`](Self::decode_errors) for the details.
     #[inline]
     pub fn is_corrupt(&self) -> bool {
         self.flags().contains(Flags::CORRUPT)
     }
 
+    /// Errors encountered while decoding this frame, e.g. missing references after a
+    /// seek or concealed slices. Counting frames with non-empty flags gives the
+    /// corruption rate of a stream; FFmpeg keeps no such counter itself.
+    #[inline]
+    pub fn decode_errors(&self) -> DecodeErrors {
+        unsafe { DecodeErrors::from_bits_truncate((*self.as_ptr()).decode_error_flags) }
+    }
+
+    /// Whether the frame data can be written without affecting other references.
+    #[inline]
+    pub fn is_writable(&self) -> bool {
+        unsafe { av_frame_is_writable(self.as_ptr() as *mut _) != 0 }
+    }
+
+    /// Makes the frame data writable, copying it if the buffers are shared with other
+    /// frames (e.g. a decoder's reference frames).
+    #[inline]
+    pub fn make_writable(&mut self) -> Result<(), Error> {
+        unsafe {
+            match av_frame_make_writable(self.as_mut_ptr()) {
+                0 => Ok(()),
+                e => Err(Error::from(e)),
+            }
+        }
+    }
+
     #[inline]
     pub fn packet(&self) -> Packet {
         unsafe {
@@ -148,6 +191,106 @@ impl Frame {
         unsafe { (*self.as_mut_ptr()).metadata = value.disown() }
     }
 
+    /// User data attached with [`set_opaque_ref()`](Self::set_opaque_ref).
+    #[inline]
+    pub fn opaque_ref(&self) -> Option<&[u8]> {
+        unsafe {
+            let buffer = (*self.as_ptr()).opaque_ref;
+
+            if buffer.is_null() { None } else { Some(slice::from_raw_parts((*buffer).data, (*buffer).size as usize)) }
+        }
+    }
+
+    /// Attaches a copy of `data` to the frame, replacing any previous one.
+    ///
+    /// The data follows the frame through filters and copies of the frame. Encoders
+    /// opened with [`codec::Flags::COPY_OPAQUE`](crate::codec::Flags::COPY_OPAQUE)
+    /// (FFmpeg 6.0+) hand it on to the packets produced from the frame, which
+    /// allows correlating output packets with input frames. Not every encoder
+    /// supports this, so be prepared for packets without it.
+    pub fn set_opaque_ref<D: AsRef<[u8]>>(&mut self, data: D) {
+        let data = data.as_ref();
+
+        unsafe {
+            let buffer = av_buffer_alloc(data.len() as _);
+
+            if buffer.is_null() {
+                panic!("out of memory");
+            }
+
+            slice::from_raw_parts_mut((*buffer).data, data.len()).copy_from_slice(data);
+
+            av_buffer_unref(&mut (*self.as_mut_ptr()).opaque_ref);
+            (*self.as_mut_ptr()).opaque_ref = buffer;
+        }
+    }
+
+    #[inline]
+    pub fn clear_opaque_ref(&mut self) {
+        unsafe {
+            av_buffer_unref(&mut (*self.as_mut_ptr()).opaque_ref);
+        }
+    }
+
+    /// Whether the frame data lives in a hardware surface (`hw_frames_ctx` is set).
+    #[inline]
+    pub fn is_hardware(&self) -> bool {
+        unsafe { !(*self.as_ptr()).hw_frames_ctx.is_null() }
+    }
+
+    /// Maps a hardware frame into CPU-accessible memory (`av_hwframe_map`).
+    ///
+    /// The returned frame has the software format of the surface and its planes
+    /// point into the mapping, so reading part of a frame does not pay for a full
+    /// download. The mapping is released when the returned frame is dropped; it
+    /// keeps the surface alive until then. Writing through the planes requires
+    /// [`MapFlags::WRITE`].
+    ///
+    /// Support depends on the backend: VAAPI, QSV, DRM and DXVA2/D3D11 can map,
+    /// while CUDA, VideoToolbox and Vulkan in some versions cannot. When mapping is
+    /// unsupported (`ENOSYS`) and no write access was requested, the data is
+    /// copied with [`transfer_data()`](Self::transfer_data) instead. Fails with
+    /// `EINVAL` for software frames.
+    pub fn hwframe_map(&self, flags: MapFlags) -> Result<Frame, Error> {
+        if !self.is_hardware() {
+            return Err(Error::Other { errno: libc::EINVAL });
+        }
+
+        unsafe {
+            let mut mapped = Frame::empty();
+
+            match av_hwframe_map(mapped.as_mut_ptr(), self.as_ptr(), flags.bits()) {
+                e if e == AVERROR(libc::ENOSYS) && !flags.contains(MapFlags::WRITE) => self.transfer_data(),
+                e if e < 0 => Err(Error::from(e)),
+                _ => Ok(mapped),
+            }
+        }
+    }
+
+    /// Downloads a hardware frame into a new frame in system memory
+    /// (`av_hwframe_transfer_data`), in the software format of the surface. The
+    /// timestamps and other properties are copied along.
+    pub fn transfer_data(&self) -> Result<Frame, Error> {
+        if !self.is_hardware() {
+            return Err(Error::Other { errno: libc::EINVAL });
+        }
+
+        unsafe {
+            let mut frame = Frame::empty();
+
+            let res = av_hwframe_transfer_data(frame.as_mut_ptr(), self.as_ptr(), 0);
+
+            if res < 0 {
+                return Err(Error::from(res));
+            }
+
+            match av_frame_copy_props(frame.as_mut_ptr(), self.as_ptr()) {
+                e if e < 0 => Err(Error::from(e)),
+                _ => Ok(frame),
+            }
+        }
+    }
+
     #[inline]
     pub fn side_data(&self, kind: side_data::Type) -> Option<SideData<'_>> {
         unsafe {
@@ -0,0 +1,109 @@
+use std::{
+    ops::{Deref, DerefMut},
+    sync::{Arc, Mutex},
+};
+
+use super::Video;
+use crate::util::format;
+
+/// Recycles video frames of one format and size.
+///
+/// [`get()`](Self::get) hands out a [`PooledFrame`] that goes back to the pool when
+/// dropped, buffers included, so a steady-state pipeline stops allocating once the
+/// pool holds as many frames as are in flight. Frames that come back with other
+/// dimensions or a format change, or whose buffers are still shared with another
+/// frame, are freed instead of being recycled.
+///
+/// Useful as destination of [`scaling::Context::run()`](crate::software::scaling::Context::run),
+/// of conversions and of generated content. Decoders and buffer sinks replace the
+/// buffers of the frame they are given with their own (FFmpeg already pools those
+/// internally), so drawing their output frames from here saves nothing.
+///
+/// The pool is cheap to clone; clones share the same frames and can be used from
+/// several threads.
+#[derive(Clone)]
+pub struct FramePool {
+    format: format::Pixel,
+    width: u32,
+    height: u32,
+    free: Arc<Mutex<Vec<Video>>>,
+}
+
+impl FramePool {
+    /// Creates a pool and allocates `capacity` frames up front.
+    pub fn new(format: format::Pixel, width: u32, height: u32, capacity: usize) -> Self {
+        let free = (0..capacity).map(|_| Video::new(format, width, height)).collect();
+
+        FramePool { format, width, height, free: Arc::new(Mutex::new(free)) }
+    }
+
+    /// Takes a frame from the pool, allocating a new one if none is free.
+    ///
+    /// The frame keeps the data of its previous use; its pts is reset.
+    pub fn get(&self) -> PooledFrame {
+        let frame = self.free.lock().unwrap().pop();
+
+        let mut frame = frame.unwrap_or_else(|| Video::new(self.format, self.width, self.height));
+        frame.set_pts(None);
+
+        PooledFrame { frame: Some(frame), pool: self.clone() }
+    }
+
+    /// Number of frames currently waiting in the pool.
+    pub fn available(&self) -> usize {
+        self.free.lock().unwrap().len()
+    }
+
+    pub fn format(&self) -> format::Pixel {
+        self.format
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    fn recycle(&self, frame: Video) {
+        if unsafe { !frame.is_empty() } && frame.format() == self.format && frame.width() == self.width && frame.height() == self.height && frame.is_writable() {
+            self.free.lock().unwrap().push(frame);
+        }
+    }
+}
+
+/// A frame borrowed from a [`FramePool`], returned to it on drop.
+pub struct PooledFrame {
+    frame: Option<Video>,
+    pool: FramePool,
+}
+
+impl PooledFrame {
+    /// Takes the frame out of the pool for good.
+    pub fn detach(mut self) -> Video {
+        self.frame.take().unwrap()
+    }
+}
+
+impl Deref for PooledFrame {
+    type Target = Video;
+
+    fn deref(&self) -> &Video {
+        self.frame.as_ref().unwrap()
+    }
+}
+
+impl DerefMut for PooledFrame {
+    fn deref_mut(&mut self) -> &mut Video {
+        self.frame.as_mut().unwrap()
+    }
+}
+
+impl Drop for PooledFrame {
+    fn drop(&mut self) {
+        if let Some(frame) = self.frame.take() {
+            self.pool.recycle(frame);
+        }
+    }
+}
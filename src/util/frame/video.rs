@@ -1,4 +1,5 @@
 use std::{
+    hash::{DefaultHasher, Hash, Hasher},
     mem,
     ops::{Deref, DerefMut},
     slice,
@@ -6,7 +7,7 @@ use std::{
 
 use super::Frame;
 use crate::{
-    Rational, color,
+    Error, Rational, color,
     ffi::*,
     picture,
     util::{chroma, format},
@@ -104,6 +105,14 @@ impl Video {
         unsafe { (*self.as_ptr()).palette_has_changed != 0 }
     }
 
+    /// Sets the pts to `seconds` expressed in `time_base`, rounded to the nearest tick.
+    ///
+    /// Handy for generated content, where the presentation time is known in seconds
+    /// (e.g. `index as f64 / fps`) rather than in ticks.
+    pub fn set_pts_seconds(&mut self, seconds: f64, time_base: Rational) {
+        self.set_pts(Some((seconds / f64::from(time_base)).round() as i64));
+    }
+
     #[inline]
     pub fn width(&self) -> u32 {
         unsafe { (*self.as_ptr()).width as u32 }
@@ -152,6 +161,16 @@ impl Video {
         }
     }
 
+    /// Fills the image with black as the format and color range define it, e.g.
+    /// luma 16 and chroma 128 for limited range 8-bit YUV, instead of zeros that
+    /// show as green. Set the color range first. See
+    /// [`image::fill_black()`](crate::util::image::fill_black).
+    #[cfg(feature = "ffmpeg_4_3")]
+    #[inline]
+    pub fn fill_black(&mut self) -> Result<(), Error> {
+        crate::util::image::fill_black(self)
+    }
+
     #[inline]
     pub fn color_primaries(&self) -> color::Primaries {
         unsafe { color::Primaries::from((*self.as_ptr()).color_primaries) }
@@ -289,6 +308,24 @@ impl Video {
         unsafe { slice::from_raw_parts_mut((*self.as_mut_ptr()).data[index] as *mut T, self.stride(index) * self.plane_height(index) as usize / mem::size_of::<T>()) }
     }
 
+    /// Raw bytes of plane `index`, including the padding at the end of each line.
+    ///
+    /// The slice borrows the frame, so it cannot outlive a reuse of the frame:
+    /// decoding into it again needs `&mut`, which the borrow checker rejects while
+    /// the slice is alive. Copy the data out (or clone the frame) to keep it.
+    ///
+    /// ```compile_fail
+    /// # use playa_ffmpeg::{decoder, frame};
+    /// # fn run(decoder: &mut decoder::Video) {
+    /// let mut frame = frame::Video::empty();
+    /// decoder.receive_frame(&mut frame).unwrap();
+    /// let luma = frame.data(0);
+    ///
+    /// // error[E0502]: cannot borrow `frame` as mutable because it is also borrowed as immutable
+    /// decoder.receive_frame(&mut frame).unwrap();
+    /// println!("{}", luma[0]);
+    /// # }
+    /// ```
     #[inline]
     pub fn data(&self, index: usize) -> &[u8] {
         if index >= self.planes() {
@@ -306,6 +343,170 @@ impl Video {
 
         unsafe { slice::from_raw_parts_mut((*self.as_mut_ptr()).data[index], self.stride(index) * self.plane_height(index) as usize) }
     }
+
+    /// Applies `f` to every pixel in place.
+    ///
+    /// The frame is made writable first (copying the data if it is shared), then `f`
+    /// is called once per pixel with its components, in row order, skipping the
+    /// padding at the end of each line. Supported formats and the component order of
+    /// the slice passed to `f`:
+    ///
+    /// - `GRAY8`: `[y]`
+    /// - `RGB24`, `BGR24`, `RGBA`, `BGRA`: the components in memory order
+    /// - `YUV420P`, `YUVJ420P`: `[y, u, v]`
+    ///
+    /// In 4:2:0 formats four pixels share one chroma sample: every pixel of the 2×2
+    /// block sees the original chroma, and the chroma written back is the one produced
+    /// for the block's top-left pixel.
+    ///
+    /// # Errors
+    ///
+    /// Returns `Error::InvalidData` for other formats or frames without data.
+    pub fn map_pixels<F: FnMut(&mut [u8])>(&mut self, mut f: F) -> Result<(), Error> {
+        let components = match self.format() {
+            format::Pixel::GRAY8 => 1,
+            format::Pixel::RGB24 | format::Pixel::BGR24 => 3,
+            format::Pixel::RGBA | format::Pixel::BGRA => 4,
+            format::Pixel::YUV420P | format::Pixel::YUVJ420P => 0,
+            _ => return Err(Error::InvalidData),
+        };
+
+        if unsafe { self.is_empty() } {
+            return Err(Error::InvalidData);
+        }
+
+        self.make_writable()?;
+
+        let width = self.width() as usize;
+        let height = self.height() as usize;
+
+        if components > 0 {
+            let stride = self.stride(0);
+            let data = self.data_mut(0);
+
+            for y in 0..height {
+                let line = &mut data[y * stride..y * stride + width * components];
+
+                for pixel in line.chunks_exact_mut(components) {
+                    f(pixel);
+                }
+            }
+
+            return Ok(());
+        }
+
+        let strides = [self.stride(0), self.stride(1), self.stride(2)];
+
+        unsafe {
+            let data = (*self.as_mut_ptr()).data;
+
+            for cy in 0..height.div_ceil(2) {
+                for cx in 0..width.div_ceil(2) {
+                    let u = data[1].add(cy * strides[1] + cx);
+                    let v = data[2].add(cy * strides[2] + cx);
+                    let chroma = (*u, *v);
+
+                    for y in (cy * 2)..(cy * 2 + 2).min(height) {
+                        for x in (cx * 2)..(cx * 2 + 2).min(width) {
+                            let luma = data[0].add(y * strides[0] + x);
+                            let mut pixel = [*luma, chroma.0, chroma.1];
+
+                            f(&mut pixel);
+
+                            *luma = pixel[0];
+
+                            if y == cy * 2 && x == cx * 2 {
+                                *u = pixel[1];
+                                *v = pixel[2];
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Whether both frames have the same format, dimensions and pixels.
+    ///
+    /// Only the visible part of each line is compared, so frames with different
+    /// strides (e.g. from different decoders or pools) can still be identical. For
+    /// paletted formats the palette is compared as well. Frames must be in system
+    /// memory; hardware frames have to be transferred first.
+    pub fn is_identical(&self, other: &Video) -> bool {
+        if self.format() != other.format() || self.width() != other.width() || self.height() != other.height() {
+            return false;
+        }
+
+        self.palette() == other.palette() && self.rows().eq(other.rows())
+    }
+
+    /// Mean absolute difference of the two frames, per byte of visible data, from
+    /// `0.0` for identical frames up to `255.0`.
+    ///
+    /// Returns `None` if the frames differ in format or dimensions. Bytes are
+    /// compared individually, so for formats deeper than 8 bits the value is only
+    /// a rough measure.
+    pub fn mean_absolute_difference(&self, other: &Video) -> Option<f64> {
+        if self.format() != other.format() || self.width() != other.width() || self.height() != other.height() {
+            return None;
+        }
+
+        let mut sum = 0u64;
+        let mut count = 0u64;
+
+        for (a, b) in self.rows().zip(other.rows()) {
+            sum += a.iter().zip(b).map(|(&a, &b)| u64::from(a.abs_diff(b))).sum::<u64>();
+            count += a.len() as u64;
+        }
+
+        Some(if count == 0 { 0.0 } else { sum as f64 / count as f64 })
+    }
+
+    /// Hash of the format, dimensions and visible pixels, independent of the
+    /// strides: frames for which [`is_identical()`](Self::is_identical) holds hash
+    /// the same.
+    ///
+    /// The value is only meaningful within one process; it is not stable across
+    /// Rust releases and must not be persisted.
+    pub fn frame_hash(&self) -> u64 {
+        let mut hasher = DefaultHasher::new();
+
+        (AVPixelFormat::from(self.format()) as i32).hash(&mut hasher);
+        self.width().hash(&mut hasher);
+        self.height().hash(&mut hasher);
+        self.palette().hash(&mut hasher);
+
+        for row in self.rows() {
+            hasher.write(row);
+        }
+
+        hasher.finish()
+    }
+
+    // Visible bytes of every line of every plane, without the padding. Planes that
+    // are not image data (the palette of PAL8) yield no lines.
+    fn rows(&self) -> impl Iterator<Item = &[u8]> + '_ {
+        (0..self.planes()).flat_map(move |index| {
+            let stride = self.stride(index);
+            let width = unsafe { av_image_get_linesize(self.format().into(), self.width() as c_int, index as c_int).max(0) as usize };
+            let height = if width == 0 { 0 } else { self.plane_height(index) as usize };
+            let data = unsafe { (*self.as_ptr()).data[index] };
+
+            (0..height).map(move |y| unsafe { slice::from_raw_parts(data.add(y * stride), width) })
+        })
+    }
+
+    fn palette(&self) -> Option<&[u8]> {
+        let paletted = self.format().descriptor().is_some_and(|descriptor| unsafe { (*descriptor.as_ptr()).flags & AV_PIX_FMT_FLAG_PAL as u64 != 0 });
+
+        unsafe {
+            let data = (*self.as_ptr()).data[1];
+
+            if !paletted || data.is_null() { None } else { Some(slice::from_raw_parts(data, 1024)) }
+        }
+    }
 }
 
 impl Deref for Video {
@@ -0,0 +1,131 @@
+//! Hardware acceleration backends.
+//!
+//! Which device types are usable depends on how the linked FFmpeg was configured
+//! (`--enable-cuda`, `--enable-vaapi`, ...), not on the platform alone, so probe
+//! them with [`device_types()`] before creating a device.
+
+use std::{
+    ffi::{CStr, CString},
+    str::from_utf8_unchecked,
+};
+
+use crate::ffi::{AVHWDeviceType::*, *};
+
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum DeviceType {
+    None,
+    VDPAU,
+    CUDA,
+    VAAPI,
+    DXVA2,
+    QSV,
+    VIDEOTOOLBOX,
+    D3D11VA,
+    DRM,
+    OPENCL,
+    MEDIACODEC,
+    #[cfg(feature = "ffmpeg_4_3")]
+    VULKAN,
+    #[cfg(feature = "ffmpeg_7_0")]
+    D3D12VA,
+    #[cfg(feature = "ffmpeg_7_1")]
+    AMF,
+}
+
+impl DeviceType {
+    /// Looks a device type up by its FFmpeg name (`cuda`, `vaapi`, `qsv`,
+    /// `d3d11va`, `videotoolbox`, `vulkan`, ...), as accepted by `ffmpeg
+    /// -init_hw_device`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        let name = CString::new(name).ok()?;
+
+        match DeviceType::from(unsafe { av_hwdevice_find_type_by_name(name.as_ptr()) }) {
+            DeviceType::None => None,
+            kind => Some(kind),
+        }
+    }
+
+    /// FFmpeg name of the device type, `None` for [`DeviceType::None`].
+    pub fn name(&self) -> Option<&'static str> {
+        unsafe {
+            let ptr = av_hwdevice_get_type_name((*self).into());
+
+            if ptr.is_null() { None } else { Some(from_utf8_unchecked(CStr::from_ptr(ptr).to_bytes())) }
+        }
+    }
+}
+
+/// Device types supported by the linked FFmpeg build (`av_hwdevice_iterate_types`),
+/// in FFmpeg's order.
+///
+/// Support only means FFmpeg was built with the backend; creating a device can
+/// still fail at runtime without a matching GPU or driver.
+pub fn device_types() -> Vec<DeviceType> {
+    let mut types = Vec::new();
+    let mut current = AV_HWDEVICE_TYPE_NONE;
+
+    loop {
+        current = unsafe { av_hwdevice_iterate_types(current) };
+
+        if current == AV_HWDEVICE_TYPE_NONE {
+            return types;
+        }
+
+        match DeviceType::from(current) {
+            DeviceType::None => (),
+            kind => types.push(kind),
+        }
+    }
+}
+
+impl From<AVHWDeviceType> for DeviceType {
+    fn from(value: AVHWDeviceType) -> Self {
+        match value {
+            AV_HWDEVICE_TYPE_NONE => DeviceType::None,
+            AV_HWDEVICE_TYPE_VDPAU => DeviceType::VDPAU,
+            AV_HWDEVICE_TYPE_CUDA => DeviceType::CUDA,
+            AV_HWDEVICE_TYPE_VAAPI => DeviceType::VAAPI,
+            AV_HWDEVICE_TYPE_DXVA2 => DeviceType::DXVA2,
+            AV_HWDEVICE_TYPE_QSV => DeviceType::QSV,
+            AV_HWDEVICE_TYPE_VIDEOTOOLBOX => DeviceType::VIDEOTOOLBOX,
+            AV_HWDEVICE_TYPE_D3D11VA => DeviceType::D3D11VA,
+            AV_HWDEVICE_TYPE_DRM => DeviceType::DRM,
+            AV_HWDEVICE_TYPE_OPENCL => DeviceType::OPENCL,
+            AV_HWDEVICE_TYPE_MEDIACODEC => DeviceType::MEDIACODEC,
+            #[cfg(feature = "ffmpeg_4_3")]
+            AV_HWDEVICE_TYPE_VULKAN => DeviceType::VULKAN,
+            #[cfg(feature = "ffmpeg_7_0")]
+            AV_HWDEVICE_TYPE_D3D12VA => DeviceType::D3D12VA,
+            #[cfg(feature = "ffmpeg_7_1")]
+            AV_HWDEVICE_TYPE_AMF => DeviceType::AMF,
+
+            // backends added in later FFmpeg versions
+            #[allow(unreachable_patterns)]
+            _ => DeviceType::None,
+        }
+    }
+}
+
+impl From<DeviceType> for AVHWDeviceType {
+    fn from(value: DeviceType) -> AVHWDeviceType {
+        match value {
+            DeviceType::None => AV_HWDEVICE_TYPE_NONE,
+            DeviceType::VDPAU => AV_HWDEVICE_TYPE_VDPAU,
+            DeviceType::CUDA => AV_HWDEVICE_TYPE_CUDA,
+            DeviceType::VAAPI => AV_HWDEVICE_TYPE_VAAPI,
+            DeviceType::DXVA2 => AV_HWDEVICE_TYPE_DXVA2,
+            DeviceType::QSV => AV_HWDEVICE_TYPE_QSV,
+            DeviceType::VIDEOTOOLBOX => AV_HWDEVICE_TYPE_VIDEOTOOLBOX,
+            DeviceType::D3D11VA => AV_HWDEVICE_TYPE_D3D11VA,
+            DeviceType::DRM => AV_HWDEVICE_TYPE_DRM,
+            DeviceType::OPENCL => AV_HWDEVICE_TYPE_OPENCL,
+            DeviceType::MEDIACODEC => AV_HWDEVICE_TYPE_MEDIACODEC,
+            #[cfg(feature = "ffmpeg_4_3")]
+            DeviceType::VULKAN => AV_HWDEVICE_TYPE_VULKAN,
+            #[cfg(feature = "ffmpeg_7_0")]
+            DeviceType::D3D12VA => AV_HWDEVICE_TYPE_D3D12VA,
+            #[cfg(feature = "ffmpeg_7_1")]
+            DeviceType::AMF => AV_HWDEVICE_TYPE_AMF,
+        }
+    }
+}
@@ -0,0 +1,60 @@
+//! Operations on raw image buffers (`libavutil/imgutils.h`).
+
+use crate::{Error, ffi::*, frame};
+use libc::c_int;
+
+/// Copies the image data of `src` into `dst` (`av_image_copy`).
+///
+/// Every plane is copied line by line, so the two frames may use different strides
+/// (e.g. a decoder frame copied into a frame allocated with a larger alignment).
+/// Chroma planes are copied with their subsampled height and the palette of paletted
+/// formats is copied as well. Only the image data is copied, not the properties.
+///
+/// # Errors
+///
+/// Returns `Error::InvalidData` if `dst` has no buffers or if the two frames do not
+/// have the same pixel format and dimensions.
+pub fn copy(dst: &mut frame::Video, src: &frame::Video) -> Result<(), Error> {
+    if unsafe { dst.is_empty() } || dst.format() != src.format() || dst.width() != src.width() || dst.height() != src.height() {
+        return Err(Error::InvalidData);
+    }
+
+    unsafe {
+        av_image_copy((*dst.as_mut_ptr()).data.as_mut_ptr(), (*dst.as_ptr()).linesize.as_ptr(), (*src.as_ptr()).data.as_ptr() as *const *const _, (*src.as_ptr()).linesize.as_ptr(), src.format().into(), src.width() as c_int, src.height() as c_int);
+    }
+
+    Ok(())
+}
+
+/// Fills the image of `frame` with black (`av_image_fill_black`).
+///
+/// Black depends on the format: zero for RGB, but for YUV a luma of 16 (scaled to
+/// the bit depth) in limited range or 0 in full range, with chroma at the middle
+/// value (128 for 8 bits). A zero-filled YUV frame comes out green. The range
+/// is read from [`color_range()`](frame::Video::color_range); an unspecified one
+/// is taken as limited. The alpha plane, if any, is made opaque.
+///
+/// # Errors
+///
+/// Returns `Error::InvalidData` if `frame` has no buffers, and `EINVAL` for
+/// formats FFmpeg cannot fill, such as hardware formats.
+#[cfg(feature = "ffmpeg_4_3")]
+pub fn fill_black(frame: &mut frame::Video) -> Result<(), Error> {
+    if unsafe { frame.is_empty() } {
+        return Err(Error::InvalidData);
+    }
+
+    unsafe {
+        let ptr = frame.as_mut_ptr();
+        let mut linesize = [0isize; 4];
+
+        for (target, &source) in linesize.iter_mut().zip((*ptr).linesize.iter()) {
+            *target = source as isize;
+        }
+
+        match av_image_fill_black((*ptr).data.as_ptr() as *const *mut _, linesize.as_ptr(), frame.format().into(), frame.color_range().into(), frame.width() as c_int, frame.height() as c_int) {
+            e if e < 0 => Err(Error::from(e)),
+            _ => Ok(()),
+        }
+    }
+}
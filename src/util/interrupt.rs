@@ -1,12 +1,78 @@
-use std::{panic, process};
+use std::{
+    panic, process,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+};
 
 use crate::ffi::*;
 use libc::{c_int, c_void};
 
+type Callback = Box<dyn Fn() -> bool + Send + Sync>;
+
 pub struct Interrupt {
     pub interrupt: AVIOInterruptCB,
 }
 
+/// Interrupt callback of a format context (`AVIOInterruptCB`), which blocking
+/// operations poll to know whether to give up with [`Error::Exit`](crate::Error::Exit).
+///
+/// Unlike an [`Interrupt`] from [`new()`], a handle can be cloned and installed
+/// on any number of contexts, e.g. so that one shutdown flag aborts the input,
+/// the output and every connection they open:
+///
+/// ```ignore
+/// let shutdown = Arc::new(AtomicBool::new(false));
+/// let interrupt = SharedInterrupt::from_flag(shutdown.clone());
+///
+/// let mut input = format::input_interruptible(&"rtsp://camera/stream", &interrupt)?;
+/// let mut output = format::output_interruptible(&"rtmp://server/live", &interrupt)?;
+///
+/// ctrlc::set_handler(move || shutdown.store(true, Ordering::Relaxed))?;
+/// ```
+///
+/// FFmpeg only keeps a pointer to the callback, so the callback has to live as
+/// long as every context and connection it was installed on. The contexts take
+/// care of it: installing a handle through [`format::input_interruptible()`](crate::format::input_interruptible),
+/// [`format::output_interruptible()`](crate::format::output_interruptible) or
+/// `set_interrupt()` keeps a clone of it until the context is closed, so the
+/// handle passed in can be dropped at any time. Only the callback copied by hand
+/// from [`as_callback()`](Self::as_callback) escapes that and must not outlive
+/// the handle.
+#[derive(Clone)]
+pub struct SharedInterrupt {
+    callback: Arc<Callback>,
+}
+
+impl SharedInterrupt {
+    /// Creates a handle interrupting once `flag` is set.
+    pub fn from_flag(flag: Arc<AtomicBool>) -> Self {
+        Self::from_fn(move || flag.load(Ordering::Relaxed))
+    }
+
+    /// Creates a handle interrupting whenever `closure` returns `true`. It may
+    /// be called from any thread running an operation on one of the contexts,
+    /// several at once.
+    pub fn from_fn<F>(closure: F) -> Self
+    where
+        F: Fn() -> bool + Send + Sync + 'static,
+    {
+        SharedInterrupt { callback: Arc::new(Box::new(closure)) }
+    }
+
+    /// Whether the callback currently asks to interrupt.
+    pub fn is_interrupted(&self) -> bool {
+        (self.callback)()
+    }
+
+    /// The callback as FFmpeg takes it, valid as long as a clone of the handle
+    /// is alive.
+    pub fn as_callback(&self) -> AVIOInterruptCB {
+        AVIOInterruptCB { callback: Some(shared_callback), opaque: Arc::as_ptr(&self.callback) as *mut c_void }
+    }
+}
+
 extern "C" fn callback<F>(opaque: *mut c_void) -> c_int
 where
     F: FnMut() -> bool,
@@ -19,6 +85,13 @@ where
     }
 }
 
+extern "C" fn shared_callback(opaque: *mut c_void) -> c_int {
+    match panic::catch_unwind(|| (unsafe { &*(opaque as *const Callback) })()) {
+        Ok(ret) => ret as c_int,
+        Err(_) => process::abort(),
+    }
+}
+
 pub fn new<F>(opaque: Box<F>) -> Interrupt
 where
     F: FnMut() -> bool,
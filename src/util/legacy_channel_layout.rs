@@ -80,7 +80,25 @@ impl ChannelLayout {
         unsafe { av_get_channel_layout_nb_channels(self.bits()) }
     }
 
+    /// Returns the channel mask; provided for parity with the FFmpeg 7.0+ layout type.
+    #[inline]
+    pub fn mask(&self) -> u64 {
+        self.bits() as u64
+    }
+
+    /// Builds a layout from a channel mask; provided for parity with the FFmpeg 7.0+
+    /// layout type.
+    #[inline]
+    pub fn from_mask(mask: u64) -> ChannelLayout {
+        ChannelLayout::from_bits_truncate(mask as c_ulonglong)
+    }
+
     pub fn default(number: i32) -> ChannelLayout {
         unsafe { ChannelLayout::from_bits_truncate(av_get_default_channel_layout(number) as c_ulonglong) }
     }
+
+    /// Same as [`default()`](Self::default); named so it does not read as `Default::default`.
+    pub fn default_for(channels: i32) -> ChannelLayout {
+        Self::default(channels)
+    }
 }
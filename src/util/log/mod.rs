@@ -5,7 +5,14 @@ pub mod flag;
 pub use self::flag::Flags;
 
 use crate::ffi::*;
-use std::convert::TryInto;
+use libc::{c_char, c_int, c_void};
+use std::{
+    cell::{Cell, RefCell},
+    convert::TryInto,
+    ffi::{CStr, CString},
+    mem,
+    sync::Mutex,
+};
 
 pub fn set_level(value: Level) {
     unsafe { av_log_set_level(value.into()) }
@@ -22,3 +29,107 @@ pub fn set_flags(value: Flags) {
 pub fn get_flags() -> Flags {
     unsafe { Flags::from_bits_truncate(av_log_get_flags()) }
 }
+
+// Threads running `capture()`; the capturing callback is installed while there
+// are any.
+static CAPTURES: Mutex<usize> = Mutex::new(0);
+
+// Messages captured on a thread.
+struct Capture {
+    level: c_int,
+    lines: Vec<String>,
+    // Start of a line logged in several pieces.
+    partial: String,
+}
+
+thread_local! {
+    // `None` outside `capture()`.
+    static CAPTURED: RefCell<Option<Capture>> = const { RefCell::new(None) };
+    // Set while a captured message is handed on to the default callback.
+    static FORWARDING: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Runs `f` and returns the lines FFmpeg logged on this thread meanwhile, at
+/// `level` or more severe, whatever the log level. Messages are still printed as
+/// usual.
+///
+/// FFmpeg has a single, process wide log callback: it is replaced while `f` runs
+/// and reset to the default one afterwards, dropping a callback set through
+/// `av_log_set_callback`.
+pub(crate) fn capture<T, F: FnOnce() -> T>(level: Level, f: F) -> (T, Vec<String>) {
+    {
+        let mut captures = CAPTURES.lock().unwrap();
+
+        if *captures == 0 {
+            unsafe { av_log_set_callback(Some(capture_callback)) }
+        }
+
+        *captures += 1;
+    }
+
+    let outer = CAPTURED.with(|captured| captured.replace(Some(Capture { level: level.into(), lines: Vec::new(), partial: String::new() })));
+    let result = f();
+    let capture = CAPTURED.with(|captured| captured.replace(outer)).unwrap();
+
+    {
+        let mut captures = CAPTURES.lock().unwrap();
+        *captures -= 1;
+
+        if *captures == 0 {
+            unsafe { av_log_set_callback(Some(av_log_default_callback)) }
+        }
+    }
+
+    let mut lines = capture.lines;
+
+    if !capture.partial.trim().is_empty() {
+        lines.push(capture.partial.trim().to_string());
+    }
+
+    (result, lines)
+}
+
+/// Like [`capture()`], returning the last error FFmpeg logged.
+pub(crate) fn capture_error<T, F: FnOnce() -> T>(f: F) -> (T, Option<String>) {
+    let (result, mut lines) = capture(Level::Error, f);
+
+    (result, lines.pop())
+}
+
+// Generic over the `va_list` type, which bindgen translates differently on each
+// platform; `V` is always the type the FFmpeg functions take.
+unsafe extern "C" fn capture_callback<V>(avcl: *mut c_void, level: c_int, fmt: *const c_char, vl: V) {
+    unsafe {
+        let capturing = CAPTURED.with(|captured| captured.borrow().as_ref().is_some_and(|capture| (level & 0xff) <= capture.level));
+
+        if !capturing || FORWARDING.get() {
+            av_log_default_callback(avcl, level, fmt, mem::transmute_copy(&vl));
+            return;
+        }
+
+        // A `va_list` can only be read once: format the message, then log it
+        // again, as a plain string, for the default callback to print.
+        let mut line = [0 as c_char; 1024];
+        let mut print_prefix = 0;
+        av_log_format_line2(avcl, level, fmt, mem::transmute_copy(&vl), line.as_mut_ptr(), line.len() as c_int, &mut print_prefix);
+
+        CAPTURED.with(|captured| {
+            if let Some(capture) = captured.borrow_mut().as_mut() {
+                capture.partial.push_str(&CStr::from_ptr(line.as_ptr()).to_string_lossy());
+
+                if capture.partial.ends_with('\n') {
+                    let message = capture.partial.trim().to_string();
+                    capture.partial.clear();
+
+                    if !message.is_empty() {
+                        capture.lines.push(message);
+                    }
+                }
+            }
+        });
+
+        FORWARDING.set(true);
+        av_log(avcl, level, CString::new("%s").unwrap().as_ptr(), line.as_ptr());
+        FORWARDING.set(false);
+    }
+}
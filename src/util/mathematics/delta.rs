@@ -0,0 +1,89 @@
+use crate::{Rational, ffi::*};
+
+/// Stateful rescaler for consecutive timestamps (`av_rescale_delta`).
+///
+/// Rescaling each timestamp on its own rounds it independently, so timestamps
+/// converted from a coarse time base (e.g. milliseconds in FLV or Matroska) to a
+/// sample-accurate one jitter around the true positions and their differences
+/// stop matching the frame durations. `RescaleDelta` instead predicts every
+/// timestamp from the previous one plus its duration, expressed in
+/// `duration_base` (usually `1/sample_rate`), and only resynchronizes with the
+/// input when the prediction leaves the rounding interval of the input timestamp,
+/// e.g. after a gap.
+///
+/// When the source time base is at least as fine as the destination one the
+/// timestamps are rescaled as usual.
+///
+/// ```ignore
+/// let mut rescaler = RescaleDelta::new(stream.time_base(), (1, 44100), (1, 44100));
+///
+/// for mut frame in frames {
+///     let pts = rescaler.rescale(frame.pts().unwrap(), frame.samples() as i32);
+///     frame.set_pts(Some(pts));
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct RescaleDelta {
+    source: Rational,
+    duration_base: Rational,
+    destination: Rational,
+    last: i64,
+}
+
+impl RescaleDelta {
+    pub fn new<S, F, D>(source: S, duration_base: F, destination: D) -> Self
+    where
+        S: Into<Rational>,
+        F: Into<Rational>,
+        D: Into<Rational>,
+    {
+        RescaleDelta { source: source.into(), duration_base: duration_base.into(), destination: destination.into(), last: AV_NOPTS_VALUE }
+    }
+
+    /// Rescales `ts` from the source to the destination time base, given the
+    /// `duration` of what it stamps in the duration time base.
+    pub fn rescale(&mut self, ts: i64, duration: i32) -> i64 {
+        unsafe { av_rescale_delta(self.source.into(), ts, self.duration_base.into(), duration, &mut self.last, self.destination.into()) }
+    }
+
+    /// Forgets the previous timestamp, e.g. after a seek; the next one is
+    /// rescaled without prediction.
+    pub fn reset(&mut self) {
+        self.last = AV_NOPTS_VALUE;
+    }
+
+    pub fn source(&self) -> Rational {
+        self.source
+    }
+
+    pub fn duration_base(&self) -> Rational {
+        self.duration_base
+    }
+
+    pub fn destination(&self) -> Rational {
+        self.destination
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Rescale;
+
+    #[test]
+    fn no_drift() {
+        let samples = 1024;
+        let mut rescaler = RescaleDelta::new((1, 1000), (1, 44100), (1, 44100));
+        let mut jitter = false;
+
+        for i in 0..10_000i64 {
+            let expected = i * samples;
+            let ts = expected.rescale((1, 44100), (1, 1000));
+
+            assert_eq!(rescaler.rescale(ts, samples as i32), expected);
+            jitter |= ts.rescale((1, 1000), (1, 44100)) != expected;
+        }
+
+        assert!(jitter);
+    }
+}
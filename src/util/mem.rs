@@ -0,0 +1,110 @@
+//! Memory allocated through FFmpeg's allocator.
+
+use std::{
+    ops::{Deref, DerefMut},
+    slice,
+};
+
+use crate::ffi::*;
+
+/// Size of the zeroed padding that codec input buffers must carry after their data.
+///
+/// Optimized bitstream readers may read (but never use) up to this many bytes past the
+/// end of the input.
+#[cfg(feature = "codec")]
+pub const INPUT_BUFFER_PADDING_SIZE: usize = AV_INPUT_BUFFER_PADDING_SIZE as usize;
+
+/// A zero-initialized byte buffer allocated with `av_malloc`.
+///
+/// The memory is aligned for the widest SIMD instructions FFmpeg was built with (up to 64
+/// bytes for AVX-512), so it can be handed to hand-written SIMD code or used as frame data.
+/// It is released with `av_free` on drop.
+pub struct AlignedBuffer {
+    ptr: *mut u8,
+    len: usize,
+}
+
+unsafe impl Send for AlignedBuffer {}
+unsafe impl Sync for AlignedBuffer {}
+
+impl AlignedBuffer {
+    /// Allocates `len` zeroed bytes.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the allocation fails.
+    pub fn new(len: usize) -> Self {
+        unsafe { Self::alloc(len, len) }
+    }
+
+    /// Allocates `len` zeroed bytes followed by [`INPUT_BUFFER_PADDING_SIZE`] zeroed
+    /// padding bytes, as required for buffers passed to decoders and parsers.
+    ///
+    /// The padding is not part of the slice.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the allocation fails.
+    #[cfg(feature = "codec")]
+    pub fn padded(len: usize) -> Self {
+        unsafe { Self::alloc(len, len + INPUT_BUFFER_PADDING_SIZE) }
+    }
+
+    unsafe fn alloc(len: usize, size: usize) -> Self {
+        unsafe {
+            let ptr = av_mallocz(size) as *mut u8;
+
+            if ptr.is_null() {
+                panic!("out of memory");
+            }
+
+            AlignedBuffer { ptr, len }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn as_ptr(&self) -> *const u8 {
+        self.ptr
+    }
+
+    pub fn as_mut_ptr(&mut self) -> *mut u8 {
+        self.ptr
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        unsafe { slice::from_raw_parts(self.ptr, self.len) }
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [u8] {
+        unsafe { slice::from_raw_parts_mut(self.ptr, self.len) }
+    }
+}
+
+impl Deref for AlignedBuffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl DerefMut for AlignedBuffer {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        self.as_mut_slice()
+    }
+}
+
+impl Drop for AlignedBuffer {
+    fn drop(&mut self) {
+        unsafe {
+            av_free(self.ptr as *mut _);
+        }
+    }
+}
@@ -15,22 +15,37 @@
 //! - [`mathematics`] - Mathematical utilities (rescaling, rounding)
 //! - [`time`] - Time representation and conversion
 //! - [`mod@log`] - Logging configuration and levels
+//! - [`hwaccel`] - Hardware acceleration device types
+//! - [`mem`] - Buffers allocated with FFmpeg's aligned allocator
+//! - [`image`] - Stride-aware copies and other raw image operations
+//! - [`bits`] - Bit reader and writer for parsing codec headers
+//! - [`samples`] - Sample format conversion without resampling
+//! - [`progress`] - Progress and speed reporting for long transcodes
+//! - `fifo` - Typed element FIFO (FFmpeg 5.1+)
 
 #[macro_use]
 pub mod dictionary;
+pub mod bits;
 pub mod chroma;
 pub mod color;
 pub mod error;
+#[cfg(feature = "ffmpeg_5_1")]
+pub mod fifo;
 pub mod format;
 pub mod frame;
+pub mod hwaccel;
+pub mod image;
 pub mod interrupt;
 pub mod log;
 pub mod mathematics;
 pub mod media;
+pub mod mem;
 pub mod option;
 pub mod picture;
+pub mod progress;
 pub mod range;
 pub mod rational;
+pub mod samples;
 pub mod time;
 
 #[cfg_attr(feature = "ffmpeg_7_0", path = "channel_layout.rs")]
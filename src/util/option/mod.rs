@@ -1,6 +1,9 @@
 mod traits;
 pub use self::traits::{Gettable, Iterable, Settable, Target};
 
+mod serialize;
+pub use self::serialize::{SerializeFlags, apply, serialize};
+
 use crate::ffi::{AVOptionType::*, *};
 
 #[derive(PartialEq, Eq, Copy, Clone, Debug)]
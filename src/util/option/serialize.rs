@@ -0,0 +1,64 @@
+use std::{
+    ffi::{CStr, CString},
+    ptr,
+};
+
+use super::{Settable, Target};
+use crate::{Error, ffi::*};
+use libc::{c_char, c_int};
+
+bitflags! {
+    #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+    pub struct SerializeFlags: c_int {
+        /// Leave out options still at their default value.
+        const SKIP_DEFAULTS    = AV_OPT_SERIALIZE_SKIP_DEFAULTS;
+        /// Include the options of child objects, e.g. the private options
+        /// (`preset`, `crf`, ...) of the codec of a codec context.
+        #[cfg(feature = "ffmpeg_7_1")]
+        const SEARCH_CHILDREN  = AV_OPT_SERIALIZE_SEARCH_CHILDREN;
+    }
+}
+
+/// Dumps the options of `target` as a `key=value:key=value` string
+/// (`av_opt_serialize`), to be logged and later restored with [`apply()`].
+///
+/// With [`SerializeFlags::SKIP_DEFAULTS`] only the options that were changed
+/// appear, which keeps the dump of a codec context to a few entries instead of
+/// its hundreds of options. Separators inside values are escaped with a
+/// backslash. Read-only options are never included.
+pub fn serialize<T: Target>(target: &T, flags: SerializeFlags) -> Result<String, Error> {
+    unsafe {
+        let mut buffer: *mut c_char = ptr::null_mut();
+
+        match av_opt_serialize(target.as_ptr() as *mut _, 0, flags.bits(), &mut buffer, b'=' as c_char, b':' as c_char) {
+            e if e < 0 => Err(Error::from(e)),
+
+            _ => {
+                let result = CStr::from_ptr(buffer).to_string_lossy().into_owned();
+                av_free(buffer as *mut _);
+
+                Ok(result)
+            }
+        }
+    }
+}
+
+/// Sets the options listed in a string produced by [`serialize()`]
+/// (`av_set_options_string`), searching child objects as well, and returns how
+/// many were set.
+///
+/// Options are set in order and the first failure stops the parsing, leaving
+/// the earlier ones applied; an unknown key fails with
+/// [`Error::OptionNotFound`].
+pub fn apply<T: Settable>(target: &mut T, options: &str) -> Result<usize, Error> {
+    unsafe {
+        let options = CString::new(options).unwrap();
+        let separator = CString::new("=").unwrap();
+        let pairs = CString::new(":").unwrap();
+
+        match av_set_options_string(target.as_mut_ptr(), options.as_ptr(), separator.as_ptr(), pairs.as_ptr()) {
+            e if e < 0 => Err(Error::from(e)),
+            count => Ok(count as usize),
+        }
+    }
+}
@@ -28,6 +28,20 @@ pub trait Settable: Target {
         }
     }
 
+    /// Sets a binary option (`AV_OPT_TYPE_BINARY`) from raw bytes.
+    ///
+    /// Unlike [`set()`](Self::set), which passes the in-memory representation of a
+    /// value, this copies exactly `value.len()` bytes. Fails with
+    /// [`Error::OptionNotFound`] when no option called `name` exists.
+    fn set_bin(&mut self, name: &str, value: &[u8]) -> Result<(), Error> {
+        unsafe {
+            let name = CString::new(name).unwrap();
+            let len = c_int::try_from(value.len()).map_err(|_| Error::from(AVERROR(libc::EINVAL)))?;
+
+            check!(av_opt_set_bin(self.as_mut_ptr(), name.as_ptr(), value.as_ptr(), len, AV_OPT_SEARCH_CHILDREN))
+        }
+    }
+
     fn set_str(&mut self, name: &str, value: &str) -> Result<(), Error> {
         unsafe {
             let name = CString::new(name).unwrap();
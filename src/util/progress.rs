@@ -0,0 +1,167 @@
+use std::time::{Duration, Instant};
+
+use crate::{Rational, Rescale, rescale::TIME_BASE};
+
+/// Snapshot of a transcode passed to the callback of a [`ProgressTracker`].
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub struct Progress {
+    /// Number of frames processed so far.
+    pub frame: u64,
+    /// Timestamp of the last frame, in `AV_TIME_BASE` units (microseconds).
+    pub timestamp: i64,
+    /// Wall-clock time since the first frame was recorded.
+    pub elapsed: Duration,
+    /// Media time processed per second of wall-clock time: 2.0 means twice as
+    /// fast as real time. 0 until some time has passed.
+    pub speed: f64,
+    /// Share of the [duration](ProgressTracker::set_duration) done, between 0
+    /// and 1, if the duration is known.
+    pub fraction: Option<f64>,
+}
+
+/// How often a [`ProgressTracker`] reports.
+#[derive(Eq, PartialEq, Clone, Copy, Debug)]
+pub enum Interval {
+    /// Every that many frames.
+    Frames(u64),
+    /// At most once per that much wall-clock time.
+    Time(Duration),
+}
+
+/// Reports the progress of a transcode to a callback, e.g. to show
+/// "2.3x, 45% done".
+///
+/// Call [`frame()`](Self::frame) for every frame processed (decoded, filtered
+/// or encoded, as long as it is always the same stage); the callback is invoked
+/// at the configured [`Interval`], which keeps the cost negligible even for
+/// short audio frames. The speed compares the media time covered since the
+/// first frame with the wall-clock time spent.
+///
+/// ```ignore
+/// let mut tracker = ProgressTracker::new(Interval::Time(Duration::from_millis(500)));
+/// tracker.set_duration(input.duration());
+/// tracker.set_progress_callback(|progress| {
+///     println!("{:.1}x, {:.0}% done", progress.speed, progress.fraction.unwrap_or(0.0) * 100.0);
+/// });
+///
+/// while decoder.receive_frame(&mut frame).is_ok() {
+///     tracker.frame(frame.pts().unwrap_or(0), stream.time_base());
+///     // ...
+/// }
+///
+/// tracker.finish();
+/// ```
+pub struct ProgressTracker {
+    interval: Interval,
+    callback: Option<Box<dyn FnMut(Progress) + Send>>,
+    duration: Option<i64>,
+
+    start: Option<(Instant, i64)>,
+    reported: Option<Instant>,
+    frames: u64,
+    timestamp: i64,
+}
+
+impl ProgressTracker {
+    pub fn new(interval: Interval) -> Self {
+        ProgressTracker { interval, callback: None, duration: None, start: None, reported: None, frames: 0, timestamp: 0 }
+    }
+
+    /// Sets the function called with the progress.
+    pub fn set_progress_callback<F>(&mut self, callback: F)
+    where
+        F: FnMut(Progress) + Send + 'static,
+    {
+        self.callback = Some(Box::new(callback));
+    }
+
+    /// Sets the total duration, in `AV_TIME_BASE` units like
+    /// `Input::duration()`, to compute [`Progress::fraction`]. It is measured
+    /// from the first timestamp recorded.
+    pub fn set_duration(&mut self, duration: i64) {
+        self.duration = if duration > 0 { Some(duration) } else { None };
+    }
+
+    /// Records a processed frame with timestamp `timestamp` in `time_base`, and
+    /// calls the callback if the interval has elapsed.
+    pub fn frame<R: Into<Rational>>(&mut self, timestamp: i64, time_base: R) {
+        let now = Instant::now();
+
+        self.frames += 1;
+        self.timestamp = timestamp.rescale(time_base, TIME_BASE);
+
+        if self.start.is_none() {
+            self.start = Some((now, self.timestamp));
+            self.reported = Some(now);
+        }
+
+        let due = match self.interval {
+            Interval::Frames(frames) => self.frames % frames.max(1) == 0,
+            Interval::Time(interval) => self.reported.is_some_and(|reported| now.duration_since(reported) >= interval),
+        };
+
+        if due {
+            self.report(now);
+        }
+    }
+
+    /// Calls the callback with the final progress, whatever the interval.
+    pub fn finish(&mut self) {
+        self.report(Instant::now());
+    }
+
+    /// The progress as of now.
+    pub fn progress(&self) -> Progress {
+        self.snapshot(Instant::now())
+    }
+
+    fn report(&mut self, now: Instant) {
+        self.reported = Some(now);
+
+        let progress = self.snapshot(now);
+
+        if let Some(callback) = self.callback.as_mut() {
+            callback(progress);
+        }
+    }
+
+    fn snapshot(&self, now: Instant) -> Progress {
+        let (elapsed, covered) = match self.start {
+            Some((start, first)) => (now.duration_since(start), self.timestamp.saturating_sub(first)),
+            None => (Duration::ZERO, 0),
+        };
+
+        let speed = if elapsed.is_zero() { 0.0 } else { covered as f64 / 1_000_000.0 / elapsed.as_secs_f64() };
+        let fraction = self.duration.map(|duration| (covered as f64 / duration as f64).clamp(0.0, 1.0));
+
+        Progress { frame: self.frames, timestamp: self.timestamp, elapsed, speed, fraction }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+
+    #[test]
+    fn test_frame_interval() {
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let sink = reports.clone();
+
+        let mut tracker = ProgressTracker::new(Interval::Frames(10));
+        tracker.set_duration(4_000_000);
+        tracker.set_progress_callback(move |progress| sink.lock().unwrap().push(progress));
+
+        for frame in 0..25 {
+            tracker.frame(frame * 40, (1, 1000));
+        }
+
+        tracker.finish();
+
+        let reports = reports.lock().unwrap();
+        assert_eq!(reports.iter().map(|progress| progress.frame).collect::<Vec<_>>(), vec![10, 20, 25]);
+        assert_eq!(reports[2].timestamp, 960_000);
+        assert_eq!(reports[2].fraction, Some(0.24));
+    }
+}
@@ -46,6 +46,20 @@ impl Rational {
         }
     }
 
+    /// Reduces the fraction with `av_reduce`, e.g. `30000/1000` to `30/1`.
+    ///
+    /// Both terms of the result are bounded by `max_denominator`; the flag tells
+    /// whether the result is exactly equal to `self` or only the closest
+    /// approximation within the bound. Same as [`reduce_with_limit()`](Self::reduce_with_limit)
+    /// with both outcomes folded into a tuple.
+    #[inline]
+    pub fn reduced(&self, max_denominator: i32) -> (Rational, bool) {
+        match self.reduce_with_limit(max_denominator) {
+            Ok(r) => (r, true),
+            Err(r) => (r, false),
+        }
+    }
+
     #[inline]
     pub fn invert(&self) -> Rational {
         unsafe { Rational::from(av_inv_q((*self).into())) }
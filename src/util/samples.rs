@@ -0,0 +1,203 @@
+//! Sample format conversion at a fixed rate and channel layout.
+//!
+//! These functions change how samples are stored (`s16` to `flt`, planar to
+//! packed) without the set-up cost of a resampler. The arithmetic matches
+//! `libswresample`: integers are scaled to the `[-1.0, 1.0)` range of float
+//! formats, floats are rounded half to even (like `lrint`) and clipped back, and
+//! integer conversions shift the values. When the sample rate or channel layout has to change as well, use a
+//! [`software::resampling::Context`](crate::software::resampling::Context).
+
+use std::slice;
+
+use crate::{Error, ffi::*, format, frame};
+
+/// Converts `source` to `format`, keeping its rate, channel layout, timestamps and
+/// other properties.
+///
+/// Fails with `EINVAL` for frames without samples or format and for
+/// `format::Sample::None`.
+pub fn convert_format(source: &frame::Audio, format: format::Sample) -> Result<frame::Audio, Error> {
+    let from = source.format();
+
+    if from == format::Sample::None || format == format::Sample::None || source.samples() == 0 || unsafe { source.is_empty() } {
+        return Err(Error::Other { errno: libc::EINVAL });
+    }
+
+    let channels = source.channels() as usize;
+    let samples = source.samples();
+    let mut destination = frame::Audio::new(format, samples, source.channel_layout());
+
+    unsafe {
+        let res = av_frame_copy_props(destination.as_mut_ptr(), source.as_ptr());
+
+        if res < 0 {
+            return Err(Error::from(res));
+        }
+    }
+
+    destination.set_rate(source.rate());
+
+    let input = planes(source);
+    let mut output = planes_mut(&mut destination);
+
+    for channel in 0..channels {
+        for sample in 0..samples {
+            let (plane, index) = if from.is_planar() { (channel, sample) } else { (0, sample * channels + channel) };
+            let value = read(input[plane], from, index);
+
+            let (plane, index) = if format.is_planar() { (channel, sample) } else { (0, sample * channels + channel) };
+            write(output[plane], format, index, value);
+        }
+    }
+
+    Ok(destination)
+}
+
+/// Converts a planar frame to the packed variant of its format, or a packed frame
+/// to the planar one. The sample values are copied unchanged.
+pub fn repack(source: &frame::Audio) -> Result<frame::Audio, Error> {
+    let format = source.format();
+
+    convert_format(source, if format.is_planar() { format.packed() } else { format.planar() })
+}
+
+// A sample in a format-independent form: integers left-aligned to 64 bits, so
+// integer conversions reduce to shifts, or a float.
+#[derive(Clone, Copy)]
+enum Value {
+    Int(i64),
+    Float(f64),
+}
+
+fn planes(frame: &frame::Audio) -> Vec<&[u8]> {
+    let length = plane_length(frame);
+
+    (0..frame.planes()).map(|index| unsafe { slice::from_raw_parts(*(*frame.as_ptr()).extended_data.add(index), length) }).collect()
+}
+
+fn planes_mut(frame: &mut frame::Audio) -> Vec<&mut [u8]> {
+    let length = plane_length(frame);
+
+    (0..frame.planes()).map(|index| unsafe { slice::from_raw_parts_mut(*(*frame.as_mut_ptr()).extended_data.add(index), length) }).collect()
+}
+
+fn plane_length(frame: &frame::Audio) -> usize {
+    let interleaved = if frame.is_packed() { frame.channels() as usize } else { 1 };
+
+    frame.samples() * interleaved * frame.format().bytes()
+}
+
+fn read(data: &[u8], format: format::Sample, index: usize) -> Value {
+    let bytes = format.bytes();
+    let raw = &data[index * bytes..(index + 1) * bytes];
+
+    match format {
+        format::Sample::U8(..) => Value::Int((i64::from(raw[0]) - 0x80) << 56),
+        format::Sample::I16(..) => Value::Int(i64::from(i16::from_ne_bytes(raw.try_into().unwrap())) << 48),
+        format::Sample::I32(..) => Value::Int(i64::from(i32::from_ne_bytes(raw.try_into().unwrap())) << 32),
+        format::Sample::I64(..) => Value::Int(i64::from_ne_bytes(raw.try_into().unwrap())),
+        format::Sample::F32(..) => Value::Float(f64::from(f32::from_ne_bytes(raw.try_into().unwrap()))),
+        format::Sample::F64(..) => Value::Float(f64::from_ne_bytes(raw.try_into().unwrap())),
+        format::Sample::None => unreachable!(),
+    }
+}
+
+fn write(data: &mut [u8], format: format::Sample, index: usize, value: Value) {
+    let bytes = format.bytes();
+    let raw = &mut data[index * bytes..(index + 1) * bytes];
+
+    match (format, value) {
+        (format::Sample::U8(..), Value::Int(value)) => raw[0] = ((value >> 56) + 0x80) as u8,
+        (format::Sample::U8(..), Value::Float(value)) => raw[0] = ((value * 128.0).round_ties_even() + 128.0).clamp(0.0, 255.0) as u8,
+        (format::Sample::I16(..), Value::Int(value)) => raw.copy_from_slice(&((value >> 48) as i16).to_ne_bytes()),
+        (format::Sample::I16(..), Value::Float(value)) => raw.copy_from_slice(&((value * 32768.0).round_ties_even().clamp(i16::MIN as f64, i16::MAX as f64) as i16).to_ne_bytes()),
+        (format::Sample::I32(..), Value::Int(value)) => raw.copy_from_slice(&((value >> 32) as i32).to_ne_bytes()),
+        (format::Sample::I32(..), Value::Float(value)) => raw.copy_from_slice(&((value * 2147483648.0).round_ties_even().clamp(i32::MIN as f64, i32::MAX as f64) as i32).to_ne_bytes()),
+        (format::Sample::I64(..), Value::Int(value)) => raw.copy_from_slice(&value.to_ne_bytes()),
+        (format::Sample::I64(..), Value::Float(value)) => raw.copy_from_slice(&((value * 9223372036854775808.0).round_ties_even() as i64).to_ne_bytes()),
+        (format::Sample::F32(..), Value::Int(value)) => raw.copy_from_slice(&((value as f64 / 9223372036854775808.0) as f32).to_ne_bytes()),
+        (format::Sample::F32(..), Value::Float(value)) => raw.copy_from_slice(&(value as f32).to_ne_bytes()),
+        (format::Sample::F64(..), Value::Int(value)) => raw.copy_from_slice(&(value as f64 / 9223372036854775808.0).to_ne_bytes()),
+        (format::Sample::F64(..), Value::Float(value)) => raw.copy_from_slice(&value.to_ne_bytes()),
+        (format::Sample::None, _) => unreachable!(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ptr;
+
+    use super::*;
+    use crate::ChannelLayout;
+
+    const PACKED: format::sample::Type = format::sample::Type::Packed;
+    const PLANAR: format::sample::Type = format::sample::Type::Planar;
+
+    // A frame holding `planes`, interleaved when `format` is packed.
+    fn audio<T: Copy>(format: format::Sample, layout: ChannelLayout, planes: &[&[T]]) -> frame::Audio {
+        let samples = if format.is_planar() { planes[0].len() } else { planes[0].len() / layout.channels() as usize };
+        let mut frame = frame::Audio::new(format, samples, layout);
+
+        for (index, plane) in planes.iter().enumerate() {
+            unsafe { ptr::copy_nonoverlapping(plane.as_ptr(), (*frame.as_mut_ptr()).data[index] as *mut T, plane.len()) }
+        }
+
+        frame
+    }
+
+    fn values<T: Copy>(frame: &frame::Audio, plane: usize) -> Vec<T> {
+        let count = if frame.is_planar() { frame.samples() } else { frame.samples() * frame.channels() as usize };
+
+        unsafe { slice::from_raw_parts((*frame.as_ptr()).data[plane] as *const T, count).to_vec() }
+    }
+
+    #[test]
+    fn test_u8_bias() {
+        let source = audio::<u8>(format::Sample::U8(PACKED), ChannelLayout::MONO, &[&[0, 128, 255]]);
+
+        assert_eq!(values::<i16>(&convert_format(&source, format::Sample::I16(PACKED)).unwrap(), 0), [-32768, 0, 32512]);
+        assert_eq!(values::<f32>(&convert_format(&source, format::Sample::F32(PACKED)).unwrap(), 0), [-1.0, 0.0, 127.0 / 128.0]);
+
+        let silence = audio::<f32>(format::Sample::F32(PACKED), ChannelLayout::MONO, &[&[0.0]]);
+        assert_eq!(values::<u8>(&convert_format(&silence, format::Sample::U8(PACKED)).unwrap(), 0), [128]);
+    }
+
+    #[test]
+    fn test_float_clipping() {
+        let source = audio::<f32>(format::Sample::F32(PACKED), ChannelLayout::MONO, &[&[1.5, -1.5, 1.0, -1.0]]);
+
+        assert_eq!(values::<u8>(&convert_format(&source, format::Sample::U8(PACKED)).unwrap(), 0), [255, 0, 255, 0]);
+        assert_eq!(values::<i16>(&convert_format(&source, format::Sample::I16(PACKED)).unwrap(), 0), [i16::MAX, i16::MIN, i16::MAX, i16::MIN]);
+        assert_eq!(values::<i32>(&convert_format(&source, format::Sample::I32(PACKED)).unwrap(), 0), [i32::MAX, i32::MIN, i32::MAX, i32::MIN]);
+    }
+
+    #[test]
+    fn test_round_half_even() {
+        let source = audio::<f32>(format::Sample::F32(PACKED), ChannelLayout::MONO, &[&[0.5 / 32768.0, 1.5 / 32768.0, 2.5 / 32768.0, -0.5 / 32768.0]]);
+
+        assert_eq!(values::<i16>(&convert_format(&source, format::Sample::I16(PACKED)).unwrap(), 0), [0, 2, 2, 0]);
+    }
+
+    #[test]
+    fn test_s16_flt_round_trip() {
+        let samples = (i16::MIN..=i16::MAX).collect::<Vec<_>>();
+        let source = audio(format::Sample::I16(PACKED), ChannelLayout::MONO, &[&samples]);
+
+        let float = convert_format(&source, format::Sample::F32(PLANAR)).unwrap();
+        assert_eq!(values::<i16>(&convert_format(&float, format::Sample::I16(PACKED)).unwrap(), 0), samples);
+    }
+
+    #[test]
+    fn test_repack() {
+        let packed = audio::<i16>(format::Sample::I16(PACKED), ChannelLayout::STEREO, &[&[1, -1, 2, -2, 3, -3]]);
+
+        let planar = repack(&packed).unwrap();
+        assert_eq!(planar.format(), format::Sample::I16(PLANAR));
+        assert_eq!(values::<i16>(&planar, 0), [1, 2, 3]);
+        assert_eq!(values::<i16>(&planar, 1), [-1, -2, -3]);
+
+        let repacked = repack(&planar).unwrap();
+        assert_eq!(repacked.format(), format::Sample::I16(PACKED));
+        assert_eq!(values::<i16>(&repacked, 0), [1, -1, 2, -2, 3, -3]);
+    }
+}
@@ -1,20 +1,36 @@
 use crate::{Error, ffi::*};
 
+/// Current wall-clock time in microseconds since the Unix epoch (`av_gettime`).
+///
+/// Suitable for stamping captured frames; it can jump when the system clock is
+/// adjusted, so use [`relative()`] to measure intervals.
 #[inline(always)]
 pub fn current() -> i64 {
     unsafe { av_gettime() }
 }
 
+/// Time in microseconds from an unspecified starting point (`av_gettime_relative`).
+///
+/// Only differences between two values are meaningful. The clock is monotonic when
+/// [`is_monotonic()`] returns `true`.
 #[inline(always)]
 pub fn relative() -> i64 {
     unsafe { av_gettime_relative() }
 }
 
+/// Same as [`relative()`].
+#[inline(always)]
+pub fn current_relative() -> i64 {
+    relative()
+}
+
+/// Whether [`relative()`] is backed by a monotonic clock.
 #[inline(always)]
 pub fn is_monotonic() -> bool {
     unsafe { av_gettime_relative_is_monotonic() != 0 }
 }
 
+/// Sleeps for at least `usec` microseconds (`av_usleep`).
 #[inline(always)]
 pub fn sleep(usec: u32) -> Result<(), Error> {
     unsafe {